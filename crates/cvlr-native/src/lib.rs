@@ -0,0 +1,128 @@
+//! Native stand-in for the parts of `cvlr` and `cvlr_solana` used by the
+//! vault specs
+//!
+//! The program aliases this crate as both `cvlr` and `cvlr_solana` in its
+//! unit tests, so the `CvlrProp`s and base harnesses of `certora::specs`
+//! compile unchanged. Rules then run as property tests (see `check_rule`):
+//!
+//! - `nondet()` reads the next value of a tape generated by `proptest`;
+//!   shrinking the tape shrinks the counterexample
+//! - `cvlr_assume!` discards the case
+//! - `cvlr_assert*!` fail the case with the values involved
+//! - any other panic (e.g. `unwrap` of a failed operation) discards the case,
+//!   like an aborted path under the prover
+//!
+//! `clog!` records a trace that is printed with a failing case.
+pub mod log;
+pub mod mathint;
+pub mod nondet;
+pub mod pubkey;
+pub mod runner;
+pub mod token;
+
+pub use nondet::{cvlr_nondet_pubkey, nondet};
+pub use runner::{check_rule, run_rule, RuleOutcome};
+
+pub mod prelude {
+    pub use crate::{
+        clog, cvlr_assert, cvlr_assert_eq, cvlr_assert_ge, cvlr_assert_gt, cvlr_assert_le,
+        cvlr_assert_lt, cvlr_assert_ne, cvlr_assume, nondet::nondet,
+    };
+}
+
+#[macro_export]
+macro_rules! cvlr_assume {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::runner::reject(stringify!($cond));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! cvlr_assert {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::runner::violation(format!("assertion failed: {}", stringify!($cond)));
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cvlr_assert_cmp {
+    ($lhs:expr, $rhs:expr, $op:tt) => {
+        match (&$lhs, &$rhs) {
+            (lhs, rhs) => {
+                if !(*lhs $op *rhs) {
+                    $crate::runner::violation(format!(
+                        "assertion failed: {} {} {} ({:?} vs {:?})",
+                        stringify!($lhs),
+                        stringify!($op),
+                        stringify!($rhs),
+                        lhs,
+                        rhs
+                    ));
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! cvlr_assert_eq {
+    ($lhs:expr, $rhs:expr $(,)?) => {
+        $crate::cvlr_assert_cmp!($lhs, $rhs, ==)
+    };
+}
+
+#[macro_export]
+macro_rules! cvlr_assert_ne {
+    ($lhs:expr, $rhs:expr $(,)?) => {
+        $crate::cvlr_assert_cmp!($lhs, $rhs, !=)
+    };
+}
+
+#[macro_export]
+macro_rules! cvlr_assert_le {
+    ($lhs:expr, $rhs:expr $(,)?) => {
+        $crate::cvlr_assert_cmp!($lhs, $rhs, <=)
+    };
+}
+
+#[macro_export]
+macro_rules! cvlr_assert_lt {
+    ($lhs:expr, $rhs:expr $(,)?) => {
+        $crate::cvlr_assert_cmp!($lhs, $rhs, <)
+    };
+}
+
+#[macro_export]
+macro_rules! cvlr_assert_ge {
+    ($lhs:expr, $rhs:expr $(,)?) => {
+        $crate::cvlr_assert_cmp!($lhs, $rhs, >=)
+    };
+}
+
+#[macro_export]
+macro_rules! cvlr_assert_gt {
+    ($lhs:expr, $rhs:expr $(,)?) => {
+        $crate::cvlr_assert_cmp!($lhs, $rhs, >)
+    };
+}
+
+/// Record values in the trace of the current case
+///
+/// Accepts `clog!(a, b)`, `clog!("message")` and `clog!(value => "tag")`.
+#[macro_export]
+macro_rules! clog {
+    () => {};
+    ($value:expr => $tag:expr $(, $($rest:tt)*)?) => {{
+        $crate::log::cvlr_log($tag, &$value);
+        $($crate::clog!($($rest)*);)?
+    }};
+    ($value:expr $(, $($rest:tt)*)?) => {{
+        $crate::log::cvlr_log(stringify!($value), &$value);
+        $($crate::clog!($($rest)*);)?
+    }};
+}
@@ -0,0 +1,91 @@
+//! Trace of the current case, written by `clog!` and `CvlrLog`
+use std::cell::RefCell;
+
+use crate::mathint::NativeInt;
+
+thread_local! {
+    static TRACE: RefCell<CvlrLogger> = RefCell::new(CvlrLogger::default());
+}
+
+#[derive(Default)]
+pub struct CvlrLogger {
+    lines: Vec<String>,
+    depth: usize,
+}
+
+impl CvlrLogger {
+    pub fn log(&mut self, msg: &str) {
+        self.lines
+            .push(format!("{:indent$}{}", "", msg, indent = 2 * self.depth));
+    }
+
+    pub fn log_value(&mut self, tag: &str, value: impl std::fmt::Display) {
+        self.log(&format!("{}: {}", tag, value));
+    }
+
+    pub fn log_scope_start(&mut self, tag: &str) {
+        self.log(&format!("{} {{", tag));
+        self.depth += 1;
+    }
+
+    pub fn log_scope_end(&mut self, _tag: &str) {
+        self.depth = self.depth.saturating_sub(1);
+        self.log("}");
+    }
+}
+
+pub trait CvlrLog {
+    fn log(&self, tag: &str, logger: &mut CvlrLogger);
+}
+
+pub fn cvlr_log_with<T: CvlrLog + ?Sized>(tag: &str, value: &T, logger: &mut CvlrLogger) {
+    value.log(tag, logger);
+}
+
+/// Log `value` to the trace of the current case
+pub fn cvlr_log<T: CvlrLog + ?Sized>(tag: &str, value: &T) {
+    TRACE.with(|trace| value.log(tag, &mut trace.borrow_mut()));
+}
+
+pub(crate) fn clear_trace() {
+    TRACE.with(|trace| *trace.borrow_mut() = CvlrLogger::default());
+}
+
+pub(crate) fn take_trace() -> Vec<String> {
+    TRACE.with(|trace| std::mem::take(&mut *trace.borrow_mut()).lines)
+}
+
+macro_rules! impl_log_display {
+    ($($ty:ty),*) => {
+        $(
+            impl CvlrLog for $ty {
+                fn log(&self, tag: &str, logger: &mut CvlrLogger) {
+                    logger.log_value(tag, self);
+                }
+            }
+        )*
+    };
+}
+
+impl_log_display!(u8, u16, u32, u64, usize, i64, bool, NativeInt);
+
+impl CvlrLog for str {
+    fn log(&self, _tag: &str, logger: &mut CvlrLogger) {
+        logger.log(self);
+    }
+}
+
+impl<T: CvlrLog + ?Sized> CvlrLog for &T {
+    fn log(&self, tag: &str, logger: &mut CvlrLogger) {
+        (**self).log(tag, logger);
+    }
+}
+
+impl<T: CvlrLog> CvlrLog for Option<T> {
+    fn log(&self, tag: &str, logger: &mut CvlrLogger) {
+        match self {
+            Some(value) => value.log(tag, logger),
+            None => logger.log_value(tag, "None"),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Mathematical integers
+//!
+//! Backed by `i128`, which holds any sum and most products of two `u64`s.
+//! Arithmetic that leaves `i128` panics, which discards the case.
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Sub},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct NativeInt(i128);
+
+impl NativeInt {
+    pub fn new(value: i128) -> Self {
+        Self(value)
+    }
+
+    pub fn is_u64(&self) -> bool {
+        u64::try_from(self.0).is_ok()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+pub fn is_u64(value: impl Into<NativeInt>) -> bool {
+    value.into().is_u64()
+}
+
+impl fmt::Debug for NativeInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for NativeInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for NativeInt {
+                fn from(value: $ty) -> Self {
+                    Self(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(u8, u16, u32, u64, i64);
+
+macro_rules! impl_op {
+    ($trait:ident, $fn:ident, $checked:ident) => {
+        impl $trait for NativeInt {
+            type Output = Self;
+
+            fn $fn(self, rhs: Self) -> Self {
+                Self(self.0.$checked(rhs.0).expect(concat!(
+                    "NativeInt::",
+                    stringify!($fn),
+                    " out of range"
+                )))
+            }
+        }
+
+        impl $trait<u64> for NativeInt {
+            type Output = Self;
+
+            fn $fn(self, rhs: u64) -> Self {
+                self.$fn(Self::from(rhs))
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, checked_add);
+impl_op!(Sub, sub, checked_sub);
+impl_op!(Mul, mul, checked_mul);
+impl_op!(Div, div, checked_div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_int() {
+        let max = NativeInt::from(u64::MAX);
+        assert!(max.is_u64());
+        assert!(!(max + 1).is_u64());
+        assert_eq!(max * 2 - max, max);
+        assert!(NativeInt::from(3u64) > NativeInt::from(2u64));
+    }
+}
@@ -0,0 +1,75 @@
+//! Nondeterministic values read from the tape of the current case
+use std::cell::RefCell;
+
+use solana_program::pubkey::Pubkey;
+
+use crate::cvlr_assume;
+
+thread_local! {
+    static TAPE: RefCell<Tape> = RefCell::new(Tape::default());
+}
+
+#[derive(Default)]
+struct Tape {
+    values: Vec<u64>,
+    next: usize,
+}
+
+/// Start a case reading `values`; reads past the end return 0
+pub(crate) fn load_tape(values: Vec<u64>) {
+    TAPE.with(|tape| *tape.borrow_mut() = Tape { values, next: 0 });
+}
+
+fn next_value() -> u64 {
+    TAPE.with(|tape| {
+        let mut tape = tape.borrow_mut();
+        let value = tape.values.get(tape.next).copied().unwrap_or_default();
+        tape.next += 1;
+        value
+    })
+}
+
+pub trait Nondet: Sized {
+    fn nondet() -> Self;
+}
+
+macro_rules! impl_nondet_uint {
+    ($($ty:ty),*) => {
+        $(
+            impl Nondet for $ty {
+                fn nondet() -> Self {
+                    next_value() as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_nondet_uint!(u8, u16, u32, u64, usize);
+
+impl Nondet for bool {
+    fn nondet() -> Self {
+        next_value() & 1 == 1
+    }
+}
+
+pub fn nondet<T: Nondet>() -> T {
+    T::nondet()
+}
+
+/// Nondeterministic value satisfying `pred`
+///
+/// Tries a few values of the tape before discarding the case, so that
+/// narrow predicates do not reject most cases.
+pub fn nondet_with<T: Nondet, F: Fn(&T) -> bool>(pred: F) -> T {
+    let value = (0..8).map(|_| T::nondet()).find(|value| pred(value));
+    cvlr_assume!(value.is_some());
+    value.unwrap()
+}
+
+/// One of 256 keys, so that keys of different accounts may alias
+pub fn cvlr_nondet_pubkey() -> Pubkey {
+    let mut key = [0; 32];
+    key[0] = nondet();
+    Pubkey::new_from_array(key)
+}
@@ -0,0 +1,12 @@
+use solana_program::pubkey::Pubkey;
+
+use crate::log::{CvlrLog, CvlrLogger};
+
+/// Loggable wrapper of a `Pubkey`
+pub struct Pk<'a>(pub &'a Pubkey);
+
+impl CvlrLog for Pk<'_> {
+    fn log(&self, tag: &str, logger: &mut CvlrLogger) {
+        logger.log_value(tag, self.0);
+    }
+}
@@ -0,0 +1,138 @@
+//! Running rules as property tests
+use std::{
+    cell::Cell,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use proptest::{
+    collection::vec,
+    prelude::*,
+    test_runner::{Config, RngAlgorithm, TestCaseError, TestError, TestRng, TestRunner},
+};
+
+use crate::{log, nondet};
+
+/// Panic payload of a failed `cvlr_assume!`
+struct Rejected(&'static str);
+
+/// Panic payload of a failed `cvlr_assert*!`
+struct Violation(String);
+
+pub fn reject(cond: &'static str) -> ! {
+    panic::resume_unwind(Box::new(Rejected(cond)))
+}
+
+pub fn violation(msg: String) -> ! {
+    panic::resume_unwind(Box::new(Violation(msg)))
+}
+
+/// Result of a rule on one tape
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleOutcome {
+    Verified,
+    /// An assumption failed or the path aborted
+    Rejected(&'static str),
+    /// An assertion failed; message and trace of the case
+    Violated(String),
+}
+
+thread_local! {
+    static IN_RULE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Silence the panic hook while a rule runs: aborted paths are expected and
+/// reporting each of them dominates the run time
+fn quiet_panics_in_rules() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IN_RULE.with(Cell::get) {
+                hook(info)
+            }
+        }));
+    });
+}
+
+/// Run `rule` once with `nondet()` reading `tape`
+pub fn run_rule(tape: Vec<u64>, rule: impl FnOnce()) -> RuleOutcome {
+    quiet_panics_in_rules();
+    nondet::load_tape(tape);
+    log::clear_trace();
+    IN_RULE.with(|in_rule| in_rule.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(rule));
+    IN_RULE.with(|in_rule| in_rule.set(false));
+    let trace = log::take_trace();
+    match result {
+        Ok(()) => RuleOutcome::Verified,
+        Err(payload) => {
+            if let Some(Violation(msg)) = payload.downcast_ref::<Violation>() {
+                RuleOutcome::Violated(format!("{}\n{}", msg, trace.join("\n")))
+            } else if let Some(Rejected(cond)) = payload.downcast_ref::<Rejected>() {
+                RuleOutcome::Rejected(cond)
+            } else {
+                RuleOutcome::Rejected("aborted")
+            }
+        }
+    }
+}
+
+/// Values of a tape, biased towards small amounts and `u64` boundaries
+fn tape_value() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        4 => 0..=1_000u64,
+        2 => 0..=1_000_000_000u64,
+        1 => any::<u64>(),
+        1 => (u64::MAX - 1_000)..=u64::MAX,
+    ]
+}
+
+fn config() -> Config {
+    Config {
+        // -- most tapes of a rule violate some assumption
+        max_global_rejects: 1 << 16,
+        failure_persistence: None,
+        ..Config::default()
+    }
+}
+
+/// Check `rule` on random tapes; returns the shrunk counterexample, if any
+///
+/// Runs are deterministic; `PROPTEST_CASES` sets the number of cases.
+pub fn check_rule(rule: impl Fn()) -> Result<(), String> {
+    let rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+    let mut runner = TestRunner::new_with_rng(config(), rng);
+    let result = runner.run(&vec(tape_value(), 0..48), |tape| {
+        match run_rule(tape, &rule) {
+            RuleOutcome::Verified => Ok(()),
+            RuleOutcome::Rejected(reason) => Err(TestCaseError::reject(reason)),
+            RuleOutcome::Violated(msg) => Err(TestCaseError::fail(msg)),
+        }
+    });
+    match result {
+        Ok(()) => Ok(()),
+        Err(TestError::Fail(msg, tape)) => Err(format!("{}\ntape: {:?}", msg, tape)),
+        Err(TestError::Abort(msg)) => panic!("rule was not checked: {}", msg),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cvlr_assert_le, cvlr_assume, nondet};
+
+    #[test]
+    fn test_check_rule() {
+        let rule = || {
+            let (a, b): (u64, u64) = (nondet(), nondet());
+            cvlr_assume!(a <= b);
+            cvlr_assert_le!(a / 2, b);
+        };
+        assert_eq!(check_rule(rule), Ok(()));
+
+        let err = check_rule(|| cvlr_assert_le!(nondet::<u64>(), 10)).unwrap_err();
+        // -- shrunk to the smallest violation
+        assert!(err.contains("tape: [11]"), "{}", err);
+    }
+}
@@ -0,0 +1,100 @@
+//! Token operations on packed SPL state
+//!
+//! Only balances and supplies are tracked; authorities are not checked, as
+//! in the prover summaries.
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    program_pack::Pack,
+};
+use spl_token::{
+    error::TokenError,
+    state::{Account, Mint},
+};
+
+fn load<T: Pack>(info: &AccountInfo) -> Result<T, ProgramError> {
+    T::unpack_unchecked(&info.try_borrow_data()?)
+}
+
+fn store<T: Pack>(info: &AccountInfo, state: T) -> ProgramResult {
+    T::pack(state, &mut info.try_borrow_mut_data()?)
+}
+
+pub fn spl_token_account_get_amount(info: &AccountInfo) -> u64 {
+    load::<Account>(info).unwrap().amount
+}
+
+pub fn spl_mint_get_supply(info: &AccountInfo) -> u64 {
+    load::<Mint>(info).unwrap().supply
+}
+
+pub fn spl_token_transfer(
+    src: &AccountInfo,
+    dst: &AccountInfo,
+    _authority: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    let mut source = load::<Account>(src)?;
+    source.amount = source
+        .amount
+        .checked_sub(amount)
+        .ok_or(TokenError::InsufficientFunds)?;
+    if src.key == dst.key {
+        return Ok(());
+    }
+    let mut destination = load::<Account>(dst)?;
+    destination.amount = destination
+        .amount
+        .checked_add(amount)
+        .ok_or(TokenError::Overflow)?;
+    store(src, source)?;
+    store(dst, destination)
+}
+
+pub fn spl_token_2022_transfer(
+    src: &AccountInfo,
+    dst: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    spl_token_transfer(src, dst, authority, amount)
+}
+
+pub fn spl_mint_to(
+    mint: &AccountInfo,
+    dst: &AccountInfo,
+    _authority: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    let mut state = load::<Mint>(mint)?;
+    let mut account = load::<Account>(dst)?;
+    state.supply = state
+        .supply
+        .checked_add(amount)
+        .ok_or(TokenError::Overflow)?;
+    account.amount = account
+        .amount
+        .checked_add(amount)
+        .ok_or(TokenError::Overflow)?;
+    store(mint, state)?;
+    store(dst, account)
+}
+
+pub fn spl_burn(
+    mint: &AccountInfo,
+    src: &AccountInfo,
+    _authority: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    let mut state = load::<Mint>(mint)?;
+    let mut account = load::<Account>(src)?;
+    account.amount = account
+        .amount
+        .checked_sub(amount)
+        .ok_or(TokenError::InsufficientFunds)?;
+    state.supply = state
+        .supply
+        .checked_sub(amount)
+        .ok_or(TokenError::Overflow)?;
+    store(mint, state)?;
+    store(src, account)
+}
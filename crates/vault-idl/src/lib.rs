@@ -0,0 +1,154 @@
+//! JSON IDL of the vault program
+//!
+//! Renders the descriptors of `certora_vault::idl` in the Anchor/Shank IDL
+//! format. Instructions and events carry an explicit `discriminant` since the
+//! program does not use Anchor discriminators; accounts are plain
+//! `#[repr(C)]` structs without padding, so fields are laid out in order.
+//!
+//! The generated IDL is checked in as `idl/certora_vault.json`; a test fails
+//! when it is out of date.
+use certora_vault::{
+    events::EVENT_VERSION,
+    idl::{self, ArgDesc, StructDesc, TypeDesc},
+    loaders::AccountSpec,
+    state::VAULT_VERSION,
+};
+use serde_json::{json, Value};
+
+/// `snake_case` or `PascalCase` to `camelCase`
+fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for (i, c) in name.chars().enumerate() {
+        if c == '_' {
+            upper = true;
+        } else if i == 0 {
+            out.push(c.to_ascii_lowercase());
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn type_json(ty: TypeDesc) -> Value {
+    match ty {
+        TypeDesc::U8 => json!("u8"),
+        TypeDesc::U64 => json!("u64"),
+        TypeDesc::PublicKey => json!("publicKey"),
+        TypeDesc::Bytes(len) => json!({ "array": ["u8", len] }),
+        TypeDesc::Defined(name) => json!({ "defined": name }),
+    }
+}
+
+fn struct_json(desc: &StructDesc) -> Value {
+    let fields: Vec<_> = desc
+        .fields
+        .iter()
+        .map(|field| json!({ "name": camel_case(field.name), "type": type_json(field.ty) }))
+        .collect();
+    json!({
+        "name": desc.name,
+        "type": { "kind": "struct", "fields": fields },
+    })
+}
+
+fn account_json(spec: &AccountSpec) -> Value {
+    let mut account = json!({
+        "name": camel_case(spec.name),
+        "isMut": spec.writable,
+        "isSigner": spec.signer,
+    });
+    let mut docs = vec![];
+    if let Some(field) = spec.has_one {
+        docs.push(format!("key is `vault.{}`", field));
+    }
+    if let Some(pda) = spec.pda {
+        docs.push(format!("PDA {:?} of the vault", pda));
+    }
+    if let Some(mint) = spec.token_mint {
+        docs.push(format!("token account of `vault.{}`", mint));
+    }
+    if !docs.is_empty() {
+        account["docs"] = json!(docs);
+    }
+    account
+}
+
+fn arg_json(arg: &ArgDesc) -> Value {
+    json!({ "name": camel_case(arg.name), "type": type_json(arg.ty) })
+}
+
+pub fn idl() -> Value {
+    let instructions: Vec<_> = idl::instructions()
+        .iter()
+        .map(|ix| {
+            json!({
+                "name": camel_case(ix.name),
+                "accounts": ix.accounts.iter().map(account_json).collect::<Vec<_>>(),
+                "args": ix.args.iter().map(arg_json).collect::<Vec<_>>(),
+                "discriminant": { "type": "u8", "value": ix.tag },
+            })
+        })
+        .collect();
+    let accounts: Vec<_> = idl::accounts().iter().map(struct_json).collect();
+    let types: Vec<_> = idl::types().iter().map(struct_json).collect();
+    let body = struct_json(&idl::event_body());
+    let events: Vec<_> = idl::events()
+        .iter()
+        .map(|event| {
+            json!({
+                "name": event.name,
+                "fields": body["type"]["fields"],
+                "discriminant": [EVENT_VERSION, event.kind],
+            })
+        })
+        .collect();
+    let errors: Vec<_> = idl::errors()
+        .iter()
+        .map(|err| json!({ "code": err.code, "name": err.name, "msg": err.msg }))
+        .collect();
+
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "name": "certora_vault",
+        "instructions": instructions,
+        "accounts": accounts,
+        "types": types,
+        "events": events,
+        "errors": errors,
+        "metadata": {
+            "origin": "shank",
+            "address": certora_vault::id().to_string(),
+            "accountVersion": VAULT_VERSION,
+        },
+    })
+}
+
+pub fn idl_string() -> String {
+    serde_json::to_string_pretty(&idl()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("vault_assets_account"), "vaultAssetsAccount");
+        assert_eq!(camel_case("DepositWithFee"), "depositWithFee");
+    }
+
+    #[test]
+    fn test_idl_is_up_to_date() {
+        let checked_in = include_str!("../../../idl/certora_vault.json");
+        assert!(
+            checked_in.trim_end() == idl_string(),
+            "idl/certora_vault.json is out of date, run \
+             `cargo run -p vault-idl > idl/certora_vault.json`"
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Print the IDL of the vault program
+//!
+//! Usage: `cargo run -p vault-idl > idl/certora_vault.json`
+fn main() {
+    println!("{}", vault_idl::idl_string());
+}
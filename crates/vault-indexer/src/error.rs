@@ -0,0 +1,49 @@
+use certora_vault::VaultError;
+
+#[derive(Debug)]
+pub enum IndexerError {
+    Io(std::io::Error),
+    /// Line of the input is not a record
+    Json {
+        line: usize,
+        err: serde_json::Error,
+    },
+    Base64(base64::DecodeError),
+    Pubkey(String),
+    /// Vault account data of unknown layout
+    AccountData(String),
+    Event(VaultError),
+}
+
+impl std::fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexerError::Io(err) => write!(f, "io error: {}", err),
+            IndexerError::Json { line, err } => write!(f, "line {}: {}", line, err),
+            IndexerError::Base64(err) => write!(f, "invalid base64: {}", err),
+            IndexerError::Pubkey(key) => write!(f, "invalid pubkey: {}", key),
+            IndexerError::AccountData(key) => write!(f, "account {} is not a vault", key),
+            IndexerError::Event(err) => write!(f, "invalid event: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for IndexerError {}
+
+impl From<std::io::Error> for IndexerError {
+    fn from(err: std::io::Error) -> Self {
+        IndexerError::Io(err)
+    }
+}
+
+impl From<base64::DecodeError> for IndexerError {
+    fn from(err: base64::DecodeError) -> Self {
+        IndexerError::Base64(err)
+    }
+}
+
+impl From<VaultError> for IndexerError {
+    fn from(err: VaultError) -> Self {
+        IndexerError::Event(err)
+    }
+}
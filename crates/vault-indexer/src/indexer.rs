@@ -0,0 +1,481 @@
+use std::{collections::BTreeMap, mem::size_of};
+
+use certora_vault::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
+    state::{legacy::VaultV0, Vault},
+};
+use solana_program::pubkey::Pubkey;
+
+use crate::{decode_base64, events_in_logs, parse_pubkey, IndexerError, Record};
+
+/// Event together with the transaction that emitted it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRecord {
+    pub slot: u64,
+    pub signature: String,
+    pub kind: VaultEventKind,
+    pub event: VaultEvent,
+}
+
+/// Share price after an event, as assets per share
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SharePrice {
+    pub slot: u64,
+    pub num_assets: u64,
+    pub num_shares: u64,
+}
+
+impl SharePrice {
+    /// Assets per share, `None` for a vault without shares
+    pub fn price(&self) -> Option<f64> {
+        (self.num_shares > 0).then(|| self.num_assets as f64 / self.num_shares as f64)
+    }
+}
+
+/// Shares of a user and the assets they paid for them
+///
+/// Users are the signers of deposits and redemptions, the `actor` of their
+/// events. Events do not name the owner of the token accounts, so shares
+/// deposited by a delegate are credited to the delegate, and redeeming
+/// them under another signer is reported as a `Divergence::UserShares`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct UserPosition {
+    pub shares: u64,
+    // assets paid for `shares`, fees included
+    pub cost_basis: u64,
+    // assets received on redeem minus their cost basis
+    pub realized_pnl: i128,
+}
+
+impl UserPosition {
+    /// Add `shares` bought for `assets`; `None`, leaving the position
+    /// unchanged, when a total overflows
+    fn buy(&mut self, shares: u64, assets: u64) -> Option<()> {
+        let shares = self.shares.checked_add(shares)?;
+        let cost_basis = self.cost_basis.checked_add(assets)?;
+        self.shares = shares;
+        self.cost_basis = cost_basis;
+        Some(())
+    }
+
+    /// Redeem `shares` for `assets`; the cost basis is released pro rata
+    fn sell(&mut self, shares: u64, assets: u64) {
+        let cost = if self.shares == 0 {
+            0
+        } else {
+            (self.cost_basis as u128 * shares as u128 / self.shares as u128) as u64
+        };
+        self.shares -= shares;
+        self.cost_basis -= cost;
+        self.realized_pnl += assets as i128 - cost as i128;
+    }
+}
+
+/// Disagreement between the event stream and the chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence {
+    /// Totals before an event differ from the totals after the previous one
+    EventGap {
+        vault: Pubkey,
+        slot: u64,
+        signature: String,
+        expected: VaultSnapshot,
+        found: VaultSnapshot,
+    },
+    /// Vault account differs from the reconstructed state
+    Account {
+        vault: Pubkey,
+        slot: u64,
+        expected: VaultSnapshot,
+        found: VaultSnapshot,
+    },
+    /// User redeemed more shares than the events gave them, e.g. shares
+    /// received through a token transfer or deposited by another signer
+    UserShares {
+        vault: Pubkey,
+        user: Pubkey,
+        slot: u64,
+        signature: String,
+        known: u64,
+        redeemed: u64,
+    },
+    /// Deposit overflows the shares or the cost basis of a user; the
+    /// position is left as it was before the deposit
+    UserOverflow {
+        vault: Pubkey,
+        user: Pubkey,
+        slot: u64,
+        signature: String,
+    },
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct VaultHistory {
+    pub events: Vec<EventRecord>,
+    pub prices: Vec<SharePrice>,
+    pub users: BTreeMap<Pubkey, UserPosition>,
+    // totals after the last event or account snapshot
+    pub state: Option<VaultSnapshot>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Indexer {
+    pub program_id: Pubkey,
+    pub vaults: BTreeMap<Pubkey, VaultHistory>,
+    pub divergences: Vec<Divergence>,
+}
+
+impl Default for Indexer {
+    fn default() -> Self {
+        Self::new(certora_vault::id())
+    }
+}
+
+impl Indexer {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            vaults: BTreeMap::new(),
+            divergences: vec![],
+        }
+    }
+
+    pub fn apply_records<'r>(
+        &mut self,
+        records: impl IntoIterator<Item = &'r Record>,
+    ) -> Result<(), IndexerError> {
+        records
+            .into_iter()
+            .try_for_each(|record| self.apply_record(record))
+    }
+
+    pub fn apply_record(&mut self, record: &Record) -> Result<(), IndexerError> {
+        match record {
+            Record::Transaction {
+                slot,
+                signature,
+                logs,
+            } => {
+                for (kind, event) in events_in_logs(&self.program_id, logs)? {
+                    self.apply_event(EventRecord {
+                        slot: *slot,
+                        signature: signature.clone(),
+                        kind,
+                        event,
+                    });
+                }
+            }
+            Record::Account { slot, pubkey, data } => {
+                let vault = parse_pubkey(pubkey)?;
+                let snapshot = vault_snapshot(&decode_base64(data)?)
+                    .ok_or_else(|| IndexerError::AccountData(pubkey.clone()))?;
+                self.apply_account(*slot, vault, snapshot);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn apply_event(&mut self, record: EventRecord) {
+        let EventRecord {
+            slot,
+            ref signature,
+            kind,
+            event,
+        } = record;
+        let history = self.vaults.entry(event.vault).or_default();
+
+        if let Some(expected) = history.state.filter(|state| *state != event.pre) {
+            self.divergences.push(Divergence::EventGap {
+                vault: event.vault,
+                slot,
+                signature: signature.clone(),
+                expected,
+                found: event.pre,
+            });
+        }
+
+        let effect = event.effect;
+        match kind {
+            VaultEventKind::Deposit
+            | VaultEventKind::DepositExact
+            | VaultEventKind::DepositWithFee
+            | VaultEventKind::DepositWithFeeExact => {
+                let user = history.users.entry(event.actor).or_default();
+                let bought = effect
+                    .assets_to_vault
+                    .checked_add(effect.assets_to_fee)
+                    .and_then(|assets| user.buy(effect.shares_to_user, assets));
+                if bought.is_none() {
+                    self.divergences.push(Divergence::UserOverflow {
+                        vault: event.vault,
+                        user: event.actor,
+                        slot,
+                        signature: signature.clone(),
+                    });
+                }
+            }
+            VaultEventKind::RedeemShares => {
+                let user = history.users.entry(event.actor).or_default();
+                if user.shares < effect.shares_to_burn {
+                    self.divergences.push(Divergence::UserShares {
+                        vault: event.vault,
+                        user: event.actor,
+                        slot,
+                        signature: signature.clone(),
+                        known: user.shares,
+                        redeemed: effect.shares_to_burn,
+                    });
+                    // -- account for the unknown shares at zero cost
+                    user.shares = effect.shares_to_burn;
+                }
+                user.sell(effect.shares_to_burn, effect.assets_to_user);
+            }
+            VaultEventKind::UpdateReward
+            | VaultEventKind::Slash
+            | VaultEventKind::CollectFee
+            | VaultEventKind::MigrateVault => {}
+        }
+
+        history.prices.push(SharePrice {
+            slot,
+            num_assets: event.post.num_assets,
+            num_shares: event.post.num_shares,
+        });
+        history.state = Some(event.post);
+        history.events.push(record);
+    }
+
+    /// Compare a vault account with the reconstructed state
+    ///
+    /// The first snapshot of a vault seen before any of its events is taken
+    /// as the starting state.
+    pub fn apply_account(&mut self, slot: u64, vault: Pubkey, found: VaultSnapshot) {
+        let history = self.vaults.entry(vault).or_default();
+        match history.state {
+            Some(expected) if expected != found => {
+                self.divergences.push(Divergence::Account {
+                    vault,
+                    slot,
+                    expected,
+                    found,
+                });
+                history.state = Some(found);
+            }
+            Some(_) => {}
+            None => history.state = Some(found),
+        }
+    }
+}
+
+/// Totals of vault account data of any known layout
+pub fn vault_snapshot(data: &[u8]) -> Option<VaultSnapshot> {
+    if data.len() == size_of::<VaultV0>() {
+        let vault = bytemuck::try_pod_read_unaligned::<VaultV0>(data).ok()?;
+        return Some(VaultSnapshot {
+            num_shares: vault.shares.into(),
+            num_assets: vault.assets.into(),
+            fee_amount: vault.fee_amount.into(),
+        });
+    }
+    let vault = bytemuck::try_pod_read_unaligned::<Vault>(data.get(..size_of::<Vault>())?).ok()?;
+    Some(VaultSnapshot::from(&vault))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use certora_vault::{events::EVENT_VERSION, operations::VaultEffect};
+
+    fn snapshot(num_shares: u64, num_assets: u64) -> VaultSnapshot {
+        VaultSnapshot {
+            num_shares,
+            num_assets,
+            fee_amount: 0,
+        }
+    }
+
+    fn event_log(kind: VaultEventKind, event: &VaultEvent) -> String {
+        format!(
+            "Program data: {} {}",
+            STANDARD.encode([EVENT_VERSION, kind as u8]),
+            STANDARD.encode(bytemuck::bytes_of(event))
+        )
+    }
+
+    fn transaction(slot: u64, kind: VaultEventKind, event: &VaultEvent) -> Record {
+        let program_id = certora_vault::id();
+        Record::Transaction {
+            slot,
+            signature: format!("sig{}", slot),
+            logs: vec![
+                format!("Program {} invoke [1]", program_id),
+                event_log(kind, event),
+                format!("Program {} success", program_id),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_deposit_and_redeem() {
+        let vault = Pubkey::new_unique();
+        let alice = Pubkey::new_unique();
+        let deposit = VaultEvent {
+            vault,
+            actor: alice,
+            effect: VaultEffect {
+                shares_to_user: 100,
+                assets_to_vault: 100,
+                ..Default::default()
+            },
+            pre: snapshot(0, 0),
+            post: snapshot(100, 100),
+        };
+        let reward = VaultEvent {
+            vault,
+            actor: Pubkey::default(),
+            effect: VaultEffect::default(),
+            pre: snapshot(100, 100),
+            post: snapshot(100, 200),
+        };
+        let redeem = VaultEvent {
+            vault,
+            actor: alice,
+            effect: VaultEffect {
+                shares_to_burn: 50,
+                assets_to_user: 100,
+                ..Default::default()
+            },
+            pre: snapshot(100, 200),
+            post: snapshot(50, 100),
+        };
+
+        let mut indexer = Indexer::default();
+        indexer
+            .apply_records(&[
+                transaction(1, VaultEventKind::Deposit, &deposit),
+                transaction(2, VaultEventKind::UpdateReward, &reward),
+                transaction(3, VaultEventKind::RedeemShares, &redeem),
+            ])
+            .unwrap();
+
+        let history = &indexer.vaults[&vault];
+        assert_eq!(history.events.len(), 3);
+        assert_eq!(history.prices[1].price(), Some(2.0));
+        assert_eq!(
+            history.users[&alice],
+            UserPosition {
+                shares: 50,
+                cost_basis: 50,
+                realized_pnl: 50,
+            }
+        );
+        assert!(indexer.divergences.is_empty());
+    }
+
+    #[test]
+    fn test_divergence() {
+        let vault = Pubkey::new_unique();
+        let event = VaultEvent {
+            vault,
+            actor: Pubkey::new_unique(),
+            effect: VaultEffect::default(),
+            pre: snapshot(10, 10),
+            post: snapshot(10, 20),
+        };
+        let account = Vault {
+            shares: 10.into(),
+            assets: 15.into(),
+            ..Default::default()
+        };
+
+        let mut indexer = Indexer::default();
+        indexer.apply_account(0, vault, snapshot(10, 5));
+        indexer.apply_event(EventRecord {
+            slot: 1,
+            signature: "sig1".to_string(),
+            kind: VaultEventKind::UpdateReward,
+            event,
+        });
+        indexer
+            .apply_record(&Record::Account {
+                slot: 1,
+                pubkey: vault.to_string(),
+                data: STANDARD.encode(bytemuck::bytes_of(&account)),
+            })
+            .unwrap();
+
+        assert_eq!(indexer.divergences.len(), 2);
+        assert!(matches!(
+            indexer.divergences[0],
+            Divergence::EventGap { .. }
+        ));
+        assert!(matches!(
+            indexer.divergences[1],
+            Divergence::Account { found, .. } if found == snapshot(10, 15)
+        ));
+    }
+
+    #[test]
+    fn test_user_overflow() {
+        let vault = Pubkey::new_unique();
+        let alice = Pubkey::new_unique();
+        let deposit = |amount: u64, fee: u64| VaultEvent {
+            vault,
+            actor: alice,
+            effect: VaultEffect {
+                shares_to_user: amount,
+                assets_to_vault: amount,
+                assets_to_fee: fee,
+                ..Default::default()
+            },
+            pre: snapshot(0, 0),
+            post: snapshot(0, 0),
+        };
+
+        let mut indexer = Indexer::default();
+        indexer
+            .apply_records(&[
+                transaction(1, VaultEventKind::DepositWithFee, &deposit(u64::MAX, 1)),
+                transaction(2, VaultEventKind::Deposit, &deposit(u64::MAX - 1, 0)),
+                transaction(3, VaultEventKind::Deposit, &deposit(2, 0)),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            indexer.vaults[&vault].users[&alice],
+            UserPosition {
+                shares: u64::MAX - 1,
+                cost_basis: u64::MAX - 1,
+                realized_pnl: 0,
+            }
+        );
+        assert_eq!(indexer.divergences.len(), 2);
+        assert!(matches!(
+            indexer.divergences[..],
+            [
+                Divergence::UserOverflow { slot: 1, .. },
+                Divergence::UserOverflow { slot: 3, .. },
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_failed_transaction() {
+        let program_id = certora_vault::id();
+        let event = VaultEvent {
+            vault: Pubkey::new_unique(),
+            actor: Pubkey::new_unique(),
+            effect: VaultEffect::default(),
+            pre: snapshot(0, 0),
+            post: snapshot(1, 1),
+        };
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            event_log(VaultEventKind::Deposit, &event),
+            format!("Program {} failed: custom program error: 0x6", program_id),
+        ];
+
+        assert!(events_in_logs(&program_id, &logs).unwrap().is_empty());
+    }
+}
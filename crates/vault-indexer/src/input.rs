@@ -0,0 +1,88 @@
+use std::io::BufRead;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use certora_vault::events::{decode_event, VaultEvent, VaultEventKind};
+use serde::Deserialize;
+use solana_program::pubkey::Pubkey;
+
+use crate::IndexerError;
+
+/// One line of the JSON-lines input
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    /// Log messages of a transaction, as in `meta.logMessages`
+    Transaction {
+        slot: u64,
+        signature: String,
+        logs: Vec<String>,
+    },
+    /// Vault account data at the end of `slot`, base64 encoded
+    Account {
+        slot: u64,
+        pubkey: String,
+        data: String,
+    },
+}
+
+/// Read all records of a JSON-lines input, skipping blank lines
+pub fn read_records(reader: impl BufRead) -> Result<Vec<Record>, IndexerError> {
+    let mut records = vec![];
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record =
+            serde_json::from_str(&line).map_err(|err| IndexerError::Json { line: idx + 1, err })?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+pub fn parse_pubkey(key: &str) -> Result<Pubkey, IndexerError> {
+    key.parse()
+        .map_err(|_| IndexerError::Pubkey(key.to_string()))
+}
+
+pub fn decode_base64(data: &str) -> Result<Vec<u8>, IndexerError> {
+    Ok(STANDARD.decode(data)?)
+}
+
+/// Vault events logged by `program_id` in the log messages of a transaction
+///
+/// Events are attributed to the innermost program being invoked, so data
+/// logged by other programs, including CPIs made by the vault, is ignored.
+/// A failed transaction is rolled back and has no events.
+pub fn events_in_logs(
+    program_id: &Pubkey,
+    logs: &[String],
+) -> Result<Vec<(VaultEventKind, VaultEvent)>, IndexerError> {
+    let program_id = program_id.to_string();
+    let mut invoked: Vec<&str> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if invoked.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let fields = data
+                .split_whitespace()
+                .map(decode_base64)
+                .collect::<Result<Vec<_>, _>>()?;
+            let fields: Vec<&[u8]> = fields.iter().map(Vec::as_slice).collect();
+            events.push(decode_event(&fields)?);
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(id), Some("invoke")) => invoked.push(id),
+                (Some(_), Some("success")) => {
+                    invoked.pop();
+                }
+                (Some(_), Some("failed:")) => return Ok(vec![]),
+                _ => {}
+            }
+        }
+    }
+    Ok(events)
+}
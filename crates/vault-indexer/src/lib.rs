@@ -0,0 +1,14 @@
+//! Offline indexer of vault events
+//!
+//! Folds the events logged by the vault program into a history of every
+//! vault: totals after each event, share price over time and the share
+//! balance and cost basis of every user. Snapshots of vault accounts found
+//! in the input are compared against the reconstructed state.
+
+pub mod error;
+pub mod indexer;
+pub mod input;
+
+pub use error::IndexerError;
+pub use indexer::*;
+pub use input::*;
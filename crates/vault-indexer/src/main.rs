@@ -0,0 +1,149 @@
+//! Replay a JSON-lines log of vault transactions and print the history
+//!
+//! Usage: `vault-indexer <log.jsonl>`
+//!
+//! The report is printed as JSON. The exit code is 1 when the events
+//! diverge from the chain.
+use std::{fs::File, io::BufReader, process::ExitCode};
+
+use certora_vault::events::VaultSnapshot;
+use serde_json::{json, Value};
+use vault_indexer::{read_records, Divergence, Indexer, IndexerError, VaultHistory};
+
+fn snapshot_json(snapshot: &VaultSnapshot) -> Value {
+    json!({
+        "num_shares": snapshot.num_shares,
+        "num_assets": snapshot.num_assets,
+        "fee_amount": snapshot.fee_amount,
+    })
+}
+
+fn history_json(history: &VaultHistory) -> Value {
+    let prices: Vec<_> = history
+        .prices
+        .iter()
+        .map(|price| {
+            json!({
+                "slot": price.slot,
+                "num_assets": price.num_assets,
+                "num_shares": price.num_shares,
+                "price": price.price(),
+            })
+        })
+        .collect();
+    let users: serde_json::Map<_, _> = history
+        .users
+        .iter()
+        .map(|(user, position)| {
+            let position = json!({
+                "shares": position.shares,
+                "cost_basis": position.cost_basis,
+                // -- i128 is not supported by serde_json without a feature
+                "realized_pnl": position.realized_pnl.to_string(),
+            });
+            (user.to_string(), position)
+        })
+        .collect();
+    json!({
+        "events": history.events.len(),
+        "state": history.state.as_ref().map(snapshot_json),
+        "share_price": prices,
+        "users": users,
+    })
+}
+
+fn divergence_json(divergence: &Divergence) -> Value {
+    match divergence {
+        Divergence::EventGap {
+            vault,
+            slot,
+            signature,
+            expected,
+            found,
+        } => json!({
+            "type": "event_gap",
+            "vault": vault.to_string(),
+            "slot": slot,
+            "signature": signature,
+            "expected": snapshot_json(expected),
+            "found": snapshot_json(found),
+        }),
+        Divergence::Account {
+            vault,
+            slot,
+            expected,
+            found,
+        } => json!({
+            "type": "account",
+            "vault": vault.to_string(),
+            "slot": slot,
+            "expected": snapshot_json(expected),
+            "found": snapshot_json(found),
+        }),
+        Divergence::UserShares {
+            vault,
+            user,
+            slot,
+            signature,
+            known,
+            redeemed,
+        } => json!({
+            "type": "user_shares",
+            "vault": vault.to_string(),
+            "user": user.to_string(),
+            "slot": slot,
+            "signature": signature,
+            "known": known,
+            "redeemed": redeemed,
+        }),
+        Divergence::UserOverflow {
+            vault,
+            user,
+            slot,
+            signature,
+        } => json!({
+            "type": "user_overflow",
+            "vault": vault.to_string(),
+            "user": user.to_string(),
+            "slot": slot,
+            "signature": signature,
+        }),
+    }
+}
+
+fn run(path: &str) -> Result<Indexer, IndexerError> {
+    let records = read_records(BufReader::new(File::open(path)?))?;
+    let mut indexer = Indexer::default();
+    indexer.apply_records(&records)?;
+    Ok(indexer)
+}
+
+fn main() -> ExitCode {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: vault-indexer <log.jsonl>");
+        return ExitCode::from(2);
+    };
+
+    let indexer = match run(&path) {
+        Ok(indexer) => indexer,
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            return ExitCode::from(2);
+        }
+    };
+
+    let vaults: serde_json::Map<_, _> = indexer
+        .vaults
+        .iter()
+        .map(|(vault, history)| (vault.to_string(), history_json(history)))
+        .collect();
+    let divergences: Vec<_> = indexer.divergences.iter().map(divergence_json).collect();
+    let report = json!({ "vaults": vaults, "divergences": divergences });
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+
+    if indexer.divergences.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,152 @@
+//! Search the inflation attack space of a vault configuration
+//!
+//! Usage: `vault-inflation [--fee-bps N] [--virtual-shares N] [--exact]
+//! [--min-deposit N] [--max-attacker N] [--max-victim N] [--json]`
+//!
+//! Prints the most profitable attack and, for every deposit of the victim,
+//! the attack with the largest victim loss. The exit code is 1 when some
+//! attack is profitable.
+use std::process::ExitCode;
+
+use serde_json::{json, Value};
+use vault_scenario::inflation::{analyze, Analysis, AttackConfig, AttackOutcome, SearchSpace};
+
+const USAGE: &str = "usage: vault-inflation [--fee-bps N] [--virtual-shares N] [--exact] \
+                     [--min-deposit N] [--max-attacker N] [--max-victim N] [--json]";
+
+struct Args {
+    config: AttackConfig,
+    max_attacker: u64,
+    max_victim: u64,
+    as_json: bool,
+}
+
+fn parse_args() -> Option<Args> {
+    let mut args = Args {
+        config: AttackConfig::default(),
+        max_attacker: 1_000_000,
+        max_victim: 1_000_000,
+        as_json: false,
+    };
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = || argv.next()?.parse::<u64>().ok();
+        match arg.as_str() {
+            "--fee-bps" => args.config.fee_bps = value()?,
+            "--virtual-shares" => args.config.virtual_shares = value()?,
+            "--min-deposit" => args.config.min_deposit = value()?,
+            "--max-attacker" => args.max_attacker = value()?,
+            "--max-victim" => args.max_victim = value()?,
+            "--exact" => args.config.exact = true,
+            "--json" => args.as_json = true,
+            _ => return None,
+        }
+    }
+    Some(args)
+}
+
+fn outcome_json(outcome: &AttackOutcome) -> Value {
+    json!({
+        "deposit": outcome.params.deposit,
+        "donation": outcome.params.donation,
+        "victim_deposit": outcome.params.victim_deposit,
+        "victim_shares": outcome.victim_shares,
+        // -- i128 is not supported by serde_json without a feature
+        "attacker_pnl": outcome.attacker_pnl.to_string(),
+        "victim_pnl": outcome.victim_pnl.to_string(),
+    })
+}
+
+fn analysis_json(analysis: &Analysis) -> Value {
+    let config = &analysis.config;
+    let losses: Vec<_> = analysis
+        .losses
+        .iter()
+        .map(|point| {
+            json!({
+                "victim_deposit": point.victim_deposit,
+                "max_loss": outcome_json(&point.max_loss),
+                "max_profit": outcome_json(&point.max_profit),
+            })
+        })
+        .collect();
+    json!({
+        "config": {
+            "fee_bps": config.fee_bps,
+            "virtual_shares": config.virtual_shares,
+            "exact": config.exact,
+            "min_deposit": config.min_deposit,
+        },
+        "feasible": analysis.feasible,
+        "max_profit": analysis.max_profit.as_ref().map(outcome_json),
+        "losses": losses,
+    })
+}
+
+fn describe(outcome: &AttackOutcome) -> String {
+    let params = &outcome.params;
+    format!(
+        "deposit={} donation={} victim_deposit={} victim_shares={} attacker_pnl={:+} victim_pnl={:+}",
+        params.deposit,
+        params.donation,
+        params.victim_deposit,
+        outcome.victim_shares,
+        outcome.attacker_pnl,
+        outcome.victim_pnl
+    )
+}
+
+fn print_analysis(analysis: &Analysis) {
+    let config = &analysis.config;
+    println!(
+        "fee_bps={} virtual_shares={} exact={} min_deposit={}: {} feasible attacks",
+        config.fee_bps, config.virtual_shares, config.exact, config.min_deposit, analysis.feasible
+    );
+    match &analysis.max_profit {
+        Some(outcome) => println!("max profit: {}", describe(outcome)),
+        None => println!("max profit: no feasible attack"),
+    }
+    println!("victim loss surface:");
+    for point in &analysis.losses {
+        println!(
+            "  {:>12}: max loss {:+} (attacker {:+}), max attacker profit {:+} (victim {:+})",
+            point.victim_deposit,
+            point.max_loss.victim_pnl,
+            point.max_loss.attacker_pnl,
+            point.max_profit.attacker_pnl,
+            point.max_profit.victim_pnl
+        );
+    }
+}
+
+fn main() -> ExitCode {
+    vault_scenario::quiet_program_logs();
+
+    let Some(args) = parse_args() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let space = SearchSpace::new(args.max_attacker, args.max_victim);
+    let analysis = match analyze(args.config, &space) {
+        Ok(analysis) => analysis,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::from(2);
+        }
+    };
+
+    if args.as_json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&analysis_json(&analysis)).unwrap()
+        );
+    } else {
+        print_analysis(&analysis);
+    }
+
+    match analysis.max_profit {
+        Some(outcome) if outcome.attacker_pnl > 0 => ExitCode::FAILURE,
+        _ => ExitCode::SUCCESS,
+    }
+}
@@ -0,0 +1,85 @@
+use certora_vault::VaultError;
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Yaml(serde_yaml::Error),
+    /// Step names an actor that the scenario does not declare
+    UnknownActor(String),
+    /// Actor is declared twice
+    DuplicateActor(String),
+    /// Actor holds fewer assets than the step spends
+    InsufficientAssets {
+        actor: String,
+        needed: u64,
+        available: u64,
+    },
+    /// Actor holds fewer shares than the step redeems
+    InsufficientShares {
+        actor: String,
+        needed: u64,
+        available: u64,
+    },
+    /// Balance of a vault account leaves `u64`
+    Overflow,
+    Vault(VaultError),
+}
+
+impl std::fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "io error: {}", err),
+            ScenarioError::Json(err) => write!(f, "invalid scenario: {}", err),
+            ScenarioError::Yaml(err) => write!(f, "invalid scenario: {}", err),
+            ScenarioError::UnknownActor(actor) => write!(f, "unknown actor {}", actor),
+            ScenarioError::DuplicateActor(actor) => write!(f, "actor {} declared twice", actor),
+            ScenarioError::InsufficientAssets {
+                actor,
+                needed,
+                available,
+            } => write!(
+                f,
+                "{} needs {} assets but holds {}",
+                actor, needed, available
+            ),
+            ScenarioError::InsufficientShares {
+                actor,
+                needed,
+                available,
+            } => write!(
+                f,
+                "{} needs {} shares but holds {}",
+                actor, needed, available
+            ),
+            ScenarioError::Overflow => write!(f, "vault balance overflow"),
+            ScenarioError::Vault(err) => write!(f, "vault error: {:?}", err),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl From<std::io::Error> for ScenarioError {
+    fn from(err: std::io::Error) -> Self {
+        ScenarioError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ScenarioError {
+    fn from(err: serde_json::Error) -> Self {
+        ScenarioError::Json(err)
+    }
+}
+
+impl From<serde_yaml::Error> for ScenarioError {
+    fn from(err: serde_yaml::Error) -> Self {
+        ScenarioError::Yaml(err)
+    }
+}
+
+impl From<VaultError> for ScenarioError {
+    fn from(err: VaultError) -> Self {
+        ScenarioError::Vault(err)
+    }
+}
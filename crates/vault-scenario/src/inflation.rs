@@ -0,0 +1,315 @@
+//! Profitability of the inflation attack
+//!
+//! The attack of `specs/inflation_attack`: Bob deposits into a vault holding
+//! only its virtual shares, donates to inflate the share price, Alice
+//! deposits and loses to rounding, and Bob redeems everything. `analyze`
+//! runs the attack as a scenario for every combination of the deposit of Bob,
+//! the donation and the deposit of Alice in a `SearchSpace`, and reports the most
+//! profitable attack and, for every deposit of Alice, the worst loss of Alice.
+//!
+//! Besides the grid, donations include, for every deposit of Bob and Alice,
+//! the smallest donations that mint Alice 0, 1, 2 or 3 shares, as the profit
+//! of the attack jumps at those thresholds. Donations never exceed the
+//! largest donation of the grid, the budget of the attacker.
+use crate::{ActorConfig, Amount, Scenario, ScenarioError, Simulation, Step, VaultConfig};
+
+const BOB: &str = "bob";
+const ALICE: &str = "alice";
+
+/// Vault and mitigations under attack
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct AttackConfig {
+    pub fee_bps: u64,
+    /// Shares and assets held by the vault before the attack
+    pub virtual_shares: u64,
+    /// Deposits use the exact variant of the deposit
+    pub exact: bool,
+    /// Deposits of fewer assets are rejected
+    pub min_deposit: u64,
+}
+
+/// Choices of the attacker and of the victim
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct AttackParams {
+    pub deposit: u64,
+    pub donation: u64,
+    pub victim_deposit: u64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AttackOutcome {
+    pub params: AttackParams,
+    /// Final assets of Bob less the starting assets
+    pub attacker_pnl: i128,
+    /// Value of the shares of Alice less the deposit
+    pub victim_pnl: i128,
+    pub victim_shares: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchSpace {
+    pub deposits: Vec<u64>,
+    pub donations: Vec<u64>,
+    pub victim_deposits: Vec<u64>,
+}
+
+/// Worst outcome for Alice at one deposit of Alice
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LossPoint {
+    pub victim_deposit: u64,
+    /// Attack with the largest loss of Alice
+    pub max_loss: AttackOutcome,
+    /// Attack with the largest profit of Bob
+    pub max_profit: AttackOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub config: AttackConfig,
+    /// Attacks run to completion
+    pub feasible: usize,
+    /// Most profitable attack over the whole space
+    pub max_profit: Option<AttackOutcome>,
+    /// Victim loss surface, by increasing deposit of Alice
+    pub losses: Vec<LossPoint>,
+}
+
+/// `1, 2, 5` times the powers of ten up to `max`, with their neighbours
+pub fn grid(max: u64) -> Vec<u64> {
+    let mut values = vec![];
+    let mut scale = 1u64;
+    loop {
+        for value in [1, 2, 5].map(|m| m * scale) {
+            values.extend([value - 1, value, value + 1]);
+        }
+        match scale.checked_mul(10) {
+            Some(next) if next <= max => scale = next,
+            _ => break,
+        }
+    }
+    values.retain(|&value| value > 0 && value <= max);
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+impl SearchSpace {
+    /// Grids of `grid(max_attacker)` and `grid(max_victim)`
+    pub fn new(max_attacker: u64, max_victim: u64) -> Self {
+        Self {
+            deposits: grid(max_attacker),
+            donations: grid(max_attacker),
+            victim_deposits: grid(max_victim),
+        }
+    }
+}
+
+impl AttackConfig {
+    /// The attack as a scenario, the actors starting with what they spend
+    pub fn scenario(&self, params: &AttackParams) -> Option<Scenario> {
+        let deposit = |actor: &str, amount| {
+            let (actor, amount, exact) = (actor.to_string(), Amount::Atoms(amount), self.exact);
+            if self.fee_bps == 0 {
+                Step::Deposit {
+                    actor,
+                    amount,
+                    exact,
+                }
+            } else {
+                Step::DepositWithFee {
+                    actor,
+                    amount,
+                    exact,
+                }
+            }
+        };
+        Some(Scenario {
+            vault: VaultConfig {
+                shares: self.virtual_shares,
+                assets: self.virtual_shares,
+                fee_bps: self.fee_bps,
+            },
+            actors: vec![
+                ActorConfig {
+                    name: BOB.to_string(),
+                    assets: params.deposit.checked_add(params.donation)?,
+                },
+                ActorConfig {
+                    name: ALICE.to_string(),
+                    assets: params.victim_deposit,
+                },
+            ],
+            steps: vec![
+                deposit(BOB, params.deposit),
+                Step::Donate {
+                    actor: BOB.to_string(),
+                    amount: Amount::Atoms(params.donation),
+                },
+                deposit(ALICE, params.victim_deposit),
+                Step::Redeem {
+                    actor: BOB.to_string(),
+                    shares: Amount::All,
+                },
+            ],
+        })
+    }
+
+    /// Run the attack; `None` if a step fails or a deposit is below the
+    /// minimum
+    pub fn attack(&self, params: &AttackParams) -> Option<AttackOutcome> {
+        if params.deposit < self.min_deposit || params.victim_deposit < self.min_deposit {
+            return None;
+        }
+        let scenario = self.scenario(params)?;
+        let mut simulation = Simulation::new(&scenario).ok()?;
+        for step in &scenario.steps {
+            simulation.step(step).ok()?;
+        }
+        let reports = simulation.actor_reports();
+        let (bob, alice) = (&reports[0], &reports[1]);
+        Some(AttackOutcome {
+            params: *params,
+            attacker_pnl: bob.pnl,
+            victim_pnl: alice.pnl,
+            victim_shares: alice.shares,
+        })
+    }
+
+    /// Smallest donations that mint Alice at most 3, 2, 1 or 0 shares after
+    /// Bob deposited `deposit`
+    fn threshold_donations(&self, deposit: u64, victim_deposit: u64) -> Vec<u64> {
+        let shares = self.virtual_shares as u128 + deposit as u128;
+        let victim_deposit = victim_deposit as u128;
+        (1..=4u128)
+            .filter_map(|target| {
+                // -- `victim_deposit * shares / (shares + donation) < target`
+                let donation = (victim_deposit * shares / target + 1).checked_sub(shares)?;
+                u64::try_from(donation).ok()
+            })
+            .collect()
+    }
+}
+
+/// Run the attack over `space`
+pub fn analyze(config: AttackConfig, space: &SearchSpace) -> Result<Analysis, ScenarioError> {
+    // -- reject configurations the vault itself rejects
+    Simulation::new(&Scenario {
+        vault: VaultConfig {
+            shares: config.virtual_shares,
+            assets: config.virtual_shares,
+            fee_bps: config.fee_bps,
+        },
+        actors: vec![],
+        steps: vec![],
+    })?;
+
+    let max_donation = space.donations.iter().copied().max().unwrap_or(0);
+    let mut feasible = 0;
+    let mut max_profit: Option<AttackOutcome> = None;
+    let mut losses = vec![];
+    for &victim_deposit in &space.victim_deposits {
+        let mut point: Option<LossPoint> = None;
+        for &deposit in &space.deposits {
+            let mut donations = space.donations.clone();
+            donations.push(0);
+            donations.extend(
+                config
+                    .threshold_donations(deposit, victim_deposit)
+                    .into_iter()
+                    .filter(|&donation| donation <= max_donation),
+            );
+            donations.sort_unstable();
+            donations.dedup();
+
+            for donation in donations {
+                let params = AttackParams {
+                    deposit,
+                    donation,
+                    victim_deposit,
+                };
+                let Some(outcome) = config.attack(&params) else {
+                    continue;
+                };
+                feasible += 1;
+                if !matches!(max_profit, Some(best) if outcome.attacker_pnl <= best.attacker_pnl) {
+                    max_profit = Some(outcome);
+                }
+                let point = point.get_or_insert(LossPoint {
+                    victim_deposit,
+                    max_loss: outcome,
+                    max_profit: outcome,
+                });
+                if outcome.victim_pnl < point.max_loss.victim_pnl {
+                    point.max_loss = outcome;
+                }
+                if outcome.attacker_pnl > point.max_profit.attacker_pnl {
+                    point.max_profit = outcome;
+                }
+            }
+        }
+        losses.extend(point);
+    }
+
+    Ok(Analysis {
+        config,
+        feasible,
+        max_profit,
+        losses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn space() -> SearchSpace {
+        SearchSpace::new(1_000, 10_000)
+    }
+
+    #[test]
+    fn test_attack_is_profitable_on_empty_vault() {
+        let analysis = analyze(AttackConfig::default(), &space()).unwrap();
+        let best = analysis.max_profit.unwrap();
+        assert!(best.attacker_pnl > 0);
+        // -- what Bob wins, Alice loses
+        assert!(best.victim_pnl <= -best.attacker_pnl);
+    }
+
+    #[test]
+    fn test_exact_deposit_bounds_loss() {
+        let config = AttackConfig {
+            exact: true,
+            ..Default::default()
+        };
+        let analysis = analyze(config, &space()).unwrap();
+        assert!(analysis.max_profit.unwrap().attacker_pnl <= 1);
+        assert!(analysis
+            .losses
+            .iter()
+            .all(|point| point.max_loss.victim_pnl >= -1));
+    }
+
+    #[test]
+    fn test_virtual_shares_make_attack_unprofitable() {
+        let config = AttackConfig {
+            virtual_shares: 1_000,
+            ..Default::default()
+        };
+        let analysis = analyze(config, &space()).unwrap();
+        assert!(analysis.max_profit.unwrap().attacker_pnl <= 0);
+        // -- yet Alice can still be harmed
+        assert!(analysis
+            .losses
+            .iter()
+            .any(|point| point.max_loss.victim_pnl < 0));
+    }
+
+    #[test]
+    fn test_invalid_fee_is_rejected() {
+        let config = AttackConfig {
+            fee_bps: 10_001,
+            ..Default::default()
+        };
+        assert!(analyze(config, &space()).is_err());
+    }
+}
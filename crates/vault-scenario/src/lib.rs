@@ -0,0 +1,33 @@
+//! Scripted scenarios on the vault operations
+//!
+//! A scenario names a set of actors with their starting assets and lists
+//! the steps they take: deposits, redemptions, donations through
+//! `vault_update_reward`, slashes and fee changes. `Simulation` runs the
+//! steps on a `Vault` with the pure `operations`, keeping the token balances
+//! of the actors and of the vault accounts, and reports the vault state,
+//! the share price and the profit and loss of every actor after each step.
+
+pub mod error;
+pub mod inflation;
+pub mod scenario;
+pub mod simulation;
+
+pub use error::ScenarioError;
+pub use scenario::*;
+pub use simulation::*;
+
+use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+
+struct QuietStubs;
+
+impl SyscallStubs for QuietStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+}
+
+/// Drop the messages logged by the program, which the default stubs print
+/// to stdout; failed steps would otherwise clutter the reports
+pub fn quiet_program_logs() {
+    set_syscall_stubs(Box::new(QuietStubs));
+}
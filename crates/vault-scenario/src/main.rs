@@ -0,0 +1,119 @@
+//! Replay a scripted scenario against a vault and print its economics
+//!
+//! Usage: `vault-scenario [--json] <scenario.{json,yaml}>`
+//!
+//! Every step is printed with the vault state, the share price and the
+//! position and profit of every actor after it. A failed step is reported
+//! and leaves the state untouched. With `--json` the report is printed as
+//! JSON.
+use std::{path::Path, process::ExitCode};
+
+use certora_vault::operations::VaultEffect;
+use serde_json::{json, Value};
+use vault_scenario::{ActorReport, Scenario, Simulation, StepReport, VaultState};
+
+fn effect_json(effect: &VaultEffect) -> Value {
+    json!({
+        "shares_to_burn": effect.shares_to_burn,
+        "shares_to_user": effect.shares_to_user,
+        "assets_to_vault": effect.assets_to_vault,
+        "assets_to_user": effect.assets_to_user,
+        "assets_to_fee": effect.assets_to_fee,
+    })
+}
+
+fn state_json(state: &VaultState) -> Value {
+    json!({
+        "num_shares": state.shares,
+        "num_assets": state.assets,
+        "fee_amount": state.fee_amount,
+        "fee_bps": state.fee_bps,
+        "vault_tokens": state.vault_tokens,
+        "fee_tokens": state.fee_tokens,
+        "slashed": state.slashed,
+        "price": state.price,
+    })
+}
+
+fn actor_json(actor: &ActorReport) -> Value {
+    json!({
+        "name": actor.name,
+        "assets": actor.assets,
+        "shares": actor.shares,
+        "value": actor.value,
+        // -- i128 is not supported by serde_json without a feature
+        "pnl": actor.pnl.to_string(),
+    })
+}
+
+fn report_json(report: &StepReport) -> Value {
+    let mut step = json!({
+        "step": report.step.to_string(),
+        "state": state_json(&report.state),
+        "actors": report.actors.iter().map(actor_json).collect::<Vec<_>>(),
+    });
+    match &report.result {
+        Ok(effect) => step["effect"] = effect_json(effect),
+        Err(err) => step["error"] = json!(err.to_string()),
+    }
+    step
+}
+
+fn print_report(index: usize, report: &StepReport) {
+    println!("#{} {}", index + 1, report.step);
+    if let Err(err) = &report.result {
+        println!("  failed: {}", err);
+        return;
+    }
+    let state = &report.state;
+    let price = state
+        .price
+        .map_or_else(|| "-".to_string(), |price| format!("{:.6}", price));
+    println!(
+        "  vault: assets={} shares={} fee_amount={} fee_bps={} tokens={} price={}",
+        state.assets, state.shares, state.fee_amount, state.fee_bps, state.vault_tokens, price
+    );
+    if state.fee_tokens > 0 || state.slashed > 0 {
+        println!(
+            "  fee account={} slashed={}",
+            state.fee_tokens, state.slashed
+        );
+    }
+    for actor in &report.actors {
+        println!(
+            "  {}: assets={} shares={} value={} pnl={:+}",
+            actor.name, actor.assets, actor.shares, actor.value, actor.pnl
+        );
+    }
+}
+
+fn main() -> ExitCode {
+    vault_scenario::quiet_program_logs();
+
+    let (flags, paths): (Vec<_>, Vec<_>) = std::env::args()
+        .skip(1)
+        .partition(|arg| arg.starts_with("--"));
+    let as_json = flags.iter().any(|flag| flag == "--json");
+    let ([path], true) = (paths.as_slice(), flags.iter().all(|flag| flag == "--json")) else {
+        eprintln!("usage: vault-scenario [--json] <scenario.{{json,yaml}}>");
+        return ExitCode::from(2);
+    };
+
+    let reports = match Scenario::read(Path::new(&path)).and_then(|s| Simulation::run(&s)) {
+        Ok(reports) => reports,
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            return ExitCode::from(2);
+        }
+    };
+
+    if as_json {
+        let steps: Vec<_> = reports.iter().map(report_json).collect();
+        println!("{}", serde_json::to_string_pretty(&steps).unwrap());
+    } else {
+        for (index, report) in reports.iter().enumerate() {
+            print_report(index, report);
+        }
+    }
+    ExitCode::SUCCESS
+}
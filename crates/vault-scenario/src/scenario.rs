@@ -0,0 +1,190 @@
+//! Scenario files
+//!
+//! A scenario is a JSON or YAML document:
+//!
+//! ```yaml
+//! vault: { shares: 0, assets: 0, fee_bps: 0 }
+//! actors:
+//!   - { name: bob, assets: 1000000 }
+//!   - { name: alice, assets: 1000000 }
+//! steps:
+//!   - deposit: { actor: bob, amount: 1 }
+//!   - donate: { actor: bob, amount: 10000 }
+//!   - deposit: { actor: alice, amount: 20000 }
+//!   - redeem: { actor: bob, shares: all }
+//! ```
+//!
+//! Amounts are atoms; `all` stands for the whole balance of the actor.
+use std::{fmt, path::Path};
+
+use serde::Deserialize;
+
+use crate::ScenarioError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default)]
+    pub vault: VaultConfig,
+    pub actors: Vec<ActorConfig>,
+    pub steps: Vec<Step>,
+}
+
+/// Starting totals of the vault; shares already outstanding belong to no
+/// actor of the scenario
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VaultConfig {
+    pub shares: u64,
+    pub assets: u64,
+    pub fee_bps: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActorConfig {
+    pub name: String,
+    /// Starting balance of the assets token
+    pub assets: u64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "AmountRepr")]
+pub enum Amount {
+    Atoms(u64),
+    /// The whole balance of the actor
+    All,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AmountRepr {
+    Atoms(u64),
+    Keyword(String),
+}
+
+impl TryFrom<AmountRepr> for Amount {
+    type Error = String;
+
+    fn try_from(repr: AmountRepr) -> Result<Self, Self::Error> {
+        match repr {
+            AmountRepr::Atoms(atoms) => Ok(Amount::Atoms(atoms)),
+            AmountRepr::Keyword(keyword) if keyword == "all" => Ok(Amount::All),
+            AmountRepr::Keyword(keyword) => Err(format!("invalid amount {:?}", keyword)),
+        }
+    }
+}
+
+impl Amount {
+    /// Atoms of a balance of `balance`
+    pub fn of(self, balance: u64) -> u64 {
+        match self {
+            Amount::Atoms(atoms) => atoms,
+            Amount::All => balance,
+        }
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Amount::Atoms(atoms) => write!(f, "{}", atoms),
+            Amount::All => write!(f, "all"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    /// `vault_deposit_assets`, or `vault_deposit_assets_exact` if `exact`
+    Deposit {
+        actor: String,
+        amount: Amount,
+        #[serde(default)]
+        exact: bool,
+    },
+    /// Deposit charging the fee of the vault
+    DepositWithFee {
+        actor: String,
+        amount: Amount,
+        #[serde(default)]
+        exact: bool,
+    },
+    Redeem {
+        actor: String,
+        shares: Amount,
+    },
+    /// Transfer of the actor into the vault assets account, followed by
+    /// `vault_update_reward`
+    Donate {
+        actor: String,
+        amount: Amount,
+    },
+    /// Yield paid into the vault assets account from outside the scenario,
+    /// followed by `vault_update_reward`
+    Reward {
+        amount: u64,
+    },
+    Slash {
+        amount: u64,
+    },
+    SetFee {
+        bps: u64,
+    },
+    CollectFee,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let exact = |exact: &bool| if *exact { " (exact)" } else { "" };
+        match self {
+            Step::Deposit {
+                actor,
+                amount,
+                exact: is_exact,
+            } => write!(f, "{} deposits {}{}", actor, amount, exact(is_exact)),
+            Step::DepositWithFee {
+                actor,
+                amount,
+                exact: is_exact,
+            } => write!(
+                f,
+                "{} deposits {} with fee{}",
+                actor,
+                amount,
+                exact(is_exact)
+            ),
+            Step::Redeem { actor, shares } => write!(f, "{} redeems {} shares", actor, shares),
+            Step::Donate { actor, amount } => write!(f, "{} donates {}", actor, amount),
+            Step::Reward { amount } => write!(f, "reward of {}", amount),
+            Step::Slash { amount } => write!(f, "slash of {}", amount),
+            Step::SetFee { bps } => write!(f, "fee set to {} bps", bps),
+            Step::CollectFee => write!(f, "fee collected"),
+        }
+    }
+}
+
+impl Scenario {
+    pub fn from_json(text: &str) -> Result<Self, ScenarioError> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    /// Steps are maps of a single key, as in JSON, rather than YAML tags
+    pub fn from_yaml(text: &str) -> Result<Self, ScenarioError> {
+        let deserializer = serde_yaml::Deserializer::from_str(text);
+        Ok(serde_yaml::with::singleton_map_recursive::deserialize(
+            deserializer,
+        )?)
+    }
+
+    /// Read a scenario file; files ending in `.json` are JSON, any other is
+    /// YAML
+    pub fn read(path: &Path) -> Result<Self, ScenarioError> {
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&text),
+            _ => Self::from_yaml(&text),
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! Running a scenario on a `Vault`
+use certora_vault::{
+    operations::{VaultEffect, VaultOperation},
+    state::{Vault, VaultBuilder},
+    utils::math::Rounding,
+};
+
+use crate::{Scenario, ScenarioError, Step};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor {
+    pub name: String,
+    pub initial_assets: u64,
+    pub assets: u64,
+    pub shares: u64,
+}
+
+/// A vault with the token balances around it
+#[derive(Clone)]
+pub struct Simulation {
+    pub vault: Vault,
+    /// Balance of the vault assets account
+    pub vault_tokens: u64,
+    /// Assets paid to the fee account, by deposits and by fee collection
+    pub fee_tokens: u64,
+    /// Assets taken out of the vault by slashes
+    pub slashed: u64,
+    pub actors: Vec<Actor>,
+}
+
+/// Vault state after a step
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultState {
+    pub shares: u64,
+    pub assets: u64,
+    pub fee_amount: u64,
+    pub fee_bps: u64,
+    pub vault_tokens: u64,
+    pub fee_tokens: u64,
+    pub slashed: u64,
+    /// Assets per share, `None` while no share is outstanding
+    pub price: Option<f64>,
+}
+
+/// Position of an actor after a step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActorReport {
+    pub name: String,
+    pub assets: u64,
+    pub shares: u64,
+    /// Assets the shares redeem for
+    pub value: u64,
+    /// `assets + value` less the starting assets
+    pub pnl: i128,
+}
+
+#[derive(Debug)]
+pub struct StepReport {
+    pub step: Step,
+    /// Effect of the vault operation; a failed step changes nothing
+    pub result: Result<VaultEffect, ScenarioError>,
+    pub state: VaultState,
+    pub actors: Vec<ActorReport>,
+}
+
+fn add(balance: u64, amount: u64) -> Result<u64, ScenarioError> {
+    balance.checked_add(amount).ok_or(ScenarioError::Overflow)
+}
+
+fn sub(balance: u64, amount: u64) -> Result<u64, ScenarioError> {
+    balance.checked_sub(amount).ok_or(ScenarioError::Overflow)
+}
+
+impl Simulation {
+    pub fn new(scenario: &Scenario) -> Result<Self, ScenarioError> {
+        let config = scenario.vault;
+        let vault = VaultBuilder::new()
+            .shares(config.shares)
+            .assets(config.assets)
+            .fee_bps(config.fee_bps)
+            .build();
+        vault.fee_in_bps()?;
+        vault.check_invariant()?;
+
+        let mut actors: Vec<Actor> = Vec::with_capacity(scenario.actors.len());
+        for actor in &scenario.actors {
+            if actors.iter().any(|known| known.name == actor.name) {
+                return Err(ScenarioError::DuplicateActor(actor.name.clone()));
+            }
+            actors.push(Actor {
+                name: actor.name.clone(),
+                initial_assets: actor.assets,
+                assets: actor.assets,
+                shares: 0,
+            });
+        }
+
+        Ok(Self {
+            vault,
+            vault_tokens: config.assets,
+            fee_tokens: 0,
+            slashed: 0,
+            actors,
+        })
+    }
+
+    /// Run every step of `scenario`; a failed step is reported and skipped
+    pub fn run(scenario: &Scenario) -> Result<Vec<StepReport>, ScenarioError> {
+        let mut simulation = Self::new(scenario)?;
+        Ok(scenario
+            .steps
+            .iter()
+            .map(|step| {
+                let result = simulation.step(step);
+                StepReport {
+                    step: step.clone(),
+                    result,
+                    state: simulation.state(),
+                    actors: simulation.actor_reports(),
+                }
+            })
+            .collect())
+    }
+
+    /// Apply `step`, leaving the simulation untouched if it fails
+    pub fn step(&mut self, step: &Step) -> Result<VaultEffect, ScenarioError> {
+        let mut next = self.clone();
+        let effect = next.apply(step)?;
+        *self = next;
+        Ok(effect)
+    }
+
+    fn actor(&self, name: &str) -> Result<usize, ScenarioError> {
+        self.actors
+            .iter()
+            .position(|actor| actor.name == name)
+            .ok_or_else(|| ScenarioError::UnknownActor(name.to_string()))
+    }
+
+    fn apply(&mut self, step: &Step) -> Result<VaultEffect, ScenarioError> {
+        match step {
+            Step::Deposit {
+                actor,
+                amount,
+                exact,
+            } => {
+                let idx = self.actor(actor)?;
+                let amount = amount.of(self.actors[idx].assets);
+                let op = if *exact {
+                    VaultOperation::DepositExact { amount }
+                } else {
+                    VaultOperation::Deposit { amount }
+                };
+                let effect = op.apply(&mut self.vault)?;
+                self.settle(idx, &effect)?;
+                Ok(effect)
+            }
+            Step::DepositWithFee {
+                actor,
+                amount,
+                exact,
+            } => {
+                let idx = self.actor(actor)?;
+                let amount = amount.of(self.actors[idx].assets);
+                let op = if *exact {
+                    VaultOperation::DepositWithFeeExact { amount }
+                } else {
+                    VaultOperation::DepositWithFee { amount }
+                };
+                let effect = op.apply(&mut self.vault)?;
+                self.settle(idx, &effect)?;
+                Ok(effect)
+            }
+            Step::Redeem { actor, shares } => {
+                let idx = self.actor(actor)?;
+                let shares = shares.of(self.actors[idx].shares);
+                let effect = VaultOperation::Redeem { shares }.apply(&mut self.vault)?;
+                self.settle(idx, &effect)?;
+                Ok(effect)
+            }
+            Step::Donate { actor, amount } => {
+                let idx = self.actor(actor)?;
+                let donor = &mut self.actors[idx];
+                let amount = amount.of(donor.assets);
+                donor.assets =
+                    donor
+                        .assets
+                        .checked_sub(amount)
+                        .ok_or(ScenarioError::InsufficientAssets {
+                            actor: donor.name.clone(),
+                            needed: amount,
+                            available: donor.assets,
+                        })?;
+                self.vault_tokens = add(self.vault_tokens, amount)?;
+                self.update_reward()
+            }
+            Step::Reward { amount } => {
+                self.vault_tokens = add(self.vault_tokens, *amount)?;
+                self.update_reward()
+            }
+            Step::Slash { amount } => {
+                let effect = VaultOperation::Slash { amount: *amount }.apply(&mut self.vault)?;
+                self.vault_tokens = sub(self.vault_tokens, effect.assets_to_user)?;
+                self.slashed = add(self.slashed, effect.assets_to_user)?;
+                Ok(effect)
+            }
+            Step::SetFee { bps } => {
+                self.vault.fee_bps = (*bps).into();
+                self.vault.fee_in_bps()?;
+                Ok(VaultEffect::default())
+            }
+            Step::CollectFee => {
+                let effect = VaultOperation::CollectFee.apply(&mut self.vault)?;
+                self.vault_tokens = sub(self.vault_tokens, effect.assets_to_user)?;
+                self.fee_tokens = add(self.fee_tokens, effect.assets_to_user)?;
+                Ok(effect)
+            }
+        }
+    }
+
+    /// Record the balance of the vault assets account in the vault
+    fn update_reward(&mut self) -> Result<VaultEffect, ScenarioError> {
+        let op = VaultOperation::UpdateReward {
+            balance: self.vault_tokens,
+        };
+        Ok(op.apply(&mut self.vault)?)
+    }
+
+    /// Move the tokens of `effect` between the actor at `idx` and the vault
+    fn settle(&mut self, idx: usize, effect: &VaultEffect) -> Result<(), ScenarioError> {
+        let actor = &mut self.actors[idx];
+        let paid = add(effect.assets_to_vault, effect.assets_to_fee)?;
+        let assets = actor
+            .assets
+            .checked_sub(paid)
+            .ok_or(ScenarioError::InsufficientAssets {
+                actor: actor.name.clone(),
+                needed: paid,
+                available: actor.assets,
+            })?;
+        let shares = actor.shares.checked_sub(effect.shares_to_burn).ok_or(
+            ScenarioError::InsufficientShares {
+                actor: actor.name.clone(),
+                needed: effect.shares_to_burn,
+                available: actor.shares,
+            },
+        )?;
+        actor.assets = add(assets, effect.assets_to_user)?;
+        actor.shares = add(shares, effect.shares_to_user)?;
+
+        self.vault_tokens = sub(
+            add(self.vault_tokens, effect.assets_to_vault)?,
+            effect.assets_to_user,
+        )?;
+        self.fee_tokens = add(self.fee_tokens, effect.assets_to_fee)?;
+        Ok(())
+    }
+
+    pub fn state(&self) -> VaultState {
+        let vault = &self.vault;
+        let price =
+            (vault.num_shares() > 0).then(|| vault.num_assets() as f64 / vault.num_shares() as f64);
+        VaultState {
+            shares: vault.num_shares(),
+            assets: vault.num_assets(),
+            fee_amount: vault.fee_amount(),
+            fee_bps: vault.fee_bps.into(),
+            vault_tokens: self.vault_tokens,
+            fee_tokens: self.fee_tokens,
+            slashed: self.slashed,
+            price,
+        }
+    }
+
+    pub fn actor_reports(&self) -> Vec<ActorReport> {
+        self.actors
+            .iter()
+            .map(|actor| {
+                // -- the shares of an actor never exceed the supply, so the
+                // conversion cannot overflow
+                let value = self
+                    .vault
+                    .convert_shares_to_assets(actor.shares, Rounding::Down)
+                    .expect("actor shares within supply");
+                let pnl = actor.assets as i128 + value as i128 - actor.initial_assets as i128;
+                ActorReport {
+                    name: actor.name.clone(),
+                    assets: actor.assets,
+                    shares: actor.shares,
+                    value,
+                    pnl,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INFLATION_ATTACK: &str = include_str!("../scenarios/inflation_attack.yaml");
+
+    #[test]
+    fn test_inflation_attack_scenario() {
+        let scenario = Scenario::from_yaml(INFLATION_ATTACK).unwrap();
+        let reports = Simulation::run(&scenario).unwrap();
+        assert!(reports.iter().all(|report| report.result.is_ok()));
+
+        let last = reports.last().unwrap();
+        let pnl = |name: &str| {
+            last.actors
+                .iter()
+                .find(|actor| actor.name == name)
+                .unwrap()
+                .pnl
+        };
+        // -- the victim is minted a single share and the attacker redeems
+        // everything but that share
+        assert!(pnl("alice") < 0);
+        assert_eq!(pnl("alice") + pnl("bob"), 0);
+        assert_eq!(last.state.vault_tokens, last.state.assets);
+    }
+
+    #[test]
+    fn test_failed_step_is_skipped() {
+        let scenario = Scenario::from_json(
+            r#"{
+                "actors": [{ "name": "bob", "assets": 10 }],
+                "steps": [
+                    { "deposit": { "actor": "bob", "amount": 11 } },
+                    { "redeem": { "actor": "carol", "shares": "all" } },
+                    { "deposit": { "actor": "bob", "amount": "all" } }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let reports = Simulation::run(&scenario).unwrap();
+        assert!(matches!(
+            reports[0].result,
+            Err(ScenarioError::InsufficientAssets { needed: 11, .. })
+        ));
+        assert_eq!(reports[0].state.shares, 0);
+        assert!(matches!(
+            reports[1].result,
+            Err(ScenarioError::UnknownActor(_))
+        ));
+        assert_eq!(reports[2].actors[0].shares, 10);
+        assert_eq!(reports[2].actors[0].pnl, 0);
+    }
+}
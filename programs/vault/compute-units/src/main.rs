@@ -0,0 +1,138 @@
+//! Compute units of a deposit and a redemption on the SBF build of the vault
+//!
+//! Loads `certora_vault.so` from the directory given as first argument, by
+//! default the `target/deploy` directory of the workspace, runs both
+//! instructions in `solana-program-test` and prints the compute units each
+//! consumed. Comparing two revisions is running this against the build of
+//! each.
+use certora_vault::{
+    client::{self, UserAccounts},
+    state::VaultBuilder,
+};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    account::Account,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+const AMOUNT: u64 = 1_000;
+
+fn account(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn pack<T: Pack>(state: T) -> Vec<u8> {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    data
+}
+
+fn mint(authority: Pubkey, supply: u64) -> Account {
+    let mint = Mint {
+        mint_authority: COption::Some(authority),
+        supply,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    account(pack(mint), spl_token::id())
+}
+
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let account = TokenAccount {
+        mint,
+        owner,
+        amount,
+        state: AccountState::Initialized,
+        ..Default::default()
+    };
+    self::account(pack(account), spl_token::id())
+}
+
+#[tokio::main]
+async fn main() {
+    let out_dir = std::env::args().nth(1).unwrap_or_else(|| {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../../../target/deploy").to_string()
+    });
+    std::env::set_var("SBF_OUT_DIR", out_dir);
+    let mut program_test = ProgramTest::new("certora_vault", certora_vault::id(), None);
+    program_test.prefer_bpf(true);
+
+    let vault_pk = Pubkey::new_unique();
+    let vault = VaultBuilder::new().pdas(&vault_pk).build();
+    let authority = Keypair::new();
+    let user = UserAccounts {
+        authority: authority.pubkey(),
+        assets_account: Pubkey::new_unique(),
+        shares_account: Pubkey::new_unique(),
+    };
+    let accounts = [
+        (
+            vault_pk,
+            account(bytemuck::bytes_of(&vault).to_vec(), certora_vault::id()),
+        ),
+        (vault.assets_mint, mint(vault.admin, AMOUNT)),
+        (vault.shares_mint, mint(vault.shares_mint, 0)),
+        (
+            vault.vault_assets_account,
+            token_account(vault.assets_mint, vault.vault_assets_account, 0),
+        ),
+        (
+            vault.fee_token_account,
+            token_account(vault.assets_mint, vault.admin, 0),
+        ),
+        (
+            user.assets_account,
+            token_account(vault.assets_mint, user.authority, AMOUNT),
+        ),
+        (
+            user.shares_account,
+            token_account(vault.shares_mint, user.authority, 0),
+        ),
+    ];
+    for (key, account) in accounts {
+        program_test.add_account(key, account);
+    }
+
+    let mut context = program_test.start_with_context().await;
+    let instructions = [
+        (
+            "deposit",
+            client::deposit(&vault_pk, &vault, &user, &spl_token::id(), AMOUNT),
+        ),
+        (
+            "redeem_shares",
+            client::redeem_shares(&vault_pk, &vault, &user, &spl_token::id(), AMOUNT),
+        ),
+    ];
+    for (name, ix) in instructions {
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &authority],
+            context.last_blockhash,
+        );
+        let result = context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await
+            .unwrap();
+        let metadata = result.metadata.unwrap();
+        if let Err(err) = result.result {
+            panic!("{name} failed: {err}\n{:#?}", metadata.log_messages);
+        }
+        println!("{name}: {} compute units", metadata.compute_units_consumed);
+    }
+}
@@ -0,0 +1,148 @@
+//! Random sequences of vault operations
+//!
+//! Each operation runs on a copy of the vault that is kept only if it
+//! succeeds, as a failed instruction leaves its accounts untouched. After
+//! every step the vault must be solvent, must not be diluted except by a
+//! slash, and must account for exactly the tokens and shares moved by the
+//! effect of the operation.
+#![no_main]
+
+use arbitrary::Arbitrary;
+use certora_vault::{
+    operations::{VaultEffect, VaultOperation},
+    state::{Vault, VaultBuilder},
+};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    shares: u64,
+    assets: u64,
+    fee_bps: u16,
+    fee_amount: u64,
+    /// Tokens of the vault assets account not yet recorded by the vault
+    surplus: u64,
+    /// `UpdateReward` pays its balance less the balance of the vault assets
+    /// account into the account first
+    ops: Vec<VaultOperation>,
+}
+
+/// Balances moved by the operations, as the token program would keep them
+struct Ledger {
+    /// Balance of the vault assets account
+    vault_tokens: u64,
+    /// Supply of the shares mint
+    shares_supply: u64,
+}
+
+impl Ledger {
+    /// Move the balances of `effect`; `None` leaves the ledger untouched
+    fn apply(&mut self, effect: &VaultEffect) -> Option<()> {
+        let vault_tokens = self
+            .vault_tokens
+            .checked_add(effect.assets_to_vault)?
+            .checked_sub(effect.assets_to_user)?;
+        let shares_supply = self
+            .shares_supply
+            .checked_add(effect.shares_to_user)?
+            .checked_sub(effect.shares_to_burn)?;
+        *self = Self {
+            vault_tokens,
+            shares_supply,
+        };
+        Some(())
+    }
+}
+
+/// Operations whose accounting is known to be off, so that the target looks
+/// for new findings: `DepositWithFeeExact` does not record the shares and
+/// assets it moves, and `UpdateReward` counts an uncollected fee as reward
+fn known_issue(op: &VaultOperation, vault: &Vault) -> bool {
+    match op {
+        VaultOperation::DepositWithFeeExact { .. } => true,
+        VaultOperation::UpdateReward { .. } => vault.fee_amount() > 0,
+        _ => false,
+    }
+}
+
+/// `post` is worth at least as much per share as `pre`, up to `slack` atoms
+fn check_no_dilution(pre: &Vault, post: &Vault, slack: u64) {
+    let pre_value = pre.num_assets() as u128 * post.num_shares() as u128;
+    let post_value = pre.num_shares() as u128 * (post.num_assets() as u128 + slack as u128);
+    assert!(
+        pre_value <= post_value,
+        "diluted: {:?} -> {:?}",
+        (pre.num_assets(), pre.num_shares()),
+        (post.num_assets(), post.num_shares()),
+    );
+}
+
+fuzz_target!(|input: Input| {
+    let mut vault = VaultBuilder::new()
+        .shares(input.shares)
+        .assets(input.assets)
+        .fee_bps(input.fee_bps.into())
+        .fee_amount(input.fee_amount)
+        .build();
+    let Some(vault_tokens) = input
+        .assets
+        .checked_add(input.fee_amount)
+        .and_then(|gross| gross.checked_add(input.surplus))
+    else {
+        return;
+    };
+    if vault.check_invariant().is_err() {
+        return;
+    }
+    let mut ledger = Ledger {
+        vault_tokens,
+        shares_supply: input.shares,
+    };
+
+    for op in &input.ops {
+        if known_issue(op, &vault) {
+            continue;
+        }
+        // -- paid in whether or not the update succeeds; tokens cannot be
+        // taken out of the account
+        if let VaultOperation::UpdateReward { balance } = *op {
+            if balance < ledger.vault_tokens {
+                continue;
+            }
+            ledger.vault_tokens = balance;
+        }
+
+        let pre = vault;
+        let tokens_before = ledger.vault_tokens;
+        let Ok(effect) = op.apply(&mut vault) else {
+            vault = pre;
+            continue;
+        };
+        // -- a transfer the token program would refuse fails the instruction
+        if ledger.apply(&effect).is_none() {
+            vault = pre;
+            continue;
+        }
+
+        assert!(vault.check_invariant().is_ok(), "insolvent after {:?}", op);
+        assert_eq!(vault.num_shares(), ledger.shares_supply, "{:?}", op);
+        assert!(vault.gross_assets() <= ledger.vault_tokens, "{:?}", op);
+        if let VaultOperation::UpdateReward { .. } = op {
+            assert_eq!(effect, VaultEffect::default());
+            assert!(pre.gross_assets() <= vault.gross_assets());
+        } else {
+            // -- the vault records exactly the tokens moved in and out
+            assert_eq!(
+                vault.gross_assets() as i128 - pre.gross_assets() as i128,
+                ledger.vault_tokens as i128 - tokens_before as i128,
+                "{:?}",
+                op
+            );
+        }
+
+        match op {
+            VaultOperation::Slash { .. } => {}
+            _ => check_no_dilution(&pre, &vault, 0),
+        }
+    }
+});
@@ -0,0 +1,280 @@
+//! Vault instructions on substituted and permuted accounts
+//!
+//! Each step builds a valid instruction for one of the actors, then swaps
+//! its accounts, substitutes them with other accounts of the ledger and
+//! flips signer flags, keeping only the signature of the actor. Whatever the
+//! processor accepts must keep the vault consistent with the ledger and must
+//! not take tokens from an account that the actor does not own.
+#![no_main]
+
+use std::sync::OnceLock;
+
+use arbitrary::Arbitrary;
+use certora_vault::{
+    client::{self, UserAccounts},
+    instruction::VaultInstruction,
+    state::{Vault, VaultBuilder},
+    test_support::{TestAccount, TokenBank, VaultFixture},
+};
+use libfuzzer_sys::fuzz_target;
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_program};
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+enum Kind {
+    Deposit,
+    DepositExact,
+    DepositWithFee,
+    DepositWithFeeExact,
+    Redeem,
+    /// `amount` is paid into the vault assets account before the update
+    UpdateReward,
+    Slash,
+    CollectFee,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Mutation {
+    /// Swap the accounts at two positions
+    Swap(u8, u8),
+    /// Replace the account at a position by an account of the ledger
+    Substitute(u8, u8),
+    /// Flip the signer flag of a position
+    Sign(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Step {
+    kind: Kind,
+    /// Index into the actors of `Setup`
+    actor: u8,
+    amount: u64,
+    mutations: Vec<Mutation>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    fee_bps: u16,
+    /// Starting balance of the assets account of each actor
+    balances: [u32; 4],
+    steps: Vec<Step>,
+}
+
+/// A vault with its accounts, and actors holding assets and shares accounts
+///
+/// The admin and the slash admin of the vault are actors too. Keys are fixed
+/// so that an input always replays the same way.
+struct Setup {
+    bank: TokenBank,
+    vault_pk: Pubkey,
+    vault: Vault,
+    actors: [UserAccounts; 4],
+    slashed_account: Pubkey,
+}
+
+fn key(n: u8) -> Pubkey {
+    Pubkey::new_from_array([n; 32])
+}
+
+impl Setup {
+    fn new() -> Self {
+        let vault_pk = key(1);
+        let actors: [UserAccounts; 4] = std::array::from_fn(|i| UserAccounts {
+            authority: key(10 + i as u8),
+            assets_account: key(20 + i as u8),
+            shares_account: key(30 + i as u8),
+        });
+        let vault = VaultBuilder::new()
+            .admin(actors[2].authority)
+            .slash_admin(actors[3].authority)
+            .assets_mint(key(2))
+            .fee_token_account(key(3))
+            .pdas(&vault_pk)
+            .build();
+        let slashed_account = key(4);
+
+        let VaultFixture { mut bank, .. } =
+            VaultFixture::new(vault_pk, vault, actors[0], slashed_account);
+        for actor in &actors[1..] {
+            bank.add(TestAccount::new(actor.authority, system_program::id()));
+            bank.create_token_account(actor.assets_account, vault.assets_mint, actor.authority);
+            bank.create_token_account(actor.shares_account, vault.shares_mint, actor.authority);
+        }
+
+        // -- substitutes: a forged vault, a foreign mint with an account of
+        // the first actor, and a program posing as the token program
+        bank.add(TestAccount::new(key(7), system_program::id()).data(vault_data(&vault)));
+        bank.create_mint(key(8), actors[0].authority);
+        bank.create_token_account(key(9), key(8), actors[0].authority);
+        bank.add(TestAccount::new(key(40), Pubkey::default()).executable());
+
+        Self {
+            bank,
+            vault_pk,
+            vault,
+            actors,
+            slashed_account,
+        }
+    }
+
+    fn instruction(&self, step: &Step) -> Instruction {
+        let (vault_pk, vault) = (&self.vault_pk, &self.vault);
+        let actor = &self.actors[step.actor as usize % self.actors.len()];
+        let token_program = &spl_token::id();
+        match step.kind {
+            Kind::Deposit => client::deposit(vault_pk, vault, actor, token_program, step.amount),
+            Kind::DepositExact => {
+                client::deposit_exact(vault_pk, vault, actor, token_program, step.amount)
+            }
+            Kind::DepositWithFee => {
+                client::deposit_with_fee(vault_pk, vault, actor, token_program, step.amount)
+            }
+            Kind::DepositWithFeeExact => {
+                client::deposit_with_fee_exact(vault_pk, vault, actor, token_program, step.amount)
+            }
+            Kind::Redeem => {
+                client::redeem_shares(vault_pk, vault, actor, token_program, step.amount)
+            }
+            Kind::UpdateReward => client::update_reward(vault_pk, vault),
+            Kind::Slash => client::slash(
+                vault_pk,
+                vault,
+                &self.slashed_account,
+                token_program,
+                step.amount,
+            ),
+            Kind::CollectFee => {
+                client::collect_fee(vault_pk, vault, &actor.assets_account, token_program)
+            }
+        }
+    }
+}
+
+fn vault_data(vault: &Vault) -> Vec<u8> {
+    bytemuck::bytes_of(vault).to_vec()
+}
+
+/// Swap, substitute and sign accounts of `ix` as `mutations` say; only
+/// `signer` can actually sign
+fn mutate(ix: &mut Instruction, mutations: &[Mutation], keys: &[Pubkey], signer: &Pubkey) {
+    let metas = &mut ix.accounts;
+    let len = metas.len();
+    let at = |i: u8| i as usize % len;
+    for mutation in mutations {
+        match *mutation {
+            Mutation::Swap(i, j) => {
+                let (i, j) = (at(i), at(j));
+                let (key_i, key_j) = (metas[i].pubkey, metas[j].pubkey);
+                metas[i].pubkey = key_j;
+                metas[j].pubkey = key_i;
+            }
+            Mutation::Substitute(i, k) => {
+                metas[at(i)].pubkey = keys[k as usize % keys.len()];
+            }
+            Mutation::Sign(i) => {
+                let i = at(i);
+                metas[i].is_signer = !metas[i].is_signer;
+            }
+        }
+    }
+    for meta in metas {
+        meta.is_signer &= meta.pubkey == *signer;
+    }
+}
+
+/// Balances of the token accounts of the actors and of the vault
+fn balances(bank: &TokenBank, setup: &Setup) -> Vec<(Pubkey, Pubkey, u64)> {
+    let mut keys = vec![
+        setup.vault.vault_assets_account,
+        setup.vault.fee_token_account,
+        setup.slashed_account,
+    ];
+    for actor in &setup.actors {
+        keys.extend([actor.assets_account, actor.shares_account]);
+    }
+    keys.into_iter()
+        .map(|key| {
+            let account = bank.token_account(&key);
+            (key, account.owner, account.amount)
+        })
+        .collect()
+}
+
+fn decode_vault(bank: &TokenBank, setup: &Setup) -> Vault {
+    client::decode_vault(&bank.account(&setup.vault_pk).data).unwrap()
+}
+
+fn check_vault(bank: &TokenBank, setup: &Setup, step: &Step) {
+    let vault = decode_vault(bank, setup);
+    assert!(vault.is_solvent(), "insolvent after {:?}", step);
+    assert_eq!(
+        bank.supply(&vault.shares_mint),
+        vault.num_shares(),
+        "shares after {:?}",
+        step
+    );
+    assert!(
+        vault.gross_assets() <= bank.balance(&vault.vault_assets_account),
+        "assets after {:?}",
+        step
+    );
+}
+
+fuzz_target!(|input: Input| {
+    static SETUP: OnceLock<Setup> = OnceLock::new();
+    let setup = SETUP.get_or_init(Setup::new);
+
+    let mut bank = setup.bank.clone();
+    let vault = VaultBuilder::from(setup.vault)
+        .fee_bps(input.fee_bps.into())
+        .build();
+    bank.account_mut(&setup.vault_pk).data = vault_data(&vault);
+    for (actor, balance) in setup.actors.iter().zip(input.balances) {
+        bank.mint_to(&actor.assets_account, balance.into());
+    }
+    let keys: Vec<Pubkey> = bank.accounts.keys().copied().collect();
+
+    for step in &input.steps {
+        // -- known issue: the vault does not record the shares and assets of
+        // an exact deposit with fee, see `vault_operations`
+        if let Kind::DepositWithFeeExact = step.kind {
+            continue;
+        }
+        let signer = setup.actors[step.actor as usize % setup.actors.len()].authority;
+        let mut ix = setup.instruction(step);
+        mutate(&mut ix, &step.mutations, &keys, &signer);
+        if let Kind::UpdateReward = step.kind {
+            bank.mint_to(&setup.vault.vault_assets_account, step.amount as u32 as u64);
+        }
+
+        let pre = balances(&bank, setup);
+        let pre_vault = decode_vault(&bank, setup);
+        let vault_balance = bank.balance(&setup.vault.vault_assets_account);
+        let snapshot = bank.clone();
+        if bank.process(&ix).is_err() {
+            // -- a failed instruction leaves its accounts untouched
+            bank = snapshot;
+            continue;
+        }
+
+        check_vault(&bank, setup, step);
+        // -- the processor changes the vault as the decoded operation does
+        let op = VaultInstruction::unpack(&ix.data)
+            .unwrap()
+            .operation(vault_balance)
+            .unwrap();
+        let mut expected = pre_vault;
+        op.apply(&mut expected).unwrap();
+        assert_eq!(
+            bytemuck::bytes_of(&expected),
+            bytemuck::bytes_of(&decode_vault(&bank, setup)),
+            "{:?} after {:?}",
+            op,
+            step
+        );
+        for ((key, owner, before), (_, _, after)) in pre.into_iter().zip(balances(&bank, setup)) {
+            if key != setup.vault.vault_assets_account && owner != signer {
+                assert!(after >= before, "{} lost tokens in {:?}", key, step);
+            }
+        }
+    }
+});
@@ -0,0 +1,228 @@
+//! `#[derive(Accounts)]` contexts of the Anchor flavour
+//!
+//! Constraints follow the `ACCOUNTS` of the native loader contexts and fail
+//! with the same `VaultError`s where Anchor lets us choose the error.
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{SHARES_MINT, VAULT_ASSETS},
+    loaders::check_token_mint,
+    state::Vault,
+    VaultError,
+};
+
+fn is_token_account_of(info: &AccountInfo, mint: &Pubkey) -> bool {
+    check_token_mint(info, mint).is_ok()
+}
+
+/// SPL token or Token-2022 program, as accepted by the native loaders
+fn is_token_program(info: &AccountInfo) -> bool {
+    spl_token_2022::check_spl_token_program_account(info.key).is_ok()
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        has_one = vault_assets_account @ VaultError::WrongAccount,
+        has_one = assets_mint @ VaultError::WrongAccount,
+        has_one = shares_mint @ VaultError::WrongAccount,
+    )]
+    pub vault_info: AccountLoader<'info, Vault>,
+    /// CHECK: key is checked against the vault and the PDA seeds
+    #[account(
+        mut,
+        seeds = [VAULT_ASSETS, vault_info.key().as_ref()],
+        bump = vault_info.load()?.vault_assets_account_bump,
+    )]
+    pub vault_assets_account: UncheckedAccount<'info>,
+    /// CHECK: key is checked against the vault
+    pub assets_mint: UncheckedAccount<'info>,
+    /// CHECK: key is checked against the vault and the PDA seeds
+    #[account(
+        mut,
+        seeds = [SHARES_MINT, vault_info.key().as_ref()],
+        bump = vault_info.load()?.vault_shares_mint_bump,
+    )]
+    pub shares_mint: UncheckedAccount<'info>,
+    /// CHECK: token account of `assets_mint`
+    #[account(
+        mut,
+        constraint = is_token_account_of(&user_assets_account, assets_mint.key)
+            @ VaultError::WrongMint,
+    )]
+    pub user_assets_account: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: token account of `shares_mint`
+    #[account(
+        mut,
+        constraint = is_token_account_of(&user_shares_account, shares_mint.key)
+            @ VaultError::WrongMint,
+    )]
+    pub user_shares_account: UncheckedAccount<'info>,
+    /// CHECK: SPL token or Token-2022 program
+    #[account(constraint = is_token_program(&spl_token_program) @ ProgramError::IncorrectProgramId)]
+    pub spl_token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DepositWithFee<'info> {
+    #[account(
+        mut,
+        has_one = vault_assets_account @ VaultError::WrongAccount,
+        has_one = assets_mint @ VaultError::WrongAccount,
+        has_one = shares_mint @ VaultError::WrongAccount,
+    )]
+    pub vault_info: AccountLoader<'info, Vault>,
+    /// CHECK: key is checked against the vault and the PDA seeds
+    #[account(
+        mut,
+        seeds = [VAULT_ASSETS, vault_info.key().as_ref()],
+        bump = vault_info.load()?.vault_assets_account_bump,
+    )]
+    pub vault_assets_account: UncheckedAccount<'info>,
+    /// CHECK: key is checked against the vault
+    #[account(
+        mut,
+        address = vault_info.load()?.fee_token_account @ VaultError::WrongAccount,
+    )]
+    pub vault_fee_account: UncheckedAccount<'info>,
+    /// CHECK: key is checked against the vault
+    pub assets_mint: UncheckedAccount<'info>,
+    /// CHECK: key is checked against the vault and the PDA seeds
+    #[account(
+        mut,
+        seeds = [SHARES_MINT, vault_info.key().as_ref()],
+        bump = vault_info.load()?.vault_shares_mint_bump,
+    )]
+    pub shares_mint: UncheckedAccount<'info>,
+    /// CHECK: token account of `assets_mint`
+    #[account(
+        mut,
+        constraint = is_token_account_of(&user_assets_account, assets_mint.key)
+            @ VaultError::WrongMint,
+    )]
+    pub user_assets_account: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: token account of `shares_mint`
+    #[account(
+        mut,
+        constraint = is_token_account_of(&user_shares_account, shares_mint.key)
+            @ VaultError::WrongMint,
+    )]
+    pub user_shares_account: UncheckedAccount<'info>,
+    /// CHECK: SPL token or Token-2022 program
+    #[account(constraint = is_token_program(&spl_token_program) @ ProgramError::IncorrectProgramId)]
+    pub spl_token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemShares<'info> {
+    #[account(
+        mut,
+        has_one = vault_assets_account @ VaultError::WrongAccount,
+        has_one = assets_mint @ VaultError::WrongAccount,
+        has_one = shares_mint @ VaultError::WrongAccount,
+    )]
+    pub vault_info: AccountLoader<'info, Vault>,
+    /// CHECK: key is checked against the vault and the PDA seeds
+    #[account(
+        mut,
+        seeds = [VAULT_ASSETS, vault_info.key().as_ref()],
+        bump = vault_info.load()?.vault_assets_account_bump,
+    )]
+    pub vault_assets_account: UncheckedAccount<'info>,
+    /// CHECK: key is checked against the vault
+    pub assets_mint: UncheckedAccount<'info>,
+    /// CHECK: key is checked against the vault and the PDA seeds
+    #[account(
+        mut,
+        seeds = [SHARES_MINT, vault_info.key().as_ref()],
+        bump = vault_info.load()?.vault_shares_mint_bump,
+    )]
+    pub shares_mint: UncheckedAccount<'info>,
+    /// CHECK: token account of `shares_mint`
+    #[account(
+        mut,
+        constraint = is_token_account_of(&user_shares_account, shares_mint.key)
+            @ VaultError::WrongMint,
+    )]
+    pub user_shares_account: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: token account of `assets_mint`
+    #[account(
+        mut,
+        constraint = is_token_account_of(&user_assets_account, assets_mint.key)
+            @ VaultError::WrongMint,
+    )]
+    pub user_assets_account: UncheckedAccount<'info>,
+    /// CHECK: SPL token or Token-2022 program
+    #[account(constraint = is_token_program(&spl_token_program) @ ProgramError::IncorrectProgramId)]
+    pub spl_token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateReward<'info> {
+    #[account(mut, has_one = vault_assets_account @ VaultError::WrongAccount)]
+    pub vault_info: AccountLoader<'info, Vault>,
+    /// CHECK: key is checked against the vault and the PDA seeds
+    #[account(
+        seeds = [VAULT_ASSETS, vault_info.key().as_ref()],
+        bump = vault_info.load()?.vault_assets_account_bump,
+    )]
+    pub vault_assets_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Slash<'info> {
+    #[account(mut)]
+    pub vault_info: AccountLoader<'info, Vault>,
+    /// CHECK: key is checked against the PDA seeds
+    #[account(
+        mut,
+        seeds = [VAULT_ASSETS, vault_info.key().as_ref()],
+        bump = vault_info.load()?.vault_assets_account_bump,
+    )]
+    pub vault_assets_account: UncheckedAccount<'info>,
+    /// CHECK: token account of `assets_mint`
+    #[account(
+        mut,
+        constraint = is_token_account_of(&user_token_account, assets_mint.key)
+            @ VaultError::WrongMint,
+    )]
+    pub user_token_account: UncheckedAccount<'info>,
+    /// CHECK: only used to check `user_token_account`
+    pub assets_mint: UncheckedAccount<'info>,
+    #[account(address = vault_info.load()?.slash_admin @ VaultError::UnauthorizedAdmin)]
+    pub authority: Signer<'info>,
+    /// CHECK: SPL token or Token-2022 program
+    #[account(constraint = is_token_program(&spl_token_program) @ ProgramError::IncorrectProgramId)]
+    pub spl_token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CollectFee<'info> {
+    #[account(
+        mut,
+        has_one = vault_assets_account @ VaultError::WrongAccount,
+        has_one = assets_mint @ VaultError::WrongAccount,
+    )]
+    pub vault_info: AccountLoader<'info, Vault>,
+    /// CHECK: key is checked against the vault
+    #[account(mut)]
+    pub vault_assets_account: UncheckedAccount<'info>,
+    /// CHECK: key is checked against the vault
+    pub assets_mint: UncheckedAccount<'info>,
+    /// CHECK: token account of `assets_mint`
+    #[account(
+        mut,
+        constraint = is_token_account_of(&fee_collect_account, assets_mint.key)
+            @ VaultError::WrongMint,
+    )]
+    pub fee_collect_account: UncheckedAccount<'info>,
+    #[account(address = vault_info.load()?.admin @ VaultError::UnauthorizedAdmin)]
+    pub authority: Signer<'info>,
+    /// CHECK: SPL token or Token-2022 program
+    #[account(constraint = is_token_program(&spl_token_program) @ ProgramError::IncorrectProgramId)]
+    pub spl_token_program: UncheckedAccount<'info>,
+}
@@ -0,0 +1,525 @@
+//! Anchor flavour of the vault program
+//!
+//! Enabled by the `anchor` feature. Instructions use Anchor 8-byte
+//! discriminators (see the generated `instruction` module) and vault
+//! accounts start with the `Vault` account discriminator. Handlers run the
+//! same `VaultOperation`s and `SplTokenBackend` as the native processors;
+//! only account loading differs.
+//!
+//! `MigrateVault` is native only: legacy vaults predate the Anchor layout.
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
+    operations::{VaultEffect, VaultOperation},
+    processor::{set_effect_return_data, SplTokenBackend, TokenBackend},
+    state::Vault,
+    utils::guards::require_ne,
+    vault_assets_account_seeds_with_bump, vault_shares_mint_seeds_with_bump, VaultError,
+};
+
+// -- `#[program]` expects the program id as `ID` in scope
+use crate::ID;
+
+pub mod contexts;
+
+pub use contexts::*;
+
+fn emit(
+    kind: VaultEventKind,
+    vault: Pubkey,
+    actor: Pubkey,
+    effect: VaultEffect,
+    pre: VaultSnapshot,
+    post: VaultSnapshot,
+) {
+    VaultEvent {
+        vault,
+        actor,
+        effect,
+        pre,
+        post,
+    }
+    .emit(kind);
+    set_effect_return_data(&effect);
+}
+
+/// Apply `op` to the vault; returns the effect and the totals around it
+fn apply(
+    vault_info: &AccountLoader<Vault>,
+    op: VaultOperation,
+) -> Result<(VaultEffect, VaultSnapshot, VaultSnapshot)> {
+    let mut vault = vault_info.load_mut()?;
+    vault.validate()?;
+    let pre = VaultSnapshot::from(&*vault);
+    let effect = op.apply(&mut vault)?;
+    Ok((effect, pre, VaultSnapshot::from(&*vault)))
+}
+
+fn deposit_common<T: TokenBackend>(
+    accounts: &Deposit,
+    kind: VaultEventKind,
+    op: VaultOperation,
+) -> Result<()> {
+    require_ne!(
+        accounts.vault_assets_account.key,
+        accounts.user_assets_account.key,
+        VaultError::SelfTransfer.into()
+    );
+
+    let (effect, pre, post) = apply(&accounts.vault_info, op)?;
+
+    T::transfer_in(
+        effect.assets_to_vault,
+        &accounts.vault_assets_account,
+        &accounts.user_assets_account,
+        &accounts.assets_mint,
+        &accounts.authority,
+        &accounts.spl_token_program,
+    )?;
+    T::mint(
+        effect.shares_to_user,
+        &accounts.user_shares_account,
+        &accounts.shares_mint,
+        &accounts.spl_token_program,
+        vault_shares_mint_seeds_with_bump!(
+            accounts.vault_info.key(),
+            accounts.vault_info.load()?.vault_shares_mint_bump
+        ),
+    )?;
+
+    emit(
+        kind,
+        accounts.vault_info.key(),
+        accounts.authority.key(),
+        effect,
+        pre,
+        post,
+    );
+    Ok(())
+}
+
+fn deposit_with_fee_common<T: TokenBackend>(
+    accounts: &DepositWithFee,
+    kind: VaultEventKind,
+    op: VaultOperation,
+) -> Result<()> {
+    require_ne!(
+        accounts.vault_assets_account.key,
+        accounts.user_assets_account.key,
+        VaultError::SelfTransfer.into()
+    );
+
+    let (effect, pre, post) = apply(&accounts.vault_info, op)?;
+
+    T::transfer_in(
+        effect.assets_to_vault,
+        &accounts.vault_assets_account,
+        &accounts.user_assets_account,
+        &accounts.assets_mint,
+        &accounts.authority,
+        &accounts.spl_token_program,
+    )?;
+    T::transfer_in(
+        effect.assets_to_fee,
+        &accounts.vault_fee_account,
+        &accounts.user_assets_account,
+        &accounts.assets_mint,
+        &accounts.authority,
+        &accounts.spl_token_program,
+    )?;
+    T::mint(
+        effect.shares_to_user,
+        &accounts.user_shares_account,
+        &accounts.shares_mint,
+        &accounts.spl_token_program,
+        vault_shares_mint_seeds_with_bump!(
+            accounts.vault_info.key(),
+            accounts.vault_info.load()?.vault_shares_mint_bump
+        ),
+    )?;
+
+    emit(
+        kind,
+        accounts.vault_info.key(),
+        accounts.authority.key(),
+        effect,
+        pre,
+        post,
+    );
+    Ok(())
+}
+
+fn redeem_shares_common<T: TokenBackend>(accounts: &RedeemShares, amount: u64) -> Result<()> {
+    require_ne!(
+        accounts.vault_assets_account.key,
+        accounts.user_assets_account.key,
+        VaultError::SelfTransfer.into()
+    );
+
+    let (effect, pre, post) = apply(
+        &accounts.vault_info,
+        VaultOperation::Redeem { shares: amount },
+    )?;
+
+    T::burn(
+        effect.shares_to_burn,
+        &accounts.user_shares_account,
+        &accounts.shares_mint,
+        &accounts.authority,
+        &accounts.spl_token_program,
+    )?;
+    T::transfer_out(
+        effect.assets_to_user,
+        &accounts.vault_assets_account,
+        &accounts.user_assets_account,
+        &accounts.assets_mint,
+        &accounts.spl_token_program,
+        vault_assets_account_seeds_with_bump!(
+            accounts.vault_info.key(),
+            accounts.vault_info.load()?.vault_assets_account_bump
+        ),
+    )?;
+
+    emit(
+        VaultEventKind::RedeemShares,
+        accounts.vault_info.key(),
+        accounts.authority.key(),
+        effect,
+        pre,
+        post,
+    );
+    Ok(())
+}
+
+#[program]
+pub mod certora_vault {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        deposit_common::<SplTokenBackend>(
+            ctx.accounts,
+            VaultEventKind::Deposit,
+            VaultOperation::Deposit { amount },
+        )
+    }
+
+    pub fn deposit_exact(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        deposit_common::<SplTokenBackend>(
+            ctx.accounts,
+            VaultEventKind::DepositExact,
+            VaultOperation::DepositExact { amount },
+        )
+    }
+
+    pub fn deposit_with_fee(ctx: Context<DepositWithFee>, amount: u64) -> Result<()> {
+        deposit_with_fee_common::<SplTokenBackend>(
+            ctx.accounts,
+            VaultEventKind::DepositWithFee,
+            VaultOperation::DepositWithFee { amount },
+        )
+    }
+
+    /// Rejected, like its native counterpart, until the operation records
+    /// the shares it mints
+    #[allow(unused_variables)]
+    pub fn deposit_with_fee_exact(ctx: Context<DepositWithFee>, amount: u64) -> Result<()> {
+        Err(ProgramError::InvalidInstructionData.into())
+    }
+
+    pub fn redeem_shares(ctx: Context<RedeemShares>, amount: u64) -> Result<()> {
+        redeem_shares_common::<SplTokenBackend>(ctx.accounts, amount)
+    }
+
+    /// Permissionless, like its native counterpart
+    pub fn update_reward(ctx: Context<UpdateReward>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let balance = SplTokenBackend::balance_of(&accounts.vault_assets_account)?;
+
+        let (effect, pre, post) = apply(
+            &accounts.vault_info,
+            VaultOperation::UpdateReward { balance },
+        )?;
+
+        emit(
+            VaultEventKind::UpdateReward,
+            accounts.vault_info.key(),
+            Pubkey::default(),
+            effect,
+            pre,
+            post,
+        );
+        Ok(())
+    }
+
+    pub fn slash(ctx: Context<Slash>, amount: u64) -> Result<()> {
+        let accounts = ctx.accounts;
+        let (effect, pre, post) = apply(&accounts.vault_info, VaultOperation::Slash { amount })?;
+
+        SplTokenBackend::transfer_out(
+            effect.assets_to_user,
+            &accounts.vault_assets_account,
+            &accounts.user_token_account,
+            &accounts.assets_mint,
+            &accounts.spl_token_program,
+            vault_assets_account_seeds_with_bump!(
+                accounts.vault_info.key(),
+                accounts.vault_info.load()?.vault_assets_account_bump
+            ),
+        )?;
+
+        emit(
+            VaultEventKind::Slash,
+            accounts.vault_info.key(),
+            accounts.authority.key(),
+            effect,
+            pre,
+            post,
+        );
+        Ok(())
+    }
+
+    pub fn collect_fee(ctx: Context<CollectFee>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let (effect, pre, post) = apply(&accounts.vault_info, VaultOperation::CollectFee)?;
+
+        SplTokenBackend::transfer_out(
+            effect.assets_to_user,
+            &accounts.vault_assets_account,
+            &accounts.fee_collect_account,
+            &accounts.assets_mint,
+            &accounts.spl_token_program,
+            vault_assets_account_seeds_with_bump!(
+                accounts.vault_info.key(),
+                accounts.vault_info.load()?.vault_assets_account_bump
+            ),
+        )?;
+
+        emit(
+            VaultEventKind::CollectFee,
+            accounts.vault_info.key(),
+            accounts.authority.key(),
+            effect,
+            pre,
+            post,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use anchor_lang::{Discriminator, InstructionData};
+
+    use crate::{
+        state::VaultBuilder,
+        test_support::{TestAccount, TokenBank, VaultFixture},
+    };
+
+    use super::*;
+
+    /// Anchor vault holding 100 assets, 50 more are paid in as reward
+    fn setup() -> VaultFixture {
+        let mut setup = VaultFixture::unique(VaultBuilder::one_to_one(100));
+        let mut data = Vault::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&setup.vault));
+        setup.bank.account_mut(&setup.vault_pk).data = data;
+        setup.bank.mint_to(&setup.vault.vault_assets_account, 150);
+        setup
+    }
+
+    fn update_reward(setup: &mut VaultFixture, vault_assets_account: Pubkey) -> Result<()> {
+        let metas = [
+            AccountMeta::new(setup.vault_pk, false),
+            AccountMeta::new_readonly(vault_assets_account, false),
+        ];
+        let data = instruction::UpdateReward {}.data();
+        let infos = setup.bank.infos(&metas);
+        entry(&ID, &infos, &data).map_err(Into::into)
+    }
+
+    fn deposit_metas(setup: &VaultFixture, token_program: Pubkey) -> Vec<AccountMeta> {
+        accounts::Deposit {
+            vault_info: setup.vault_pk,
+            vault_assets_account: setup.vault.vault_assets_account,
+            assets_mint: setup.vault.assets_mint,
+            shares_mint: setup.vault.shares_mint,
+            user_assets_account: setup.user.assets_account,
+            authority: setup.user.authority,
+            user_shares_account: setup.user.shares_account,
+            spl_token_program: token_program,
+        }
+        .to_account_metas(None)
+    }
+
+    /// Deposit through the `Deposit` context, with tokens moved by the bank
+    fn deposit(setup: &mut VaultFixture, amount: u64) -> Result<()> {
+        let metas = deposit_metas(setup, spl_token::id());
+        let infos = setup.bank.infos(&metas);
+        let accounts = Deposit::try_accounts(
+            &ID,
+            &mut &infos[..],
+            &[],
+            &mut DepositBumps::default(),
+            &mut BTreeSet::new(),
+        )?;
+        deposit_common::<TokenBank>(
+            &accounts,
+            VaultEventKind::Deposit,
+            VaultOperation::Deposit { amount },
+        )
+    }
+
+    /// Redeem through the `RedeemShares` context, with tokens moved by the
+    /// bank
+    fn redeem_shares(setup: &mut VaultFixture, amount: u64) -> Result<()> {
+        let metas = accounts::RedeemShares {
+            vault_info: setup.vault_pk,
+            vault_assets_account: setup.vault.vault_assets_account,
+            assets_mint: setup.vault.assets_mint,
+            shares_mint: setup.vault.shares_mint,
+            user_shares_account: setup.user.shares_account,
+            authority: setup.user.authority,
+            user_assets_account: setup.user.assets_account,
+            spl_token_program: spl_token::id(),
+        }
+        .to_account_metas(None);
+        let infos = setup.bank.infos(&metas);
+        let accounts = RedeemShares::try_accounts(
+            &ID,
+            &mut &infos[..],
+            &[],
+            &mut RedeemSharesBumps::default(),
+            &mut BTreeSet::new(),
+        )?;
+        redeem_shares_common::<TokenBank>(&accounts, amount)
+    }
+
+    /// The vault as stored after its discriminator
+    fn stored_vault(setup: &VaultFixture) -> Vault {
+        let data = &setup.bank.account(&setup.vault_pk).data;
+        *bytemuck::from_bytes(&data[Vault::DISCRIMINATOR.len()..])
+    }
+
+    #[test]
+    fn test_update_reward_dispatch() {
+        let mut setup = setup();
+        let vault_assets_account = setup.vault.vault_assets_account;
+        update_reward(&mut setup, vault_assets_account).unwrap();
+        assert_eq!(stored_vault(&setup).num_assets(), 150);
+        assert_eq!(stored_vault(&setup).num_shares(), 100);
+    }
+
+    #[test]
+    fn test_context_rejects_wrong_assets_account() {
+        let mut setup = setup();
+        let other = Pubkey::new_unique();
+        let mint = setup
+            .bank
+            .token_account(&setup.vault.vault_assets_account)
+            .mint;
+        setup.bank.create_token_account(other, mint, other);
+
+        let err = update_reward(&mut setup, other).unwrap_err();
+        assert_eq!(
+            ProgramError::from(err),
+            ProgramError::from(VaultError::WrongAccount)
+        );
+        assert_eq!(stored_vault(&setup).num_assets(), 100);
+    }
+
+    #[test]
+    fn test_unknown_discriminator() {
+        let mut setup = setup();
+        let metas = [AccountMeta::new(setup.vault_pk, false)];
+        let infos = setup.bank.infos(&metas);
+        assert!(entry(&ID, &infos, &[0; 8]).is_err());
+    }
+
+    #[test]
+    fn test_deposit_and_redeem() {
+        let mut setup = setup();
+        let user = setup.user;
+        let vault_assets_account = setup.vault.vault_assets_account;
+        setup.bank.mint_to(&user.assets_account, 1_000);
+
+        deposit(&mut setup, 1_000).unwrap();
+        assert_eq!(setup.bank.balance(&user.assets_account), 0);
+        assert_eq!(setup.bank.balance(&user.shares_account), 1_000);
+        assert_eq!(setup.bank.balance(&vault_assets_account), 1_150);
+        assert_eq!(stored_vault(&setup).num_assets(), 1_100);
+        assert_eq!(stored_vault(&setup).num_shares(), 1_100);
+
+        redeem_shares(&mut setup, 400).unwrap();
+        assert_eq!(setup.bank.balance(&user.assets_account), 400);
+        assert_eq!(setup.bank.balance(&user.shares_account), 600);
+        assert_eq!(setup.bank.balance(&vault_assets_account), 750);
+        assert_eq!(stored_vault(&setup).num_assets(), 700);
+        assert_eq!(stored_vault(&setup).num_shares(), 700);
+    }
+
+    #[test]
+    fn test_redeem_more_than_balance_fails() {
+        let mut setup = setup();
+        let user = setup.user;
+        setup.bank.mint_to(&user.assets_account, 100);
+        deposit(&mut setup, 100).unwrap();
+
+        assert!(redeem_shares(&mut setup, 101).is_err());
+        assert_eq!(setup.bank.balance(&user.shares_account), 100);
+    }
+
+    #[test]
+    fn test_context_token_programs() {
+        let mut setup = setup();
+        for (token_program, ok) in [
+            (spl_token::id(), true),
+            (spl_token_2022::id(), true),
+            (Pubkey::new_unique(), false),
+        ] {
+            setup
+                .bank
+                .add(TestAccount::new(token_program, Pubkey::default()).executable());
+            let metas = deposit_metas(&setup, token_program);
+            let infos = setup.bank.infos(&metas);
+            let result = Deposit::try_accounts(
+                &ID,
+                &mut &infos[..],
+                &[],
+                &mut DepositBumps::default(),
+                &mut BTreeSet::new(),
+            );
+            match result {
+                Ok(_) => assert!(ok),
+                Err(err) => {
+                    assert!(!ok);
+                    assert_eq!(ProgramError::from(err), ProgramError::IncorrectProgramId);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deposit_with_fee_exact_is_rejected() {
+        let mut setup = setup();
+        let metas = accounts::DepositWithFee {
+            vault_info: setup.vault_pk,
+            vault_assets_account: setup.vault.vault_assets_account,
+            vault_fee_account: setup.vault.fee_token_account,
+            assets_mint: setup.vault.assets_mint,
+            shares_mint: setup.vault.shares_mint,
+            user_assets_account: setup.user.assets_account,
+            authority: setup.user.authority,
+            user_shares_account: setup.user.shares_account,
+            spl_token_program: spl_token::id(),
+        }
+        .to_account_metas(None);
+        let data = instruction::DepositWithFeeExact { amount: 10 }.data();
+        let infos = setup.bank.infos(&metas);
+
+        let err = entry(&ID, &infos, &data).unwrap_err();
+        assert_eq!(err, ProgramError::InvalidInstructionData);
+    }
+}
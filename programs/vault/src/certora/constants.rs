@@ -1 +1,8 @@
-pub const MAX_FEE_BPS: u64 = 10_000;
+pub use crate::utils::math::MAX_FEE_BPS;
+
+/// Number of operations run by the sequence rules; `loop_iter` of the confs
+/// running them must be at least as large
+pub const SEQUENCE_LENGTH: usize = 3;
+
+/// Number of users of the ghost ledger
+pub const LEDGER_USERS: usize = 3;
@@ -0,0 +1,254 @@
+//! Ghost state of the specs
+//!
+//! The ledger models a bounded set of users and their assets and shares
+//! balances. The processor mocks in `certora::mocks::processor` update it on
+//! every token movement, and record the authority of every debit of a user
+//! account; ledger properties compare it to the vault.
+#[cfg(test)]
+use std::cell::Cell;
+
+use cvlr::mathint::NativeInt;
+use cvlr::nondet::nondet;
+use cvlr::{cvlr_assert, cvlr_assume};
+use cvlr_solana::cvlr_nondet_pubkey;
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+use crate::certora::constants::LEDGER_USERS;
+use crate::certora::specs::base_processor::{AccountsSnapshot, TokenAccountSnapshot};
+
+#[derive(Clone, Copy)]
+pub struct GhostUser {
+    pub owner: Pubkey,
+    pub assets_account: Pubkey,
+    pub shares_account: Pubkey,
+    pub assets: u64,
+    pub shares: u64,
+    /// The first debit of an account of the user without the signature of
+    /// the authority of the instruction
+    pub unauthorized_debit: Option<GhostDebit>,
+}
+
+#[derive(Clone, Copy)]
+pub struct GhostDebit {
+    pub authority: Pubkey,
+    pub is_signer: bool,
+}
+
+#[derive(Clone, Copy)]
+pub struct GhostLedger {
+    /// The first user is the one whose accounts the instruction takes
+    pub users: [GhostUser; LEDGER_USERS],
+    /// Authority of the instruction, if it takes one
+    pub authority: Option<Pubkey>,
+}
+
+// -- the prover runs on SBF, which has no thread locals, and checks one rule
+// at a time
+#[cfg(not(test))]
+static mut LEDGER: Option<GhostLedger> = None;
+
+#[cfg(test)]
+thread_local! {
+    // -- one ledger per thread, so that rules checked in parallel do not share it
+    static LEDGER: Cell<Option<GhostLedger>> = const { Cell::new(None) };
+}
+
+#[cfg(not(test))]
+fn load() -> Option<GhostLedger> {
+    // SAFETY: the prover runs single-threaded, and `GhostLedger` is `Copy`
+    unsafe { *core::ptr::addr_of!(LEDGER) }
+}
+
+#[cfg(not(test))]
+fn store(ledger: Option<GhostLedger>) {
+    // SAFETY: the prover runs single-threaded
+    unsafe { *core::ptr::addr_of_mut!(LEDGER) = ledger }
+}
+
+#[cfg(test)]
+fn load() -> Option<GhostLedger> {
+    LEDGER.with(Cell::get)
+}
+
+#[cfg(test)]
+fn store(ledger: Option<GhostLedger>) {
+    LEDGER.with(|cell| cell.set(ledger));
+}
+
+impl GhostUser {
+    fn has_account(&self, key: &Pubkey) -> bool {
+        self.assets_account == *key || self.shares_account == *key
+    }
+
+    /// Balance of the account `key` of the user
+    fn balance_mut(&mut self, key: &Pubkey) -> &mut u64 {
+        if self.assets_account == *key {
+            &mut self.assets
+        } else {
+            &mut self.shares
+        }
+    }
+}
+
+/// Whether `key` is an account of the instruction other than the user
+/// accounts
+fn is_instruction_key(accounts: &AccountsSnapshot, key: &Pubkey) -> bool {
+    let is_key = |account: Option<Pubkey>| account == Some(*key);
+    *key == accounts.vault_key
+        || *key == accounts.vault_assets_account.key
+        || is_key(accounts.vault_fee_account.map(|account| account.key))
+        || is_key(accounts.assets_mint.map(|mint| mint.key))
+        || is_key(accounts.shares_mint.map(|mint| mint.key))
+        || is_key(accounts.authority)
+}
+
+/// The key and balance of a user account of the instruction, or of a new
+/// account if it takes none
+fn user_account(account: Option<TokenAccountSnapshot>) -> (Pubkey, u64) {
+    match account {
+        Some(account) => (account.key, account.amount),
+        None => (cvlr_nondet_pubkey(), nondet()),
+    }
+}
+
+impl GhostLedger {
+    /// Users of the vault of `accounts`, holding all of its shares
+    ///
+    /// The user assets and shares accounts of the instruction are those of
+    /// the first user, which may or may not be owned by the authority of the
+    /// instruction. The other users are not the authority, and no two users
+    /// share an account.
+    pub fn nondet_for(accounts: &AccountsSnapshot) -> Self {
+        let (assets_account, assets) = user_account(accounts.user_assets_account);
+        let (shares_account, shares) = user_account(accounts.user_shares_account);
+        let owner = match accounts.authority {
+            Some(authority) if nondet() => authority,
+            _ => cvlr_nondet_pubkey(),
+        };
+        let mut users = [GhostUser {
+            owner,
+            assets_account,
+            shares_account,
+            assets,
+            shares,
+            unauthorized_debit: None,
+        }; LEDGER_USERS];
+
+        let rest = accounts.vault.num_shares().checked_sub(shares);
+        cvlr_assume!(rest.is_some());
+        let mut rest = rest.unwrap();
+        for (i, other) in users.iter_mut().enumerate().skip(1) {
+            other.owner = cvlr_nondet_pubkey();
+            other.assets_account = cvlr_nondet_pubkey();
+            other.shares_account = cvlr_nondet_pubkey();
+            other.assets = nondet();
+            // -- the last user holds the shares the others do not
+            other.shares = if i + 1 == LEDGER_USERS {
+                rest
+            } else {
+                nondet::<u64>().min(rest)
+            };
+            rest -= other.shares;
+        }
+
+        for (i, user) in users.iter().enumerate() {
+            cvlr_assume!(user.assets_account != user.shares_account);
+            cvlr_assume!(!is_instruction_key(accounts, &user.assets_account));
+            cvlr_assume!(!is_instruction_key(accounts, &user.shares_account));
+            cvlr_assume!(i == 0 || !is_instruction_key(accounts, &user.owner));
+            for earlier in &users[..i] {
+                cvlr_assume!(!earlier.has_account(&user.assets_account));
+                cvlr_assume!(!earlier.has_account(&user.shares_account));
+            }
+        }
+        Self {
+            users,
+            authority: accounts.authority,
+        }
+    }
+
+    pub fn total_shares(&self) -> NativeInt {
+        self.users
+            .iter()
+            .fold(NativeInt::from(0u64), |total, user| {
+                total + NativeInt::from(user.shares)
+            })
+    }
+
+    pub fn total_assets(&self) -> NativeInt {
+        self.users
+            .iter()
+            .fold(NativeInt::from(0u64), |total, user| {
+                total + NativeInt::from(user.assets)
+            })
+    }
+
+    /// The user holding the account `key`, if any
+    fn user_mut(&mut self, key: &Pubkey) -> Option<&mut GhostUser> {
+        self.users.iter_mut().find(|user| user.has_account(key))
+    }
+
+    fn credit(&mut self, account: &AccountInfo, amount: u64) {
+        if let Some(user) = self.user_mut(account.key) {
+            let balance = user.balance_mut(account.key);
+            let credited = balance.checked_add(amount);
+            cvlr_assume!(credited.is_some());
+            *balance = credited.unwrap();
+        }
+    }
+
+    /// Debit a user account, recording the debit if the authority of the
+    /// instruction did not sign it
+    fn debit(&mut self, account: &AccountInfo, authority: &AccountInfo, amount: u64) {
+        let authorized = authority.is_signer && Some(*authority.key) == self.authority;
+        if let Some(user) = self.user_mut(account.key) {
+            let balance = user.balance_mut(account.key);
+            cvlr_assume!(*balance >= amount);
+            *balance -= amount;
+            if !authorized && user.unauthorized_debit.is_none() {
+                user.unauthorized_debit = Some(GhostDebit {
+                    authority: *authority.key,
+                    is_signer: authority.is_signer,
+                });
+            }
+        }
+    }
+}
+
+/// Start tracking the users of `ledger`
+pub fn init_ledger(ledger: GhostLedger) {
+    store(Some(ledger));
+}
+
+/// Stop tracking users, so that an earlier rule does not constrain the next
+pub fn clear() {
+    store(None);
+}
+
+/// The ledger set by `init_ledger`
+pub fn ledger() -> GhostLedger {
+    let ledger = load();
+    cvlr_assert!(ledger.is_some());
+    ledger.unwrap()
+}
+
+fn update(f: impl FnOnce(&mut GhostLedger)) {
+    let mut ledger = load();
+    ledger.as_mut().map(f);
+    store(ledger);
+}
+
+pub fn transfer(from: &AccountInfo, to: &AccountInfo, authority: &AccountInfo, amount: u64) {
+    update(|ledger| {
+        ledger.debit(from, authority, amount);
+        ledger.credit(to, amount);
+    });
+}
+
+pub fn mint(to: &AccountInfo, amount: u64) {
+    update(|ledger| ledger.credit(to, amount));
+}
+
+pub fn burn(from: &AccountInfo, authority: &AccountInfo, amount: u64) {
+    update(|ledger| ledger.debit(from, authority, amount));
+}
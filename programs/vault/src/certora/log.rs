@@ -1,8 +1,10 @@
+use crate::certora::ghost::GhostUser;
 use crate::operations::VaultEffect;
 use crate::state::Vault;
 use crate::VaultError;
 /// Implementation for cvlr::log::CvlrLog trait
 use cvlr::log::cvlr_log_with;
+use cvlr_solana::pubkey::Pk;
 
 impl cvlr::log::CvlrLog for Vault {
     #[inline(always)]
@@ -33,8 +35,41 @@ impl cvlr::log::CvlrLog for VaultError {
         match self {
             VaultError::Unspecified => logger.log("Unspecified"),
             VaultError::MathOverflow => logger.log("MathOverflow"),
-            VaultError::GuardFail => logger.log("GuardFail"),
             VaultError::SelfTransfer => logger.log("SelfTransfer"),
+            VaultError::ZeroAmount => logger.log("ZeroAmount"),
+            VaultError::FeeOutOfRange => logger.log("FeeOutOfRange"),
+            VaultError::InsolventAfterSlash => logger.log("InsolventAfterSlash"),
+            VaultError::CapExceeded => logger.log("CapExceeded"),
+            VaultError::WrongMint => logger.log("WrongMint"),
+            VaultError::WrongPda => logger.log("WrongPda"),
+            VaultError::WrongAccount => logger.log("WrongAccount"),
+            VaultError::UnauthorizedAdmin => logger.log("UnauthorizedAdmin"),
+            VaultError::AccountNotWritable => logger.log("AccountNotWritable"),
+            VaultError::InvalidVaultData => logger.log("InvalidVaultData"),
+            VaultError::InvalidVaultVersion => logger.log("InvalidVaultVersion"),
+            VaultError::SameMint => logger.log("SameMint"),
+            VaultError::Insolvent => logger.log("Insolvent"),
+            VaultError::InvalidEventData => logger.log("InvalidEventData"),
+            VaultError::MissingReturnData => logger.log("MissingReturnData"),
         }
     }
 }
+
+impl cvlr::log::CvlrLog for GhostUser {
+    #[inline(always)]
+    fn log(&self, tag: &str, logger: &mut cvlr::log::CvlrLogger) {
+        logger.log_scope_start(tag);
+        cvlr_log_with("owner", &Pk(&self.owner), logger);
+        cvlr_log_with("assets", &self.assets, logger);
+        cvlr_log_with("shares", &self.shares, logger);
+        if let Some(debit) = &self.unauthorized_debit {
+            cvlr_log_with(
+                "unauthorized_debit_authority",
+                &Pk(&debit.authority),
+                logger,
+            );
+            cvlr_log_with("unauthorized_debit_is_signer", &debit.is_signer, logger);
+        }
+        logger.log_scope_end(tag);
+    }
+}
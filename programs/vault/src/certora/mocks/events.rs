@@ -0,0 +1,6 @@
+use crate::events::{VaultEvent, VaultEventKind};
+use cvlr::prelude::*;
+
+pub fn emit_event(_kind: VaultEventKind, _event: &VaultEvent) {
+    clog!("emitting event");
+}
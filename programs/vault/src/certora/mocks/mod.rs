@@ -4,4 +4,5 @@
 // For example, a function `my_mod::fun1` is mocked by
 // `certora::mocks::my_mod::fun1`
 
+pub mod events;
 pub mod processor;
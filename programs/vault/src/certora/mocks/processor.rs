@@ -4,6 +4,9 @@ use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
 };
 
+use crate::certora::ghost;
+use crate::processor::TokenBackend;
+
 pub fn spl_transfer_assets_from_user<'a>(
     amount: u64,
     vault_assets: &AccountInfo<'a>,
@@ -18,7 +21,9 @@ pub fn spl_transfer_assets_from_user<'a>(
 
     // We can use `spl_token_program` to know which token version we are and call either spl_token_transfer or spl_token_2022_transfer.
     // However, our mocks for spl_token_transfer and spl_token_2022_transfer are the same.
-    cvlr_solana::token::spl_token_2022_transfer(user_assets, vault_assets, authority, amount)
+    cvlr_solana::token::spl_token_2022_transfer(user_assets, vault_assets, authority, amount)?;
+    ghost::transfer(user_assets, vault_assets, authority, amount);
+    Ok(())
 }
 
 pub fn spl_mint_shares<'a>(
@@ -31,7 +36,9 @@ pub fn spl_mint_shares<'a>(
     clog!(&Pk(user_shares_account.key) => "user shares account key");
     clog!(&Pk(mint.key) => "mint key");
 
-    cvlr_solana::token::spl_mint_to(mint, user_shares_account, mint, amount)
+    cvlr_solana::token::spl_mint_to(mint, user_shares_account, mint, amount)?;
+    ghost::mint(user_shares_account, amount);
+    Ok(())
 }
 
 pub fn spl_burn_shares<'a>(
@@ -45,7 +52,9 @@ pub fn spl_burn_shares<'a>(
     clog!(&Pk(user_shares_account.key) => "user shares account key");
     clog!(&Pk(mint.key) => "mint key");
 
-    cvlr_solana::token::spl_burn(mint, user_shares_account, authority, amount)
+    cvlr_solana::token::spl_burn(mint, user_shares_account, authority, amount)?;
+    ghost::burn(user_shares_account, authority, amount);
+    Ok(())
 }
 
 pub fn spl_transfer_assets_from_vault<'a>(
@@ -59,10 +68,74 @@ pub fn spl_transfer_assets_from_vault<'a>(
     clog!(&Pk(vault_assets.key) => "vault assets key");
     clog!(&Pk(user_assets.key) => "user token key");
 
-    cvlr_solana::token::spl_token_2022_transfer(vault_assets, user_assets, vault_assets, amount)
+    cvlr_solana::token::spl_token_2022_transfer(vault_assets, user_assets, vault_assets, amount)?;
+    ghost::transfer(vault_assets, user_assets, vault_assets, amount);
+    Ok(())
 }
 
 pub fn spl_token_account_amount(info: &AccountInfo) -> Result<u64, ProgramError> {
     // We can return Err non-deterministically if needed.
     Ok(cvlr_solana::token::spl_token_account_get_amount(info))
 }
+
+/// Token backend of the processor specs
+pub struct CvlrTokenBackend;
+
+impl TokenBackend for CvlrTokenBackend {
+    fn transfer_in<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        spl_transfer_assets_from_user(
+            amount,
+            vault_assets,
+            user_assets,
+            mint,
+            authority,
+            token_program,
+        )
+    }
+
+    fn transfer_out<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        _signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        spl_transfer_assets_from_vault(amount, vault_assets, user_assets, mint, token_program)
+    }
+
+    fn mint<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        _signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        spl_mint_shares(amount, user_shares, mint, token_program)
+    }
+
+    fn burn<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        spl_burn_shares(amount, user_shares, mint, authority, token_program)
+    }
+
+    fn balance_of(info: &AccountInfo) -> Result<u64, ProgramError> {
+        spl_token_account_amount(info)
+    }
+}
+
+pub fn set_effect_return_data(_effect: &crate::operations::VaultEffect) {
+    clog!("setting return data");
+}
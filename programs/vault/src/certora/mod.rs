@@ -1,14 +1,20 @@
-#[cfg(feature = "certora")]
+#[cfg(any(feature = "certora", test))]
 pub mod nondet;
 
-#[cfg(feature = "certora")]
+#[cfg(any(feature = "certora", test))]
 pub mod log;
 
-#[cfg(feature = "certora")]
+#[cfg(any(feature = "certora", test))]
 pub mod mocks;
 
-#[cfg(feature = "certora")]
+#[cfg(any(feature = "certora", test))]
+pub mod ghost;
+
+#[cfg(any(feature = "certora", test))]
 pub mod constants;
 
-#[cfg(feature = "certora")]
+#[cfg(any(feature = "certora", test))]
 pub mod specs;
+
+#[cfg(all(test, not(feature = "certora")))]
+mod native;
@@ -0,0 +1,450 @@
+//! The CVLR rules of `specs`, checked natively with `cvlr-native`
+//!
+//! Each test mirrors the `#[rule]` of the same name and expects its status
+//! in `ci/expected.json`: `SUCCESS` rules must hold on every generated case
+//! and `FAIL` rules must produce a counterexample. Processor rules run on
+//! accounts held by a `TokenBank`, with vault amounts and token balances
+//! taken from `nondet()`.
+use std::sync::OnceLock;
+
+use cvlr::{cvlr_assume, nondet};
+use cvlr_native::check_rule;
+use solana_program::{account_info::AccountInfo, instruction::Instruction, pubkey::Pubkey};
+
+use crate::{
+    certora::constants::SEQUENCE_LENGTH,
+    certora::specs::{
+        access_control::props::AccessControlProcessDeposit,
+        base::{
+            base_collect_fee, base_deposit_assets, base_deposit_assets_exact,
+            base_deposit_assets_with_fee, base_deposit_assets_with_fee_exact, base_process_slash,
+            base_redeem_shares, base_sequence, base_update_reward,
+        },
+        base_processor::{
+            base_process_collect_fee, base_process_deposit, base_process_deposit_exact,
+            base_process_deposit_with_fee, base_process_deposit_with_fee_exact,
+            base_process_redeem_shares, base_process_slash as base_processor_slash,
+            base_process_update_reward,
+        },
+        exact_deposit::props_processor::ExactDepositProp,
+        fees::{props::FeeAssessedProp, props_processor::FeeAssessedProp as FeeAssessedProcessor},
+        ledger::props::{LedgerOwnershipProp, LedgerProRataProp, LedgerSharesProp},
+        no_dilution::{
+            props::NoDilutionProp, props_processor::NoDilutionProp as NoDilutionProcessor,
+        },
+        sequences::round_trips::{
+            base_reward_sandwich, base_round_trip_after_operation, base_round_trip_deposit_redeem,
+            base_round_trip_redeem_deposit,
+        },
+        solvency::{
+            props::SolvencyInvariant, props_processor::SolvencyInvariant as SolvencyProcessor,
+        },
+        vault_consistency::props::VaultConsistencyInvariant,
+    },
+    client::{self, UserAccounts},
+    operations::VaultOperation,
+    state::{builder::default_keys, Vault, VaultBuilder},
+    test_support::VaultFixture,
+};
+
+/// Accounts of a vault whose token balances agree with it, as in any
+/// reachable state; the user assets account may alias the vault assets
+/// account
+fn nondet_fixture() -> VaultFixture {
+    let vault_pk = default_keys::VAULT;
+    static TEMPLATE: OnceLock<VaultBuilder> = OnceLock::new();
+    let template = *TEMPLATE.get_or_init(|| VaultBuilder::new().pdas(&vault_pk));
+    let amounts: Vault = nondet();
+    let vault = template
+        .shares(amounts.num_shares())
+        .assets(amounts.num_assets())
+        .fee_bps(amounts.fee_bps.into())
+        .fee_amount(amounts.fee_amount())
+        .build();
+    let user = UserAccounts {
+        authority: Pubkey::new_from_array([6; 32]),
+        assets_account: if nondet() {
+            vault.vault_assets_account
+        } else {
+            Pubkey::new_from_array([7; 32])
+        },
+        shares_account: Pubkey::new_from_array([8; 32]),
+    };
+    let slashed_account = Pubkey::new_from_array([9; 32]);
+    let mut fixture = VaultFixture::new(vault_pk, vault, user, slashed_account);
+
+    let bank = &mut fixture.bank;
+    let vault_tokens = vault.num_assets().checked_add(nondet());
+    cvlr_assume!(vault_tokens.is_some());
+    bank.mint_to(&vault.vault_assets_account, vault_tokens.unwrap());
+    bank.mint_to(&vault.fee_token_account, nondet());
+    if user.assets_account != vault.vault_assets_account {
+        bank.mint_to(&user.assets_account, nondet());
+    }
+    // -- all shares are held by the user
+    bank.mint_to(&user.shares_account, vault.num_shares());
+    fixture
+}
+
+/// Run `base` on the accounts of `ix`
+fn run(ix: fn(&VaultFixture) -> Instruction, base: fn(&[AccountInfo])) {
+    let mut fixture = nondet_fixture();
+    let ix = ix(&fixture);
+    base(&fixture.bank.infos(&ix.accounts));
+}
+
+fn deposit(fixture: &VaultFixture) -> Instruction {
+    client::deposit(
+        &fixture.vault_pk,
+        &fixture.vault,
+        &fixture.user,
+        &spl_token::id(),
+        0,
+    )
+}
+
+fn deposit_with_fee(fixture: &VaultFixture) -> Instruction {
+    client::deposit_with_fee(
+        &fixture.vault_pk,
+        &fixture.vault,
+        &fixture.user,
+        &spl_token::id(),
+        0,
+    )
+}
+
+fn redeem_shares(fixture: &VaultFixture) -> Instruction {
+    client::redeem_shares(
+        &fixture.vault_pk,
+        &fixture.vault,
+        &fixture.user,
+        &spl_token::id(),
+        0,
+    )
+}
+
+fn update_reward(fixture: &VaultFixture) -> Instruction {
+    client::update_reward(&fixture.vault_pk, &fixture.vault)
+}
+
+fn slash(fixture: &VaultFixture) -> Instruction {
+    client::slash(
+        &fixture.vault_pk,
+        &fixture.vault,
+        &fixture.slashed_account,
+        &spl_token::id(),
+        0,
+    )
+}
+
+/// The fee goes to the user assets account
+fn collect_fee(fixture: &VaultFixture) -> Instruction {
+    client::collect_fee(
+        &fixture.vault_pk,
+        &fixture.vault,
+        &fixture.user.assets_account,
+        &spl_token::id(),
+    )
+}
+
+macro_rules! rules {
+    ($($name:ident: $rule:expr => $expected:literal;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let result = check_rule($rule);
+                match $expected {
+                    "SUCCESS" => {
+                        if let Err(counterexample) = result {
+                            panic!("{}", counterexample);
+                        }
+                    }
+                    _ => assert!(result.is_err(), "expected a counterexample"),
+                }
+            }
+        )*
+    };
+}
+
+rules! {
+    rule_solvency_deposit_assets: base_deposit_assets::<SolvencyInvariant> => "SUCCESS";
+    rule_solvency_deposit_assets_with_fee:
+        base_deposit_assets_with_fee::<SolvencyInvariant> => "SUCCESS";
+    rule_solvency_redeem_shares: base_redeem_shares::<SolvencyInvariant> => "SUCCESS";
+    rule_solvency_update_reward: base_update_reward::<SolvencyInvariant> => "SUCCESS";
+    rule_solvency_slash: base_process_slash::<SolvencyInvariant> => "SUCCESS";
+    rule_solvency_deposit_assets_exact:
+        base_deposit_assets_exact::<SolvencyInvariant> => "SUCCESS";
+    rule_solvency_deposit_assets_with_fee_exact:
+        base_deposit_assets_with_fee_exact::<SolvencyInvariant> => "SUCCESS";
+    rule_solvency_collect_fee: base_collect_fee::<SolvencyInvariant> => "SUCCESS";
+
+    rule_no_dilution_deposit_assets: base_deposit_assets::<NoDilutionProp> => "SUCCESS";
+    rule_no_dilution_deposit_assets_with_fee:
+        base_deposit_assets_with_fee::<NoDilutionProp> => "SUCCESS";
+    rule_no_dilution_redeem_shares: base_redeem_shares::<NoDilutionProp> => "SUCCESS";
+    rule_no_dilution_update_reward: base_update_reward::<NoDilutionProp> => "SUCCESS";
+    rule_no_dilution_slash: base_process_slash::<NoDilutionProp> => "FAIL";
+    rule_no_dilution_deposit_assets_exact:
+        base_deposit_assets_exact::<NoDilutionProp> => "SUCCESS";
+    rule_no_dilution_deposit_assets_with_fee_exact:
+        base_deposit_assets_with_fee_exact::<NoDilutionProp> => "SUCCESS";
+    rule_no_dilution_collect_fee: base_collect_fee::<NoDilutionProp> => "SUCCESS";
+
+    rule_fees_assessed_deposit_assets_with_fee:
+        base_deposit_assets_with_fee::<FeeAssessedProp> => "FAIL";
+    rule_fees_assessed_deposit_assets: base_deposit_assets::<FeeAssessedProp> => "FAIL";
+    rule_fees_assessed_deposit_assets_exact:
+        base_deposit_assets_exact::<FeeAssessedProp> => "FAIL";
+    rule_fees_assessed_deposit_assets_with_fee_exact:
+        base_deposit_assets_with_fee_exact::<FeeAssessedProp> => "FAIL";
+    rule_fees_assessed_redeem_shares: base_redeem_shares::<FeeAssessedProp> => "FAIL";
+    rule_fees_assessed_update_reward: base_update_reward::<FeeAssessedProp> => "FAIL";
+    rule_fees_assessed_slash: base_process_slash::<FeeAssessedProp> => "FAIL";
+    rule_fees_assessed_collect_fee: base_collect_fee::<FeeAssessedProp> => "FAIL";
+
+    rule_solvency_process_deposit: || run(
+        deposit,
+        base_process_deposit::<SolvencyProcessor>,
+    ) => "SUCCESS";
+    rule_solvency_process_deposit_with_fee: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee::<SolvencyProcessor>,
+    ) => "SUCCESS";
+    rule_solvency_process_deposit_exact: || run(
+        deposit,
+        base_process_deposit_exact::<SolvencyProcessor>,
+    ) => "SUCCESS";
+    rule_solvency_process_deposit_with_fee_exact: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee_exact::<SolvencyProcessor>,
+    ) => "SUCCESS";
+    rule_solvency_process_redeem_shares: || run(
+        redeem_shares,
+        base_process_redeem_shares::<SolvencyProcessor>,
+    ) => "SUCCESS";
+    rule_solvency_process_update_reward: || run(
+        update_reward,
+        base_process_update_reward::<SolvencyProcessor>,
+    ) => "SUCCESS";
+    rule_solvency_process_slash: || run(
+        slash,
+        base_processor_slash::<SolvencyProcessor>,
+    ) => "SUCCESS";
+    rule_solvency_process_collect_fee: || run(
+        collect_fee,
+        base_process_collect_fee::<SolvencyProcessor>,
+    ) => "SUCCESS";
+
+    rule_no_dilution_process_deposit: || run(
+        deposit,
+        base_process_deposit::<NoDilutionProcessor>,
+    ) => "SUCCESS";
+    rule_no_dilution_process_deposit_with_fee: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee::<NoDilutionProcessor>,
+    ) => "SUCCESS";
+    rule_no_dilution_process_deposit_exact: || run(
+        deposit,
+        base_process_deposit_exact::<NoDilutionProcessor>,
+    ) => "SUCCESS";
+    rule_no_dilution_process_deposit_with_fee_exact: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee_exact::<NoDilutionProcessor>,
+    ) => "SUCCESS";
+    rule_no_dilution_process_redeem_shares: || run(
+        redeem_shares,
+        base_process_redeem_shares::<NoDilutionProcessor>,
+    ) => "SUCCESS";
+    rule_no_dilution_process_update_reward: || run(
+        update_reward,
+        base_process_update_reward::<NoDilutionProcessor>,
+    ) => "SUCCESS";
+    rule_no_dilution_process_slash: || run(
+        slash,
+        base_processor_slash::<NoDilutionProcessor>,
+    ) => "FAIL";
+    rule_no_dilution_process_collect_fee: || run(
+        collect_fee,
+        base_process_collect_fee::<NoDilutionProcessor>,
+    ) => "SUCCESS";
+
+    rule_vault_consistency_process_deposit: || run(
+        deposit,
+        base_process_deposit::<VaultConsistencyInvariant>,
+    ) => "SUCCESS";
+    rule_vault_consistency_process_redeem_shares: || run(
+        redeem_shares,
+        base_process_redeem_shares::<VaultConsistencyInvariant>,
+    ) => "SUCCESS";
+    rule_vault_consistency_process_update_reward: || run(
+        update_reward,
+        base_process_update_reward::<VaultConsistencyInvariant>,
+    ) => "SUCCESS";
+    rule_vault_consistency_process_slash: || run(
+        slash,
+        base_processor_slash::<VaultConsistencyInvariant>,
+    ) => "SUCCESS";
+    rule_vault_consistency_process_deposit_with_fee: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee::<VaultConsistencyInvariant>,
+    ) => "SUCCESS";
+    rule_vault_consistency_process_deposit_exact: || run(
+        deposit,
+        base_process_deposit_exact::<VaultConsistencyInvariant>,
+    ) => "SUCCESS";
+    rule_vault_consistency_process_deposit_with_fee_exact: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee_exact::<VaultConsistencyInvariant>,
+    ) => "FAIL";
+    rule_vault_consistency_process_collect_fee: || run(
+        collect_fee,
+        base_process_collect_fee::<VaultConsistencyInvariant>,
+    ) => "FAIL";
+
+    rule_fees_assessed_process_deposit: || run(
+        deposit,
+        base_process_deposit::<FeeAssessedProcessor>,
+    ) => "FAIL";
+    rule_fees_assessed_process_deposit_with_fee: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee::<FeeAssessedProcessor>,
+    ) => "SUCCESS";
+    rule_fees_assessed_process_deposit_exact: || run(
+        deposit,
+        base_process_deposit_exact::<FeeAssessedProcessor>,
+    ) => "FAIL";
+    rule_fees_assessed_process_deposit_with_fee_exact: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee_exact::<FeeAssessedProcessor>,
+    ) => "FAIL";
+    rule_fees_assessed_process_redeem_shares: || run(
+        redeem_shares,
+        base_process_redeem_shares::<FeeAssessedProcessor>,
+    ) => "FAIL";
+    rule_fees_assessed_process_update_reward: || run(
+        update_reward,
+        base_process_update_reward::<FeeAssessedProcessor>,
+    ) => "SUCCESS";
+    rule_fees_assessed_process_slash: || run(
+        slash,
+        base_processor_slash::<FeeAssessedProcessor>,
+    ) => "FAIL";
+    rule_fees_assessed_process_collect_fee: || run(
+        collect_fee,
+        base_process_collect_fee::<FeeAssessedProcessor>,
+    ) => "SUCCESS";
+
+    rule_exact_deposit_process_deposit_exact: || run(
+        deposit,
+        base_process_deposit_exact::<ExactDepositProp>,
+    ) => "SUCCESS";
+    rule_exact_deposit_process_deposit_with_fee_exact: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee_exact::<ExactDepositProp>,
+    ) => "SUCCESS";
+    rule_exact_deposit_process_deposit: || run(
+        deposit,
+        base_process_deposit::<ExactDepositProp>,
+    ) => "FAIL";
+    rule_exact_deposit_process_deposit_with_fee: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee::<ExactDepositProp>,
+    ) => "FAIL";
+
+    rule_access_control_process_deposit: || run(
+        deposit,
+        base_process_deposit::<AccessControlProcessDeposit>,
+    ) => "SUCCESS";
+
+    rule_solvency_operation_pair: base_sequence::<SolvencyInvariant, 2> => "SUCCESS";
+    rule_solvency_operation_sequence:
+        base_sequence::<SolvencyInvariant, SEQUENCE_LENGTH> => "SUCCESS";
+    rule_no_dilution_operation_pair: base_sequence::<NoDilutionProp, 2> => "FAIL";
+    rule_round_trip_deposit_redeem: || base_round_trip_deposit_redeem(
+        |amount| VaultOperation::Deposit { amount },
+    ) => "SUCCESS";
+    rule_round_trip_deposit_exact_redeem: || base_round_trip_deposit_redeem(
+        |amount| VaultOperation::DepositExact { amount },
+    ) => "SUCCESS";
+    rule_round_trip_deposit_with_fee_redeem: || base_round_trip_deposit_redeem(
+        |amount| VaultOperation::DepositWithFee { amount },
+    ) => "SUCCESS";
+    rule_round_trip_deposit_with_fee_exact_redeem: || base_round_trip_deposit_redeem(
+        |amount| VaultOperation::DepositWithFeeExact { amount },
+    ) => "SUCCESS";
+    rule_round_trip_redeem_deposit: base_round_trip_redeem_deposit => "SUCCESS";
+    rule_round_trip_after_operation: base_round_trip_after_operation => "SUCCESS";
+    rule_reward_sandwich: base_reward_sandwich => "SUCCESS";
+
+    rule_ledger_shares_process_deposit: || run(
+        deposit,
+        base_process_deposit::<LedgerSharesProp>,
+    ) => "SUCCESS";
+    rule_ledger_shares_process_deposit_with_fee: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee::<LedgerSharesProp>,
+    ) => "SUCCESS";
+    rule_ledger_shares_process_deposit_exact: || run(
+        deposit,
+        base_process_deposit_exact::<LedgerSharesProp>,
+    ) => "SUCCESS";
+    rule_ledger_shares_process_deposit_with_fee_exact: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee_exact::<LedgerSharesProp>,
+    ) => "FAIL";
+    rule_ledger_shares_process_redeem_shares: || run(
+        redeem_shares,
+        base_process_redeem_shares::<LedgerSharesProp>,
+    ) => "SUCCESS";
+    rule_ledger_shares_process_update_reward: || run(
+        update_reward,
+        base_process_update_reward::<LedgerSharesProp>,
+    ) => "SUCCESS";
+    rule_ledger_shares_process_slash: || run(
+        slash,
+        base_processor_slash::<LedgerSharesProp>,
+    ) => "SUCCESS";
+    rule_ledger_shares_process_collect_fee: || run(
+        collect_fee,
+        base_process_collect_fee::<LedgerSharesProp>,
+    ) => "SUCCESS";
+
+    rule_ledger_ownership_process_deposit: || run(
+        deposit,
+        base_process_deposit::<LedgerOwnershipProp>,
+    ) => "SUCCESS";
+    rule_ledger_ownership_process_deposit_with_fee: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee::<LedgerOwnershipProp>,
+    ) => "SUCCESS";
+    rule_ledger_ownership_process_deposit_exact: || run(
+        deposit,
+        base_process_deposit_exact::<LedgerOwnershipProp>,
+    ) => "SUCCESS";
+    rule_ledger_ownership_process_deposit_with_fee_exact: || run(
+        deposit_with_fee,
+        base_process_deposit_with_fee_exact::<LedgerOwnershipProp>,
+    ) => "SUCCESS";
+    rule_ledger_ownership_process_redeem_shares: || run(
+        redeem_shares,
+        base_process_redeem_shares::<LedgerOwnershipProp>,
+    ) => "SUCCESS";
+    rule_ledger_ownership_process_update_reward: || run(
+        update_reward,
+        base_process_update_reward::<LedgerOwnershipProp>,
+    ) => "SUCCESS";
+    rule_ledger_ownership_process_slash: || run(
+        slash,
+        base_processor_slash::<LedgerOwnershipProp>,
+    ) => "SUCCESS";
+    rule_ledger_ownership_process_collect_fee: || run(
+        collect_fee,
+        base_process_collect_fee::<LedgerOwnershipProp>,
+    ) => "SUCCESS";
+
+    rule_ledger_redeem_pro_rata: || run(
+        redeem_shares,
+        base_process_redeem_shares::<LedgerProRataProp>,
+    ) => "SUCCESS";
+}
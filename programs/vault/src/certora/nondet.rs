@@ -1,24 +1,39 @@
 use crate::certora::constants::MAX_FEE_BPS;
+use crate::certora::specs::base::vault_operations;
+use crate::operations::VaultOperation;
 /// Implementations for cvlr::nondet::Nondet trait
-use crate::state::Vault;
+use crate::state::{Vault, VaultBuilder};
 use cvlr::nondet::{nondet, nondet_with};
 use cvlr_solana::cvlr_nondet_pubkey;
 
 impl cvlr::nondet::Nondet for Vault {
     fn nondet() -> Self {
-        Self {
-            admin: cvlr_nondet_pubkey(),
-            slash_admin: cvlr_nondet_pubkey(),
-            shares_mint: cvlr_nondet_pubkey(),
-            vault_shares_mint_bump: nondet(),
-            assets_mint: cvlr_nondet_pubkey(),
-            shares: u64::nondet().into(),
-            assets: u64::nondet().into(),
-            vault_assets_account: cvlr_nondet_pubkey(),
-            vault_assets_account_bump: nondet(),
-            fee_bps: nondet_with(|x: &u64| *x <= MAX_FEE_BPS).into(),
-            fee_amount: u64::nondet().into(),
-            fee_token_account: cvlr_nondet_pubkey(),
-        }
+        VaultBuilder::new()
+            .admin(cvlr_nondet_pubkey())
+            .slash_admin(cvlr_nondet_pubkey())
+            .shares_mint(cvlr_nondet_pubkey(), nondet())
+            .assets_mint(cvlr_nondet_pubkey())
+            .shares(nondet())
+            .assets(nondet())
+            .vault_assets_account(cvlr_nondet_pubkey(), nondet())
+            .fee_bps(nondet_with(|x: &u64| *x <= MAX_FEE_BPS))
+            .fee_amount(nondet())
+            .fee_token_account(cvlr_nondet_pubkey())
+            .build()
     }
 }
+
+/// Any operation of `vault_operations!`, with a nondet amount
+macro_rules! nondet_operation {
+    (; $($suffix:ident => $harness:ident for $variant:ident { $($field:ident)? }),*) => {
+        impl cvlr::nondet::Nondet for VaultOperation {
+            fn nondet() -> Self {
+                let amount: u64 = nondet();
+                let operations = [$(VaultOperation::$variant { $($field: amount)? }),*];
+                operations[nondet::<u8>() as usize % operations.len()]
+            }
+        }
+    };
+}
+
+vault_operations!([nondet_operation]);
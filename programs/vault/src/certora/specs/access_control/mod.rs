@@ -1,2 +1,3 @@
+#[cfg(feature = "certora")]
 pub mod access_control;
 pub mod props;
@@ -1,9 +1,9 @@
-use crate::certora::specs::base_processor::CvlrProp;
-use crate::state::Vault;
+use crate::certora::specs::base_processor::{
+    AccountsSnapshot, CvlrProp, ProcessParams, TokenDeltas,
+};
 use cvlr::cvlr_assert;
 use cvlr_solana::pubkey::Pk;
-use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
-use std::mem::size_of;
+use solana_program::pubkey::Pubkey;
 
 pub struct AccessControlProcessDeposit {
     vault_assets_account_key: Pubkey,
@@ -47,28 +47,18 @@ mod log {
 }
 
 impl CvlrProp for AccessControlProcessDeposit {
-    fn new(
-        vault_info_account: &AccountInfo,
-        vault_assets_account: &AccountInfo,
-        _vault_fee_account: Option<&AccountInfo>,
-        assets_mint: Option<&AccountInfo>,
-        shares_mint: Option<&AccountInfo>,
-        _user_assets_account: Option<&AccountInfo>,
-        _authority: Option<&AccountInfo>,
-        _user_shares_account: Option<&AccountInfo>,
-    ) -> Self {
-        let data = vault_info_account.try_borrow_data().unwrap();
-        let vault = bytemuck::from_bytes::<Vault>(&data[0..size_of::<Vault>()]);
+    fn new(accounts: &AccountsSnapshot) -> Self {
+        let vault = &accounts.vault;
 
         let vault_assets_mint_key = vault.assets_mint;
         let vault_shares_mint_key = vault.shares_mint;
         let vault_assets_account_key = vault.vault_assets_account;
 
-        cvlr::cvlr_assert!(assets_mint.is_some());
-        cvlr::cvlr_assert!(shares_mint.is_some());
-        let assets_account_key = *vault_assets_account.key;
-        let assets_mint_key = *assets_mint.unwrap().key;
-        let shares_mint_key = *shares_mint.unwrap().key;
+        cvlr::cvlr_assert!(accounts.assets_mint.is_some());
+        cvlr::cvlr_assert!(accounts.shares_mint.is_some());
+        let assets_account_key = accounts.vault_assets_account.key;
+        let assets_mint_key = accounts.assets_mint.unwrap().key;
+        let shares_mint_key = accounts.shares_mint.unwrap().key;
 
         Self {
             vault_assets_account_key,
@@ -82,7 +72,7 @@ impl CvlrProp for AccessControlProcessDeposit {
 
     fn assume_pre(&self) {}
 
-    fn check_post(&self, _old: &Self) {
+    fn check_post(&self, _old: &Self, _params: ProcessParams, _deltas: TokenDeltas) {
         cvlr_assert!(self.vault_assets_account_key == self.assets_account_key);
         cvlr_assert!(self.vault_assets_mint_key == self.assets_mint_key);
         cvlr_assert!(self.vault_shares_mint_key == self.shares_mint_key);
@@ -15,103 +15,140 @@ pub trait CvlrProp: CvlrLog {
     fn check_post(&self, old: &Self, params: OperationParams, effect: VaultEffect);
 }
 
+/// Check `C` across the operation `op` builds from a nondet amount
 #[inline(always)]
-pub fn base_deposit_assets<C: CvlrProp>() {
+pub fn base_operation<C: CvlrProp>(op: impl FnOnce(u64) -> VaultOperation) {
     let mut vault: Vault = nondet();
     let pre = C::new(&vault);
     pre.assume_pre();
 
-    let token_amount = nondet();
-    let effect = vault_deposit_assets(&mut vault, token_amount).unwrap();
+    let amount = nondet();
+    clog!(amount);
+    let effect = op(amount).apply(&mut vault).unwrap();
 
     let post = C::new(&vault);
     clog!(pre, post);
-    post.check_post(
-        &pre,
-        OperationParams {
-            amount: token_amount,
-        },
-        effect,
-    );
+    post.check_post(&pre, OperationParams { amount }, effect);
 }
 
 #[inline(always)]
-pub fn base_deposit_assets_with_fee<C: CvlrProp>() {
-    let mut vault: Vault = nondet();
-    let pre = C::new(&vault);
-    pre.assume_pre();
-
-    let token_amount = nondet();
-    clog!(token_amount);
-    let effect = vault_deposit_assets_with_fee(&mut vault, token_amount).unwrap();
+pub fn base_deposit_assets<C: CvlrProp>() {
+    base_operation::<C>(|amount| VaultOperation::Deposit { amount })
+}
 
-    let post = C::new(&vault);
-    clog!(pre, post);
-    post.check_post(
-        &pre,
-        OperationParams {
-            amount: token_amount,
-        },
-        effect,
-    );
+#[inline(always)]
+pub fn base_deposit_assets_exact<C: CvlrProp>() {
+    base_operation::<C>(|amount| VaultOperation::DepositExact { amount })
 }
 
 #[inline(always)]
-pub fn base_redeem_shares<C: CvlrProp>() {
-    let mut vault: Vault = nondet();
-    let pre = C::new(&vault);
-    pre.assume_pre();
+pub fn base_deposit_assets_with_fee<C: CvlrProp>() {
+    base_operation::<C>(|amount| VaultOperation::DepositWithFee { amount })
+}
 
-    let shares_amount = nondet();
-    let effect = vault_redeem_shares(&mut vault, shares_amount).unwrap();
+#[inline(always)]
+pub fn base_deposit_assets_with_fee_exact<C: CvlrProp>() {
+    base_operation::<C>(|amount| VaultOperation::DepositWithFeeExact { amount })
+}
 
-    let post = C::new(&vault);
-    clog!(pre, post);
-    post.check_post(
-        &pre,
-        OperationParams {
-            amount: shares_amount,
-        },
-        effect,
-    );
+#[inline(always)]
+pub fn base_redeem_shares<C: CvlrProp>() {
+    base_operation::<C>(|shares| VaultOperation::Redeem { shares })
 }
 
 #[inline(always)]
 pub fn base_update_reward<C: CvlrProp>() {
-    let mut vault: Vault = nondet();
-    let pre = C::new(&vault);
-    pre.assume_pre();
+    base_operation::<C>(|balance| VaultOperation::UpdateReward { balance })
+}
 
-    let token_amount = nondet();
-    let effect = vault_update_reward(&mut vault, token_amount).unwrap();
+#[inline(always)]
+pub fn base_process_slash<C: CvlrProp>() {
+    base_operation::<C>(|amount| VaultOperation::Slash { amount })
+}
 
-    let post = C::new(&vault);
-    clog!(pre, post);
-    post.check_post(
-        &pre,
-        OperationParams {
-            amount: token_amount,
-        },
-        effect,
-    );
+#[inline(always)]
+pub fn base_collect_fee<C: CvlrProp>() {
+    base_operation::<C>(|_| VaultOperation::CollectFee)
 }
 
+/// Check `C` across each of `N` nondet operations run in sequence on one
+/// vault, every step starting where the previous one left the vault
 #[inline(always)]
-pub fn base_process_slash<C: CvlrProp>() {
+pub fn base_sequence<C: CvlrProp, const N: usize>() {
     let mut vault: Vault = nondet();
-    let pre = C::new(&vault);
+    let mut pre = C::new(&vault);
     pre.assume_pre();
 
-    let token_amount = nondet();
-    let effect = vault_process_slash(&mut vault, token_amount).unwrap();
+    for _ in 0..N {
+        let op: VaultOperation = nondet();
+        let amount = op.amount().unwrap_or(0);
+        clog!(amount);
+        let effect = op.apply(&mut vault).unwrap();
+
+        let post = C::new(&vault);
+        clog!(pre, post);
+        post.check_post(&pre, OperationParams { amount }, effect);
+        pre = post;
+    }
+}
 
-    let post = C::new(&vault);
-    clog!(pre, post);
-    post.check_post(
-        &pre,
-        OperationParams {
-            amount: token_amount,
-        },
-        effect,
-    );
+/// Call `$callback!` with every vault operation as
+/// `rule suffix => harness for VaultOperation variant { amount field }`
+///
+/// `operation_rule_suffix` matches on the variants listed here, so a new
+/// `VaultOperation` does not compile until it has an entry, and every
+/// property using `operation_rules!` then checks it.
+macro_rules! vault_operations {
+    ([$($callback:tt)*] $($args:tt)*) => {
+        $($callback)*!($($args)*;
+            deposit_assets => base_deposit_assets for Deposit { amount },
+            deposit_assets_exact => base_deposit_assets_exact for DepositExact { amount },
+            deposit_assets_with_fee => base_deposit_assets_with_fee for DepositWithFee { amount },
+            deposit_assets_with_fee_exact =>
+                base_deposit_assets_with_fee_exact for DepositWithFeeExact { amount },
+            redeem_shares => base_redeem_shares for Redeem { shares },
+            update_reward => base_update_reward for UpdateReward { balance },
+            slash => base_process_slash for Slash { amount },
+            collect_fee => base_collect_fee for CollectFee {}
+        );
+    };
+}
+pub(crate) use vault_operations;
+
+/// A rule `<prefix>_<suffix>` checking `$prop` for every vault operation
+///
+/// `operation_rules!(rule_solvency, SolvencyInvariant)` generates
+/// `rule_solvency_deposit_assets`, `rule_solvency_redeem_shares`, and so on.
+#[cfg(feature = "certora")]
+macro_rules! operation_rules {
+    ($prefix:ident, $prop:ty) => {
+        $crate::certora::specs::base::vault_operations!(
+            [$crate::certora::specs::base::operation_rules] @rules $prefix, $prop
+        );
+    };
+    (@rules $prefix:ident, $prop:ty; $($suffix:ident => $harness:ident for $variant:ident { $($field:ident)? }),*) => {
+        ::paste::paste! {
+            $(
+                #[rule]
+                pub fn [<$prefix _ $suffix>]() {
+                    $crate::certora::specs::base::$harness::<$prop>();
+                }
+            )*
+        }
+    };
 }
+#[cfg(feature = "certora")]
+pub(crate) use operation_rules;
+
+macro_rules! rule_suffix_fn {
+    (; $($suffix:ident => $harness:ident for $variant:ident { $($field:ident)? }),*) => {
+        /// Suffix of the rules `operation_rules!` generates for `op`
+        pub fn operation_rule_suffix(op: &VaultOperation) -> &'static str {
+            match op {
+                $(VaultOperation::$variant { .. } => stringify!($suffix),)*
+            }
+        }
+    };
+}
+
+vault_operations!([rule_suffix_fn]);
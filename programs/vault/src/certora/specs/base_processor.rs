@@ -1,305 +1,342 @@
-use crate::processor::{
-    process_deposit, process_deposit_exact, process_deposit_with_fee,
-    process_deposit_with_fee_exact, process_redeem_shares, process_slash, process_update_reward,
-};
+use crate::certora::ghost;
+use crate::certora::mocks::processor::CvlrTokenBackend;
+use crate::instruction::VaultInstruction;
+use crate::processor::process_vault_instruction;
+use crate::state::Vault;
 use cvlr::clog;
 use cvlr::log::CvlrLog;
+use cvlr::mathint::NativeInt;
 use cvlr::nondet;
 use solana_program::account_info::{next_account_info, AccountInfo};
-
-pub trait CvlrProp: CvlrLog {
-    fn new(
-        vault_info: &AccountInfo,
-        vault_assets_account: &AccountInfo,
-        vault_fee_account: Option<&AccountInfo>,
-        assets_mint: Option<&AccountInfo>,
-        shares_mint: Option<&AccountInfo>,
-        user_assets_account: Option<&AccountInfo>,
-        authority: Option<&AccountInfo>,
-        user_shares_account: Option<&AccountInfo>,
-    ) -> Self;
-    fn assume_pre(&self);
-    fn check_post(&self, old: &Self);
+use solana_program::pubkey::Pubkey;
+use std::mem::size_of;
+
+/// Accounts of an instruction by role, taken in the order of its loader
+/// context; `None` for the roles the instruction does not take
+#[derive(Clone, Copy)]
+pub struct InstructionAccounts<'a, 'info> {
+    pub vault_info: &'a AccountInfo<'info>,
+    pub vault_assets_account: &'a AccountInfo<'info>,
+    pub vault_fee_account: Option<&'a AccountInfo<'info>>,
+    pub assets_mint: Option<&'a AccountInfo<'info>>,
+    pub shares_mint: Option<&'a AccountInfo<'info>>,
+    /// Token account the assets move to or from: the user assets account of
+    /// deposits and redemptions, the slashed account of `Slash` and the fee
+    /// collect account of `CollectFee`
+    pub user_assets_account: Option<&'a AccountInfo<'info>>,
+    pub authority: Option<&'a AccountInfo<'info>>,
+    pub user_shares_account: Option<&'a AccountInfo<'info>>,
 }
 
-#[inline(always)]
-pub fn base_process_deposit<C: CvlrProp>(accounts: &[AccountInfo]) {
-    let iter = &mut accounts.iter();
-    let vault_info = next_account_info(iter).unwrap();
-    let vault_assets_account = next_account_info(iter).unwrap();
-    let assets_mint = next_account_info(iter).unwrap();
-    let shares_mint = next_account_info(iter).unwrap();
-    let user_assets_account = next_account_info(iter).unwrap();
-    let authority = next_account_info(iter).unwrap();
-    let user_shares_account = next_account_info(iter).unwrap();
+/// Token account of an instruction, as read before or after it runs
+#[derive(Clone, Copy)]
+pub struct TokenAccountSnapshot {
+    pub key: Pubkey,
+    pub amount: u64,
+}
 
-    let pre = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
-    pre.assume_pre();
+#[derive(Clone, Copy)]
+pub struct MintSnapshot {
+    pub key: Pubkey,
+    pub supply: u64,
+}
 
-    let amount = nondet();
-    process_deposit(accounts, amount).unwrap();
+/// State of the accounts of an instruction, by role
+#[derive(Clone, Copy)]
+pub struct AccountsSnapshot {
+    pub vault_key: Pubkey,
+    pub vault: Vault,
+    pub vault_assets_account: TokenAccountSnapshot,
+    pub vault_fee_account: Option<TokenAccountSnapshot>,
+    pub assets_mint: Option<MintSnapshot>,
+    pub shares_mint: Option<MintSnapshot>,
+    pub user_assets_account: Option<TokenAccountSnapshot>,
+    pub authority: Option<Pubkey>,
+    pub user_shares_account: Option<TokenAccountSnapshot>,
+}
 
-    let post = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
+pub struct ProcessParams {
+    pub instruction: VaultInstruction,
+    // it can be either tokens or shares depending on the instruction, and
+    // zero if the instruction takes no amount
+    pub amount: u64,
+}
 
-    clog!(pre, post);
-    post.check_post(&pre);
+/// Change of the token balances over an instruction, `post - pre`; `None`
+/// for the roles the instruction does not take
+#[derive(Clone, Copy)]
+pub struct TokenDeltas {
+    pub vault_assets: NativeInt,
+    pub vault_fee: Option<NativeInt>,
+    pub user_assets: Option<NativeInt>,
+    pub user_shares: Option<NativeInt>,
+    pub shares_supply: Option<NativeInt>,
 }
 
-#[inline(always)]
-pub fn base_process_deposit_with_fee<C: CvlrProp>(accounts: &[AccountInfo]) {
-    let iter = &mut accounts.iter();
-    let vault_info = next_account_info(iter).unwrap();
-    let vault_assets_account = next_account_info(iter).unwrap();
-    let vault_fee_account = next_account_info(iter).unwrap();
-    let assets_mint = next_account_info(iter).unwrap();
-    let shares_mint = next_account_info(iter).unwrap();
-    let user_assets_account = next_account_info(iter).unwrap();
-    let authority = next_account_info(iter).unwrap();
-    let user_shares_account = next_account_info(iter).unwrap();
+pub trait CvlrProp: CvlrLog {
+    /// Set up the ghost state the property reads, before the instruction
+    /// runs
+    fn init_ghosts(_accounts: &AccountsSnapshot) {}
+    fn new(accounts: &AccountsSnapshot) -> Self;
+    fn assume_pre(&self);
+    fn check_post(&self, old: &Self, params: ProcessParams, deltas: TokenDeltas);
+}
 
-    let pre = C::new(
-        vault_info,
-        vault_assets_account,
-        Some(vault_fee_account),
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
-    pre.assume_pre();
+impl<'a, 'info> InstructionAccounts<'a, 'info> {
+    pub fn new(instruction: &VaultInstruction, accounts: &'a [AccountInfo<'info>]) -> Self {
+        let iter = &mut accounts.iter();
+        let mut next = || next_account_info(iter).unwrap();
+        let mut roles = Self {
+            vault_info: next(),
+            vault_assets_account: next(),
+            vault_fee_account: None,
+            assets_mint: None,
+            shares_mint: None,
+            user_assets_account: None,
+            authority: None,
+            user_shares_account: None,
+        };
+        match instruction {
+            VaultInstruction::Deposit { .. }
+            | VaultInstruction::DepositExact { .. }
+            | VaultInstruction::DepositWithFee { .. }
+            | VaultInstruction::DepositWithFeeExact { .. } => {
+                if matches!(
+                    instruction,
+                    VaultInstruction::DepositWithFee { .. }
+                        | VaultInstruction::DepositWithFeeExact { .. }
+                ) {
+                    roles.vault_fee_account = Some(next());
+                }
+                roles.assets_mint = Some(next());
+                roles.shares_mint = Some(next());
+                roles.user_assets_account = Some(next());
+                roles.authority = Some(next());
+                roles.user_shares_account = Some(next());
+            }
+            VaultInstruction::RedeemShares { .. } => {
+                roles.assets_mint = Some(next());
+                roles.shares_mint = Some(next());
+                roles.user_shares_account = Some(next());
+                roles.authority = Some(next());
+                roles.user_assets_account = Some(next());
+            }
+            VaultInstruction::UpdateReward => {}
+            VaultInstruction::Slash { .. } => {
+                roles.user_assets_account = Some(next());
+                roles.assets_mint = Some(next());
+                roles.authority = Some(next());
+            }
+            VaultInstruction::CollectFee => {
+                roles.assets_mint = Some(next());
+                roles.user_assets_account = Some(next());
+                roles.authority = Some(next());
+            }
+            VaultInstruction::CreateVault | VaultInstruction::MigrateVault => {
+                unreachable!("no vault operation")
+            }
+        }
+        roles
+    }
+
+    pub fn snapshot(&self) -> AccountsSnapshot {
+        let vault = {
+            let data = self.vault_info.try_borrow_data().unwrap();
+            *bytemuck::from_bytes::<Vault>(&data[0..size_of::<Vault>()])
+        };
+        AccountsSnapshot {
+            vault_key: *self.vault_info.key,
+            vault,
+            vault_assets_account: token_account(self.vault_assets_account),
+            vault_fee_account: self.vault_fee_account.map(token_account),
+            assets_mint: self.assets_mint.map(mint),
+            shares_mint: self.shares_mint.map(mint),
+            user_assets_account: self.user_assets_account.map(token_account),
+            authority: self.authority.map(|authority| *authority.key),
+            user_shares_account: self.user_shares_account.map(token_account),
+        }
+    }
+}
 
-    let amount = nondet();
-    process_deposit_with_fee(accounts, amount).unwrap();
+fn token_account(info: &AccountInfo) -> TokenAccountSnapshot {
+    TokenAccountSnapshot {
+        key: *info.key,
+        amount: cvlr_solana::token::spl_token_account_get_amount(info),
+    }
+}
 
-    let post = C::new(
-        vault_info,
-        vault_assets_account,
-        Some(vault_fee_account),
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
+fn mint(info: &AccountInfo) -> MintSnapshot {
+    MintSnapshot {
+        key: *info.key,
+        supply: cvlr_solana::token::spl_mint_get_supply(info),
+    }
+}
 
-    clog!(pre, post);
-    post.check_post(&pre);
+impl TokenDeltas {
+    pub fn new(pre: &AccountsSnapshot, post: &AccountsSnapshot) -> Self {
+        let delta = |pre: u64, post: u64| NativeInt::from(post) - NativeInt::from(pre);
+        let account_delta = |pre: Option<TokenAccountSnapshot>,
+                             post: Option<TokenAccountSnapshot>| {
+            pre.zip(post)
+                .map(|(pre, post)| delta(pre.amount, post.amount))
+        };
+        Self {
+            vault_assets: delta(
+                pre.vault_assets_account.amount,
+                post.vault_assets_account.amount,
+            ),
+            vault_fee: account_delta(pre.vault_fee_account, post.vault_fee_account),
+            user_assets: account_delta(pre.user_assets_account, post.user_assets_account),
+            user_shares: account_delta(pre.user_shares_account, post.user_shares_account),
+            shares_supply: pre
+                .shares_mint
+                .zip(post.shares_mint)
+                .map(|(pre, post)| delta(pre.supply, post.supply)),
+        }
+    }
 }
 
+/// Check `C` across `instruction` run on `accounts`
 #[inline(always)]
-pub fn base_process_deposit_exact<C: CvlrProp>(accounts: &[AccountInfo]) {
-    let iter = &mut accounts.iter();
-    let vault_info = next_account_info(iter).unwrap();
-    let vault_assets_account = next_account_info(iter).unwrap();
-    let assets_mint = next_account_info(iter).unwrap();
-    let shares_mint = next_account_info(iter).unwrap();
-    let user_assets_account = next_account_info(iter).unwrap();
-    let authority = next_account_info(iter).unwrap();
-    let user_shares_account = next_account_info(iter).unwrap();
-
-    let pre = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
+pub fn base_process<C: CvlrProp>(accounts: &[AccountInfo], instruction: VaultInstruction) {
+    let roles = InstructionAccounts::new(&instruction, accounts);
+    let pre_accounts = roles.snapshot();
+    ghost::clear();
+    C::init_ghosts(&pre_accounts);
+    let pre = C::new(&pre_accounts);
     pre.assume_pre();
 
-    let amount = nondet();
-    process_deposit_exact(accounts, amount).unwrap();
+    let amount = instruction.amount().unwrap_or(0);
+    clog!(amount);
+    process_vault_instruction::<CvlrTokenBackend>(accounts, instruction).unwrap();
 
-    let post = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
+    let post_accounts = roles.snapshot();
+    let post = C::new(&post_accounts);
 
     clog!(pre, post);
-    post.check_post(&pre);
+    let params = ProcessParams {
+        instruction,
+        amount,
+    };
+    post.check_post(
+        &pre,
+        params,
+        TokenDeltas::new(&pre_accounts, &post_accounts),
+    );
 }
 
 #[inline(always)]
-pub fn base_process_deposit_with_fee_exact<C: CvlrProp>(accounts: &[AccountInfo]) {
-    let iter = &mut accounts.iter();
-    let vault_info = next_account_info(iter).unwrap();
-    let vault_assets_account = next_account_info(iter).unwrap();
-    let vault_fee_account = next_account_info(iter).unwrap();
-    let assets_mint = next_account_info(iter).unwrap();
-    let shares_mint = next_account_info(iter).unwrap();
-    let user_assets_account = next_account_info(iter).unwrap();
-    let authority = next_account_info(iter).unwrap();
-    let user_shares_account = next_account_info(iter).unwrap();
-
-    let pre = C::new(
-        vault_info,
-        vault_assets_account,
-        Some(vault_fee_account),
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
-    pre.assume_pre();
+pub fn base_process_deposit<C: CvlrProp>(accounts: &[AccountInfo]) {
+    base_process::<C>(accounts, VaultInstruction::Deposit { amount: nondet() })
+}
 
-    let amount = nondet();
-    process_deposit_with_fee_exact(accounts, amount).unwrap();
+#[inline(always)]
+pub fn base_process_deposit_with_fee<C: CvlrProp>(accounts: &[AccountInfo]) {
+    base_process::<C>(
+        accounts,
+        VaultInstruction::DepositWithFee { amount: nondet() },
+    )
+}
 
-    let post = C::new(
-        vault_info,
-        vault_assets_account,
-        Some(vault_fee_account),
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
+#[inline(always)]
+pub fn base_process_deposit_exact<C: CvlrProp>(accounts: &[AccountInfo]) {
+    base_process::<C>(
+        accounts,
+        VaultInstruction::DepositExact { amount: nondet() },
+    )
+}
 
-    clog!(pre, post);
-    post.check_post(&pre);
+#[inline(always)]
+pub fn base_process_deposit_with_fee_exact<C: CvlrProp>(accounts: &[AccountInfo]) {
+    base_process::<C>(
+        accounts,
+        VaultInstruction::DepositWithFeeExact { amount: nondet() },
+    )
 }
 
 #[inline(always)]
 pub fn base_process_redeem_shares<C: CvlrProp>(accounts: &[AccountInfo]) {
-    let iter = &mut accounts.iter();
-    let vault_info = next_account_info(iter).unwrap();
-    let vault_assets_account = next_account_info(iter).unwrap();
-    let assets_mint = next_account_info(iter).unwrap();
-    let shares_mint = next_account_info(iter).unwrap();
-    let user_assets_account = next_account_info(iter).unwrap();
-    let authority = next_account_info(iter).unwrap();
-    let user_shares_account = next_account_info(iter).unwrap();
-
-    let pre = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
-
-    pre.assume_pre();
-
-    let amount = nondet();
-    process_redeem_shares(accounts, amount).unwrap();
-
-    let post = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        Some(assets_mint),
-        Some(shares_mint),
-        Some(user_assets_account),
-        Some(authority),
-        Some(user_shares_account),
-    );
-
-    clog!(pre, post);
-    post.check_post(&pre);
+    base_process::<C>(
+        accounts,
+        VaultInstruction::RedeemShares { amount: nondet() },
+    )
 }
 
 #[inline(always)]
 pub fn base_process_update_reward<C: CvlrProp>(accounts: &[AccountInfo]) {
-    let iter = &mut accounts.iter();
-    let vault_info = next_account_info(iter).unwrap();
-    let vault_assets_account = next_account_info(iter).unwrap();
-
-    let pre = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-    );
-    pre.assume_pre();
-
-    process_update_reward(accounts).unwrap();
-
-    let post = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-    );
-
-    clog!(pre, post);
-    post.check_post(&pre);
+    base_process::<C>(accounts, VaultInstruction::UpdateReward)
 }
 
 #[inline(always)]
 pub fn base_process_slash<C: CvlrProp>(accounts: &[AccountInfo]) {
-    let iter = &mut accounts.iter();
-    let vault_info = next_account_info(iter).unwrap();
-    let vault_assets_account = next_account_info(iter).unwrap();
-    let user_assets_account = next_account_info(iter).unwrap();
-    let assets_mint = next_account_info(iter).unwrap();
-    let authority = next_account_info(iter).unwrap();
-
-    let pre = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        Some(assets_mint),
-        None,
-        Some(user_assets_account),
-        Some(authority),
-        None,
-    );
-    pre.assume_pre();
-
-    let amount = nondet();
-    process_slash(accounts, amount).unwrap();
+    base_process::<C>(accounts, VaultInstruction::Slash { amount: nondet() })
+}
 
-    let post = C::new(
-        vault_info,
-        vault_assets_account,
-        None,
-        Some(assets_mint),
-        None,
-        Some(user_assets_account),
-        Some(authority),
-        None,
-    );
+#[inline(always)]
+pub fn base_process_collect_fee<C: CvlrProp>(accounts: &[AccountInfo]) {
+    base_process::<C>(accounts, VaultInstruction::CollectFee)
+}
 
-    clog!(pre, post);
-    post.check_post(&pre);
+/// Call `$callback!` with every instruction running a vault operation as
+/// `rule suffix => harness for VaultInstruction variant`
+///
+/// As with `vault_operations!`, `processor_rule_suffix` makes a new
+/// `VaultInstruction` fail to compile until it has an entry here.
+macro_rules! vault_instructions {
+    ([$($callback:tt)*] $($args:tt)*) => {
+        $($callback)*!($($args)*;
+            deposit => base_process_deposit for Deposit,
+            deposit_with_fee => base_process_deposit_with_fee for DepositWithFee,
+            deposit_exact => base_process_deposit_exact for DepositExact,
+            deposit_with_fee_exact => base_process_deposit_with_fee_exact for DepositWithFeeExact,
+            redeem_shares => base_process_redeem_shares for RedeemShares,
+            update_reward => base_process_update_reward for UpdateReward,
+            slash => base_process_slash for Slash,
+            collect_fee => base_process_collect_fee for CollectFee
+        );
+    };
+}
+#[cfg(feature = "certora")]
+pub(crate) use vault_instructions;
+
+/// A rule `<prefix>_<suffix>` checking `$prop` for every instruction running
+/// a vault operation, on nondet accounts
+///
+/// `processor_rules!(rule_solvency_process, SolvencyInvariant)` generates
+/// `rule_solvency_process_deposit`, `rule_solvency_process_slash`, and so on.
+#[cfg(feature = "certora")]
+macro_rules! processor_rules {
+    ($prefix:ident, $prop:ty) => {
+        $crate::certora::specs::base_processor::vault_instructions!(
+            [$crate::certora::specs::base_processor::processor_rules] @rules $prefix, $prop
+        );
+    };
+    (@rules $prefix:ident, $prop:ty; $($suffix:ident => $harness:ident for $variant:ident),*) => {
+        ::paste::paste! {
+            $(
+                #[rule]
+                pub fn [<$prefix _ $suffix>]() {
+                    let accs = ::cvlr_solana::cvlr_deserialize_nondet_accounts();
+                    $crate::certora::specs::base_processor::$harness::<$prop>(&accs);
+                }
+            )*
+        }
+    };
 }
+#[cfg(feature = "certora")]
+pub(crate) use processor_rules;
+
+macro_rules! rule_suffix_fn {
+    (; $($suffix:ident => $harness:ident for $variant:ident),*) => {
+        /// Suffix of the rules `processor_rules!` generates for `ix`; `None`
+        /// for the instructions without a vault operation
+        pub fn processor_rule_suffix(ix: &VaultInstruction) -> Option<&'static str> {
+            match ix {
+                $(VaultInstruction::$variant { .. } => Some(stringify!($suffix)),)*
+                VaultInstruction::CreateVault | VaultInstruction::MigrateVault => None,
+            }
+        }
+    };
+}
+
+vault_instructions!([rule_suffix_fn]);
@@ -0,0 +1,33 @@
+use crate::certora::specs::base_processor::{
+    base_process_deposit, base_process_deposit_exact, base_process_deposit_with_fee,
+    base_process_deposit_with_fee_exact,
+};
+use crate::certora::specs::exact_deposit::props_processor::ExactDepositProp;
+use cvlr::prelude::*;
+use cvlr_solana::cvlr_deserialize_nondet_accounts;
+
+#[rule]
+pub fn rule_exact_deposit_process_deposit_exact() {
+    let accs = cvlr_deserialize_nondet_accounts();
+    base_process_deposit_exact::<ExactDepositProp>(&accs);
+}
+
+#[rule]
+pub fn rule_exact_deposit_process_deposit_with_fee_exact() {
+    let accs = cvlr_deserialize_nondet_accounts();
+    base_process_deposit_with_fee_exact::<ExactDepositProp>(&accs);
+}
+
+#[rule]
+/// It should produce a counterexample: a deposit keeps the whole amount,
+/// including what the rounded down shares are not worth
+pub fn rule_exact_deposit_process_deposit() {
+    let accs = cvlr_deserialize_nondet_accounts();
+    base_process_deposit::<ExactDepositProp>(&accs);
+}
+
+#[rule]
+pub fn rule_exact_deposit_process_deposit_with_fee() {
+    let accs = cvlr_deserialize_nondet_accounts();
+    base_process_deposit_with_fee::<ExactDepositProp>(&accs);
+}
@@ -0,0 +1,3 @@
+#[cfg(feature = "certora")]
+pub mod exact_deposit;
+pub mod props_processor;
@@ -0,0 +1,58 @@
+use crate::certora::specs::base_processor::{
+    AccountsSnapshot, CvlrProp, ProcessParams, TokenDeltas,
+};
+use cvlr::mathint::NativeInt;
+use cvlr::{cvlr_assert_eq, cvlr_assert_le, cvlr_assert_lt};
+
+pub struct ExactDepositProp {
+    shares_total: NativeInt,
+    token_total: NativeInt,
+}
+
+mod log {
+    use super::*;
+    use cvlr::log::cvlr_log_with;
+    use cvlr::log::CvlrLog;
+
+    impl CvlrLog for ExactDepositProp {
+        #[inline(always)]
+        fn log(&self, tag: &str, logger: &mut cvlr::log::CvlrLogger) {
+            logger.log_scope_start(tag);
+            cvlr_log_with("token_total", &self.token_total, logger);
+            cvlr_log_with("shares_total", &self.shares_total, logger);
+            logger.log_scope_end(tag);
+        }
+    }
+}
+
+/// An exact deposit moves into the vault no more than the deposited amount
+/// and only the assets the minted shares are worth, rounded up in favour of
+/// the vault, so that the depositor loses less than one atom to rounding.
+impl CvlrProp for ExactDepositProp {
+    fn new(accounts: &AccountsSnapshot) -> Self {
+        Self {
+            shares_total: accounts.vault.num_shares().into(),
+            token_total: accounts.vault.num_assets().into(),
+        }
+    }
+
+    fn assume_pre(&self) {}
+
+    fn check_post(&self, old: &Self, params: ProcessParams, deltas: TokenDeltas) {
+        let assets_in = deltas.vault_assets;
+        let shares_out = deltas.shares_supply.unwrap();
+        cvlr::clog!(assets_in, shares_out);
+        cvlr_assert_le!(assets_in, NativeInt::from(params.amount));
+
+        // -- a vault with as many shares as assets converts 1:1
+        if old.shares_total == old.token_total {
+            cvlr_assert_eq!(assets_in, shares_out);
+            return;
+        }
+        cvlr_assert_le!(shares_out * old.token_total, assets_in * old.shares_total);
+        cvlr_assert_lt!(
+            (assets_in - NativeInt::from(1u64)) * old.shares_total,
+            shares_out * old.token_total
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::certora::specs::base::{base_deposit_assets, base_deposit_assets_with_fee, CvlrProp};
+use crate::certora::specs::base::{operation_rules, CvlrProp};
 use crate::certora::specs::fees::props::FeeAssessedProp;
 use crate::certora::specs::solvency::props::SolvencyInvariant;
 use crate::operations::{vault_deposit_assets, vault_deposit_assets_with_fee};
@@ -6,15 +6,7 @@ use crate::state::Vault;
 use cvlr::mathint::NativeInt;
 use cvlr::prelude::*;
 
-#[rule]
-pub fn rule_fees_assessed_deposit_assets_with_fee() {
-    base_deposit_assets_with_fee::<FeeAssessedProp>();
-}
-
-#[rule]
-pub fn rule_fees_assessed_deposit_assets() {
-    base_deposit_assets::<FeeAssessedProp>();
-}
+operation_rules!(rule_fees_assessed, FeeAssessedProp);
 
 fn safe_assumptions(vault: &Vault, token_amount: u64) {
     // vault is solvent. We have proved separately that solvency is an invariant.
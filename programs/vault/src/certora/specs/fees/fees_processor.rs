@@ -0,0 +1,5 @@
+use crate::certora::specs::base_processor::processor_rules;
+use crate::certora::specs::fees::props_processor::FeeAssessedProp;
+use cvlr::prelude::*;
+
+processor_rules!(rule_fees_assessed_process, FeeAssessedProp);
@@ -1,2 +1,6 @@
+#[cfg(feature = "certora")]
 pub mod fees;
+#[cfg(feature = "certora")]
+pub mod fees_processor;
 pub mod props;
+pub mod props_processor;
@@ -0,0 +1,43 @@
+use crate::certora::specs::base_processor::{
+    AccountsSnapshot, CvlrProp, ProcessParams, TokenDeltas,
+};
+use cvlr::mathint::NativeInt;
+use cvlr::{cvlr_assert_gt, cvlr_assert_le};
+
+pub struct FeeAssessedProp {
+    fee_bps: NativeInt,
+}
+
+mod log {
+    use super::*;
+    use cvlr::log::CvlrLog;
+    impl CvlrLog for FeeAssessedProp {
+        #[inline(always)]
+        fn log(&self, _tag: &str, _logger: &mut cvlr::log::CvlrLogger) {}
+    }
+}
+
+/// property for `process_deposit_with_fee`: the fee is what the vault fee
+/// account receives, and instructions without one assess no fee. The fee
+/// rounds down, so it is only required to be positive once the amount is
+/// large enough for the fee to reach one atom
+impl CvlrProp for FeeAssessedProp {
+    fn new(accounts: &AccountsSnapshot) -> Self {
+        Self {
+            fee_bps: u64::from(accounts.vault.fee_bps).into(),
+        }
+    }
+
+    fn assume_pre(&self) {}
+
+    fn check_post(&self, _old: &Self, params: ProcessParams, deltas: TokenDeltas) {
+        let tokens_amount = NativeInt::from(params.amount);
+        let fee_bps = self.fee_bps;
+        let fee = deltas.vault_fee.unwrap_or(NativeInt::from(0u64));
+        cvlr::clog!(tokens_amount, fee_bps, fee);
+        cvlr_assert_le!(fee, tokens_amount);
+        if tokens_amount * fee_bps >= NativeInt::from(10_000u64) {
+            cvlr_assert_gt!(fee, NativeInt::from(0u64));
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::certora::mocks::processor::CvlrTokenBackend;
 use crate::processor::{process_deposit, process_redeem_shares, process_slash};
 use crate::state::Vault;
 use cvlr::mathint::NativeInt;
@@ -34,7 +35,7 @@ pub fn rule_deposit_must_increase_assets() {
     let total_assets_pre = get_vault_total_assets!(vault_info);
     let amount = nondet();
 
-    process_deposit(&accounts, amount).unwrap();
+    process_deposit::<CvlrTokenBackend>(&accounts, amount).unwrap();
 
     let total_assets_post = get_vault_total_assets!(vault_info);
     clog!(amount, total_assets_pre, total_assets_post);
@@ -49,7 +50,7 @@ pub fn rule_deposit_must_increase_shares() {
     let total_shares_pre = get_vault_total_shares!(vault_info);
     let amount = nondet();
 
-    process_deposit(&accounts, amount).unwrap();
+    process_deposit::<CvlrTokenBackend>(&accounts, amount).unwrap();
 
     let total_shares_post = get_vault_total_shares!(vault_info);
     clog!(amount, total_shares_pre, total_shares_post);
@@ -65,7 +66,7 @@ pub fn rule_deposit_assets_and_shares_monotonicity() {
     let total_shares_pre = get_vault_total_shares!(vault_info);
     let amount = nondet();
 
-    process_deposit(&accounts, amount).unwrap();
+    process_deposit::<CvlrTokenBackend>(&accounts, amount).unwrap();
 
     let total_assets_post = get_vault_total_assets!(vault_info);
     let total_shares_post = get_vault_total_shares!(vault_info);
@@ -93,7 +94,7 @@ pub fn rule_redeem_assets_and_shares_monotonicity() {
     // vault must be solvent
     cvlr_assume!(total_shares_pre <= total_assets_pre);
 
-    process_redeem_shares(&accounts, amount).unwrap();
+    process_redeem_shares::<CvlrTokenBackend>(&accounts, amount).unwrap();
 
     let total_assets_post = get_vault_total_assets!(vault_info);
     let total_shares_post = get_vault_total_shares!(vault_info);
@@ -120,7 +121,7 @@ pub fn rule_slash_no_dilution() {
     let total_shares_pre: NativeInt = get_vault_total_shares!(vault_info).into();
 
     let amount = nondet();
-    process_slash(&accounts, amount).unwrap();
+    process_slash::<CvlrTokenBackend>(&accounts, amount).unwrap();
 
     let total_assets_post: NativeInt = get_vault_total_assets!(vault_info).into();
     let total_shares_post: NativeInt = get_vault_total_shares!(vault_info).into();
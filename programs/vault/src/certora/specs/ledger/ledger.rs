@@ -0,0 +1,16 @@
+use crate::certora::specs::base_processor::{base_process_redeem_shares, processor_rules};
+use crate::certora::specs::ledger::props::{
+    LedgerOwnershipProp, LedgerProRataProp, LedgerSharesProp,
+};
+use cvlr::prelude::*;
+use cvlr_solana::cvlr_deserialize_nondet_accounts;
+
+processor_rules!(rule_ledger_shares_process, LedgerSharesProp);
+
+processor_rules!(rule_ledger_ownership_process, LedgerOwnershipProp);
+
+#[rule]
+pub fn rule_ledger_redeem_pro_rata() {
+    let accs = cvlr_deserialize_nondet_accounts();
+    base_process_redeem_shares::<LedgerProRataProp>(&accs);
+}
@@ -0,0 +1,3 @@
+#[cfg(feature = "certora")]
+pub mod ledger;
+pub mod props;
@@ -0,0 +1,146 @@
+use crate::certora::constants::LEDGER_USERS;
+use crate::certora::ghost::{self, GhostLedger, GhostUser};
+use crate::certora::specs::base_processor::{
+    AccountsSnapshot, CvlrProp, ProcessParams, TokenDeltas,
+};
+use cvlr::mathint::NativeInt;
+use cvlr::{cvlr_assert, cvlr_assert_eq, cvlr_assert_le, cvlr_assert_lt, cvlr_assume};
+use solana_program::pubkey::Pubkey;
+
+pub struct LedgerSharesProp {
+    shares_total: NativeInt,
+    ledger_shares: NativeInt,
+}
+
+pub struct LedgerOwnershipProp {
+    authority: Option<Pubkey>,
+    users: [GhostUser; LEDGER_USERS],
+}
+
+pub struct LedgerProRataProp {
+    shares_total: NativeInt,
+    token_total: NativeInt,
+    ledger_shares: NativeInt,
+    ledger_assets: NativeInt,
+}
+
+mod log {
+    use super::*;
+    use cvlr::log::cvlr_log_with;
+    use cvlr::log::CvlrLog;
+    use cvlr_solana::pubkey::Pk;
+
+    impl CvlrLog for LedgerSharesProp {
+        #[inline(always)]
+        fn log(&self, tag: &str, logger: &mut cvlr::log::CvlrLogger) {
+            logger.log_scope_start(tag);
+            cvlr_log_with("shares_total", &self.shares_total, logger);
+            cvlr_log_with("ledger_shares", &self.ledger_shares, logger);
+            logger.log_scope_end(tag);
+        }
+    }
+
+    impl CvlrLog for LedgerOwnershipProp {
+        #[inline(always)]
+        fn log(&self, tag: &str, logger: &mut cvlr::log::CvlrLogger) {
+            logger.log_scope_start(tag);
+            if let Some(authority) = &self.authority {
+                cvlr_log_with("authority", &Pk(authority), logger);
+            }
+            for user in &self.users {
+                cvlr_log_with("user", user, logger);
+            }
+            logger.log_scope_end(tag);
+        }
+    }
+
+    impl CvlrLog for LedgerProRataProp {
+        #[inline(always)]
+        fn log(&self, tag: &str, logger: &mut cvlr::log::CvlrLogger) {
+            logger.log_scope_start(tag);
+            cvlr_log_with("token_total", &self.token_total, logger);
+            cvlr_log_with("shares_total", &self.shares_total, logger);
+            cvlr_log_with("ledger_assets", &self.ledger_assets, logger);
+            cvlr_log_with("ledger_shares", &self.ledger_shares, logger);
+            logger.log_scope_end(tag);
+        }
+    }
+}
+
+/// The share balances of the users add up to the shares of the vault.
+impl CvlrProp for LedgerSharesProp {
+    fn init_ghosts(accounts: &AccountsSnapshot) {
+        ghost::init_ledger(GhostLedger::nondet_for(accounts));
+    }
+
+    fn new(accounts: &AccountsSnapshot) -> Self {
+        Self {
+            shares_total: accounts.vault.num_shares().into(),
+            ledger_shares: ghost::ledger().total_shares(),
+        }
+    }
+
+    fn assume_pre(&self) {
+        cvlr_assume!(self.ledger_shares == self.shares_total);
+    }
+
+    fn check_post(&self, _old: &Self, _params: ProcessParams, _deltas: TokenDeltas) {
+        cvlr_assert_eq!(self.ledger_shares, self.shares_total);
+    }
+}
+
+/// The vault debits the accounts of users only with the signature of the
+/// authority of the instruction, never with its own. SPL Token then requires
+/// the authority to own the account, so no user moves the shares or assets of
+/// another.
+impl CvlrProp for LedgerOwnershipProp {
+    fn init_ghosts(accounts: &AccountsSnapshot) {
+        ghost::init_ledger(GhostLedger::nondet_for(accounts));
+    }
+
+    fn new(accounts: &AccountsSnapshot) -> Self {
+        Self {
+            authority: accounts.authority,
+            users: ghost::ledger().users,
+        }
+    }
+
+    fn assume_pre(&self) {}
+
+    fn check_post(&self, _old: &Self, _params: ProcessParams, _deltas: TokenDeltas) {
+        for user in &self.users {
+            cvlr_assert!(user.unauthorized_debit.is_none());
+        }
+    }
+}
+
+/// The users receive the assets the shares they burn are worth, rounded
+/// down.
+impl CvlrProp for LedgerProRataProp {
+    fn init_ghosts(accounts: &AccountsSnapshot) {
+        ghost::init_ledger(GhostLedger::nondet_for(accounts));
+    }
+
+    fn new(accounts: &AccountsSnapshot) -> Self {
+        let ledger = ghost::ledger();
+        Self {
+            shares_total: accounts.vault.num_shares().into(),
+            token_total: accounts.vault.num_assets().into(),
+            ledger_shares: ledger.total_shares(),
+            ledger_assets: ledger.total_assets(),
+        }
+    }
+
+    fn assume_pre(&self) {}
+
+    fn check_post(&self, old: &Self, _params: ProcessParams, _deltas: TokenDeltas) {
+        let burned = old.ledger_shares - self.ledger_shares;
+        let received = self.ledger_assets - old.ledger_assets;
+        cvlr::clog!(burned, received);
+        cvlr_assert_le!(received * old.shares_total, burned * old.token_total);
+        cvlr_assert_lt!(
+            burned * old.token_total,
+            (received + NativeInt::from(1u64)) * old.shares_total
+        );
+    }
+}
@@ -1,11 +1,19 @@
 pub mod base;
 pub mod base_processor;
+#[cfg(feature = "certora")]
+pub mod rounding;
+#[cfg(feature = "certora")]
 pub mod utils_math;
 
 pub mod access_control;
+pub mod exact_deposit;
 pub mod fees;
+#[cfg(feature = "certora")]
 pub mod inflation_attack;
+#[cfg(feature = "certora")]
 pub mod integrity;
+pub mod ledger;
 pub mod no_dilution;
+pub mod sequences;
 pub mod solvency;
 pub mod vault_consistency;
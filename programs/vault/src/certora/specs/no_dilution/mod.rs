@@ -1,4 +1,6 @@
+#[cfg(feature = "certora")]
 pub mod no_dilution;
+#[cfg(feature = "certora")]
 pub mod no_dilution_processor;
 pub mod props;
 pub mod props_processor;
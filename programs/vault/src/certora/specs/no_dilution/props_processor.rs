@@ -1,9 +1,9 @@
-use crate::certora::specs::base_processor::CvlrProp;
+use crate::certora::specs::base_processor::{
+    AccountsSnapshot, CvlrProp, ProcessParams, TokenDeltas,
+};
 use crate::state::Vault;
 use cvlr::mathint::NativeInt;
 use cvlr::{cvlr_assert_le, cvlr_assume};
-use solana_program::account_info::AccountInfo;
-use std::mem::size_of;
 
 pub struct NoDilutionProp {
     shares_total: NativeInt,
@@ -33,18 +33,8 @@ fn safe_assumptions(vault: &Vault) {
 
 /// "no dilution" is a desired property for some operations: the ratio token_total / shares_total cannot decrease.
 impl CvlrProp for NoDilutionProp {
-    fn new(
-        vault_info_account: &AccountInfo,
-        _vault_assets_account: &AccountInfo,
-        _vault_fee_account: Option<&AccountInfo>,
-        _assets_mint: Option<&AccountInfo>,
-        _shares_mint: Option<&AccountInfo>,
-        _user_assets_account: Option<&AccountInfo>,
-        _authority: Option<&AccountInfo>,
-        _user_shares_account: Option<&AccountInfo>,
-    ) -> Self {
-        let data = vault_info_account.try_borrow_data().unwrap();
-        let vault = bytemuck::from_bytes::<Vault>(&data[0..size_of::<Vault>()]);
+    fn new(accounts: &AccountsSnapshot) -> Self {
+        let vault = &accounts.vault;
         safe_assumptions(vault);
 
         Self {
@@ -55,7 +45,7 @@ impl CvlrProp for NoDilutionProp {
 
     fn assume_pre(&self) {}
 
-    fn check_post(&self, old: &Self) {
+    fn check_post(&self, old: &Self, _params: ProcessParams, _deltas: TokenDeltas) {
         cvlr_assert_le!(
             old.token_total * self.shares_total,
             old.shares_total * self.token_total
@@ -0,0 +1,105 @@
+//! Rules checking `operations::rounding_policy`
+use crate::operations::{
+    rounding_policy, vault_deposit_assets, vault_deposit_assets_exact,
+    vault_deposit_assets_with_fee, vault_redeem_shares,
+};
+use crate::state::Vault;
+use crate::utils::math::Rounding;
+use cvlr::mathint::NativeInt;
+use cvlr::prelude::*;
+
+/// `res` is `x * num / den` rounded by `rounding`
+///
+/// A vault with as many shares as assets, including an empty one, converts
+/// 1:1 without rounding.
+fn check_rounding(res: u64, x: u64, num: u64, den: u64, rounding: Rounding) {
+    if num == den {
+        cvlr_assert_eq!(res, x);
+        return;
+    }
+    let res = NativeInt::from(res);
+    let exact = NativeInt::from(x) * NativeInt::from(num);
+    let den = NativeInt::from(den);
+    clog!(res, exact, den);
+    match rounding {
+        Rounding::Down => {
+            cvlr_assert_le!(res * den, exact);
+            cvlr_assert_lt!(exact, (res + NativeInt::from(1u64)) * den);
+        }
+        Rounding::Up => {
+            cvlr_assert_le!(exact, res * den);
+            cvlr_assert_lt!((res - NativeInt::from(1u64)) * den, exact);
+        }
+    }
+}
+
+#[rule]
+pub fn rule_rounding_deposit_assets() {
+    let mut vault: Vault = nondet();
+    let (shares, assets) = (vault.num_shares(), vault.num_assets());
+    let token_amount: u64 = nondet();
+
+    let effect = vault_deposit_assets(&mut vault, token_amount).unwrap();
+    check_rounding(
+        effect.shares_to_user,
+        token_amount,
+        shares,
+        assets,
+        rounding_policy::DEPOSIT_SHARES,
+    );
+}
+
+#[rule]
+pub fn rule_rounding_redeem_shares() {
+    let mut vault: Vault = nondet();
+    let (shares, assets) = (vault.num_shares(), vault.num_assets());
+    let shares_amount: u64 = nondet();
+
+    let effect = vault_redeem_shares(&mut vault, shares_amount).unwrap();
+    check_rounding(
+        effect.assets_to_user,
+        shares_amount,
+        assets,
+        shares,
+        rounding_policy::REDEEM_ASSETS,
+    );
+}
+
+#[rule]
+pub fn rule_rounding_deposit_assets_exact() {
+    let mut vault: Vault = nondet();
+    let (shares, assets) = (vault.num_shares(), vault.num_assets());
+    let token_amount: u64 = nondet();
+
+    let effect = vault_deposit_assets_exact(&mut vault, token_amount).unwrap();
+    check_rounding(
+        effect.shares_to_user,
+        token_amount,
+        shares,
+        assets,
+        rounding_policy::EXACT_DEPOSIT_SHARES,
+    );
+    check_rounding(
+        effect.assets_to_vault,
+        effect.shares_to_user,
+        assets,
+        shares,
+        rounding_policy::EXACT_DEPOSIT_ASSETS,
+    );
+}
+
+#[rule]
+pub fn rule_rounding_fee() {
+    let mut vault: Vault = nondet();
+    let fee_bps = u64::from(vault.fee_bps);
+    let token_amount: u64 = nondet();
+
+    let effect = vault_deposit_assets_with_fee(&mut vault, token_amount).unwrap();
+    check_rounding(
+        effect.assets_to_fee,
+        token_amount,
+        fee_bps,
+        10_000,
+        rounding_policy::FEE,
+    );
+}
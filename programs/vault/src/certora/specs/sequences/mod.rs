@@ -0,0 +1,3 @@
+pub mod round_trips;
+#[cfg(feature = "certora")]
+pub mod sequences;
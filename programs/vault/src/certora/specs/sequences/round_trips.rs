@@ -0,0 +1,96 @@
+//! Properties of operations run one after the other on one vault
+use crate::operations::{VaultEffect, VaultOperation};
+use crate::state::Vault;
+use cvlr::mathint::NativeInt;
+use cvlr::prelude::*;
+
+/// Assets a deposit takes from the user, fee included
+fn assets_paid(effect: &VaultEffect) -> NativeInt {
+    NativeInt::from(effect.assets_to_vault) + NativeInt::from(effect.assets_to_fee)
+}
+
+/// Deposit, then redeem the shares received: the user gets back no more than
+/// it paid
+#[inline(always)]
+pub fn round_trip_deposit_redeem(vault: &mut Vault, deposit: VaultOperation) {
+    cvlr_assume!(deposit.is_deposit());
+    let deposited = deposit.apply(vault).unwrap();
+    let redeemed = VaultOperation::Redeem {
+        shares: deposited.shares_to_user,
+    }
+    .apply(vault)
+    .unwrap();
+
+    clog!(deposited, redeemed);
+    cvlr_assert_le!(
+        NativeInt::from(redeemed.assets_to_user),
+        assets_paid(&deposited)
+    );
+}
+
+#[inline(always)]
+pub fn base_round_trip_deposit_redeem(deposit: impl FnOnce(u64) -> VaultOperation) {
+    let mut vault: Vault = nondet();
+    let amount = nondet();
+    clog!(amount);
+    round_trip_deposit_redeem(&mut vault, deposit(amount));
+}
+
+/// Redeem, then deposit the assets received: the user gets back no more
+/// shares than it redeemed
+#[inline(always)]
+pub fn base_round_trip_redeem_deposit() {
+    let mut vault: Vault = nondet();
+    let shares = nondet();
+    clog!(shares);
+
+    let redeemed = VaultOperation::Redeem { shares }.apply(&mut vault).unwrap();
+    let deposited = VaultOperation::Deposit {
+        amount: redeemed.assets_to_user,
+    }
+    .apply(&mut vault)
+    .unwrap();
+
+    clog!(redeemed, deposited);
+    cvlr_assert_le!(deposited.shares_to_user, shares);
+}
+
+/// Any operation, then a round trip through any deposit
+#[inline(always)]
+pub fn base_round_trip_after_operation() {
+    let mut vault: Vault = nondet();
+    let op: VaultOperation = nondet();
+    op.apply(&mut vault).unwrap();
+    round_trip_deposit_redeem(&mut vault, nondet());
+}
+
+/// Deposit, update the reward, then redeem the shares received: the user
+/// earns at most the part of the reward its shares hold
+#[inline(always)]
+pub fn base_reward_sandwich() {
+    let mut vault: Vault = nondet();
+    let amount = nondet();
+    clog!(amount);
+
+    let deposited = VaultOperation::Deposit { amount }
+        .apply(&mut vault)
+        .unwrap();
+    let (shares_total, assets_pre) = (vault.num_shares(), vault.num_assets());
+    VaultOperation::UpdateReward { balance: nondet() }
+        .apply(&mut vault)
+        .unwrap();
+    let reward = vault.num_assets() - assets_pre;
+    let redeemed = VaultOperation::Redeem {
+        shares: deposited.shares_to_user,
+    }
+    .apply(&mut vault)
+    .unwrap();
+
+    clog!(deposited, reward, redeemed);
+    // -- `assets_to_user - paid <= reward * shares / shares_total`
+    cvlr_assert_le!(
+        NativeInt::from(redeemed.assets_to_user) * NativeInt::from(shares_total),
+        assets_paid(&deposited) * NativeInt::from(shares_total)
+            + NativeInt::from(reward) * NativeInt::from(deposited.shares_to_user)
+    );
+}
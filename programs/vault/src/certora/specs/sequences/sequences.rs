@@ -0,0 +1,62 @@
+use crate::certora::constants::SEQUENCE_LENGTH;
+use crate::certora::specs::base::base_sequence;
+use crate::certora::specs::no_dilution::props::NoDilutionProp;
+use crate::certora::specs::sequences::round_trips::{
+    base_reward_sandwich, base_round_trip_after_operation, base_round_trip_deposit_redeem,
+    base_round_trip_redeem_deposit,
+};
+use crate::certora::specs::solvency::props::SolvencyInvariant;
+use crate::operations::VaultOperation;
+use cvlr::prelude::*;
+
+#[rule]
+pub fn rule_solvency_operation_pair() {
+    base_sequence::<SolvencyInvariant, 2>();
+}
+
+#[rule]
+pub fn rule_solvency_operation_sequence() {
+    base_sequence::<SolvencyInvariant, SEQUENCE_LENGTH>();
+}
+
+#[rule]
+/// It should produce a counterexample, as slashing dilutes
+pub fn rule_no_dilution_operation_pair() {
+    base_sequence::<NoDilutionProp, 2>();
+}
+
+#[rule]
+pub fn rule_round_trip_deposit_redeem() {
+    base_round_trip_deposit_redeem(|amount| VaultOperation::Deposit { amount });
+}
+
+#[rule]
+pub fn rule_round_trip_deposit_exact_redeem() {
+    base_round_trip_deposit_redeem(|amount| VaultOperation::DepositExact { amount });
+}
+
+#[rule]
+pub fn rule_round_trip_deposit_with_fee_redeem() {
+    base_round_trip_deposit_redeem(|amount| VaultOperation::DepositWithFee { amount });
+}
+
+#[rule]
+pub fn rule_round_trip_deposit_with_fee_exact_redeem() {
+    base_round_trip_deposit_redeem(|amount| VaultOperation::DepositWithFeeExact { amount });
+}
+
+#[rule]
+pub fn rule_round_trip_redeem_deposit() {
+    base_round_trip_redeem_deposit();
+}
+
+#[rule]
+/// Covers, among others, an exact deposit after a slash
+pub fn rule_round_trip_after_operation() {
+    base_round_trip_after_operation();
+}
+
+#[rule]
+pub fn rule_reward_sandwich() {
+    base_reward_sandwich();
+}
@@ -1,4 +1,6 @@
 pub mod props;
 pub mod props_processor;
+#[cfg(feature = "certora")]
 pub mod solvency;
+#[cfg(feature = "certora")]
 pub mod solvency_processor;
@@ -1,9 +1,8 @@
-use crate::certora::specs::base_processor::CvlrProp;
-use crate::state::Vault;
+use crate::certora::specs::base_processor::{
+    AccountsSnapshot, CvlrProp, ProcessParams, TokenDeltas,
+};
 use cvlr::mathint::NativeInt;
 use cvlr::{cvlr_assert_le, cvlr_assume};
-use solana_program::account_info::AccountInfo;
-use std::mem::size_of;
 
 pub struct SolvencyInvariant {
     shares_total: NativeInt,
@@ -28,19 +27,8 @@ mod log {
 
 /// Solvency is an invariant: the vault can never have more shares than tokens.
 impl CvlrProp for SolvencyInvariant {
-    fn new(
-        vault_info_account: &AccountInfo,
-        _vault_assets_account: &AccountInfo,
-        _vault_fee_account: Option<&AccountInfo>,
-        _assets_mint: Option<&AccountInfo>,
-        _shares_mint: Option<&AccountInfo>,
-        _user_assets_account: Option<&AccountInfo>,
-        _authority: Option<&AccountInfo>,
-        _user_shares_account: Option<&AccountInfo>,
-    ) -> Self {
-        let data = vault_info_account.try_borrow_data().unwrap();
-        let vault = bytemuck::from_bytes::<Vault>(&data[0..size_of::<Vault>()]);
-
+    fn new(accounts: &AccountsSnapshot) -> Self {
+        let vault = &accounts.vault;
         Self {
             shares_total: vault.num_shares().into(),
             token_total: vault.num_assets().into(),
@@ -51,7 +39,7 @@ impl CvlrProp for SolvencyInvariant {
         cvlr_assume!(self.shares_total <= self.token_total);
     }
 
-    fn check_post(&self, _old: &Self) {
+    fn check_post(&self, _old: &Self, _params: ProcessParams, _deltas: TokenDeltas) {
         cvlr_assert_le!(self.shares_total, self.token_total);
     }
 }
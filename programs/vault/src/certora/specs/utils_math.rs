@@ -1,3 +1,4 @@
+use crate::utils::math::Rounding;
 use cvlr::prelude::*;
 
 #[rule]
@@ -6,8 +7,8 @@ pub fn mul_div_floor_equiv_ok() {
     let b: u64 = nondet();
     let c: u64 = nondet();
 
-    let native_res = crate::utils::math::math_native::mul_div_floor(a, b, c).unwrap();
-    let certora_res = crate::utils::math::math_certora::mul_div_floor(a, b, c).unwrap();
+    let native_res = crate::utils::math::math_native::mul_div(a, b, c, Rounding::Down).unwrap();
+    let certora_res = crate::utils::math::math_certora::mul_div(a, b, c, Rounding::Down).unwrap();
     cvlr_assert_eq!(native_res, certora_res);
 }
 
@@ -17,8 +18,8 @@ pub fn mul_div_floor_equiv_err() {
     let b: u64 = nondet();
     let c: u64 = nondet();
 
-    let native_res = crate::utils::math::math_native::mul_div_floor(a, b, c);
-    let certora_res = crate::utils::math::math_certora::mul_div_floor(a, b, c);
+    let native_res = crate::utils::math::math_native::mul_div(a, b, c, Rounding::Down);
+    let certora_res = crate::utils::math::math_certora::mul_div(a, b, c, Rounding::Down);
     // -- in logic, division by 0 is an arbitrary output, not an error
     cvlr_assume!(c > 0);
     cvlr_assert_eq!(native_res.is_err(), certora_res.is_err());
@@ -30,8 +31,8 @@ pub fn mul_div_ceil_equiv_ok() {
     let b: u64 = nondet();
     let c: u64 = nondet();
 
-    let native_res = crate::utils::math::math_native::mul_div_ceil(a, b, c).unwrap();
-    let certora_res = crate::utils::math::math_certora::mul_div_ceil(a, b, c).unwrap();
+    let native_res = crate::utils::math::math_native::mul_div(a, b, c, Rounding::Up).unwrap();
+    let certora_res = crate::utils::math::math_certora::mul_div(a, b, c, Rounding::Up).unwrap();
     cvlr_assert_eq!(native_res, certora_res);
 }
 
@@ -41,7 +42,7 @@ pub fn mul_div_ceil_equiv_err() {
     let b: u64 = nondet();
     let c: u64 = nondet();
 
-    let native_res = crate::utils::math::math_native::mul_div_ceil(a, b, c);
-    let certora_res = crate::utils::math::math_certora::mul_div_ceil(a, b, c);
+    let native_res = crate::utils::math::math_native::mul_div(a, b, c, Rounding::Up);
+    let certora_res = crate::utils::math::math_certora::mul_div(a, b, c, Rounding::Up);
     cvlr_assert_eq!(native_res.is_err(), certora_res.is_err());
 }
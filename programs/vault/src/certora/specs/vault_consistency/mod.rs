@@ -1,2 +1,3 @@
 pub mod props;
+#[cfg(feature = "certora")]
 pub mod vault_consistency;
@@ -1,9 +1,8 @@
-use crate::certora::specs::base_processor::CvlrProp;
-use crate::state::Vault;
+use crate::certora::specs::base_processor::{
+    AccountsSnapshot, CvlrProp, ProcessParams, TokenDeltas,
+};
 use cvlr::mathint::NativeInt;
 use cvlr::{cvlr_assert_eq, cvlr_assert_le, cvlr_assume};
-use solana_program::account_info::AccountInfo;
-use std::mem::size_of;
 
 pub struct VaultConsistencyInvariant {
     vault_assets: NativeInt,
@@ -32,28 +31,12 @@ mod log {
 
 /// Vault's recorded asset/shares amount is consistent with the actual token balances in its associated SPL Token accounts.
 impl CvlrProp for VaultConsistencyInvariant {
-    fn new(
-        vault_info_account: &AccountInfo,
-        vault_assets_account: &AccountInfo,
-        _vault_fee_account: Option<&AccountInfo>,
-        _assets_mint: Option<&AccountInfo>,
-        shares_mint: Option<&AccountInfo>,
-        _user_assets_account: Option<&AccountInfo>,
-        _authority: Option<&AccountInfo>,
-        _user_shares_account: Option<&AccountInfo>,
-    ) -> Self {
-        let data = vault_info_account.try_borrow_data().unwrap();
-        let vault = bytemuck::from_bytes::<Vault>(&data[0..size_of::<Vault>()]);
-
+    fn new(accounts: &AccountsSnapshot) -> Self {
         Self {
-            vault_assets: vault.num_assets().into(),
-            vault_shares: vault.num_shares().into(),
-            account_tokens: cvlr_solana::token::spl_token_account_get_amount(vault_assets_account)
-                .into(),
-            mint_shares: match shares_mint {
-                Some(mint) => Some(cvlr_solana::token::spl_mint_get_supply(mint).into()),
-                None => None,
-            },
+            vault_assets: accounts.vault.num_assets().into(),
+            vault_shares: accounts.vault.num_shares().into(),
+            account_tokens: accounts.vault_assets_account.amount.into(),
+            mint_shares: accounts.shares_mint.map(|mint| mint.supply.into()),
         }
     }
 
@@ -64,7 +47,7 @@ impl CvlrProp for VaultConsistencyInvariant {
         }
     }
 
-    fn check_post(&self, _old: &Self) {
+    fn check_post(&self, _old: &Self, _params: ProcessParams, _deltas: TokenDeltas) {
         cvlr_assert_le!(self.vault_assets, self.account_tokens);
         if let Some(mint_shares) = self.mint_shares {
             cvlr_assert_eq!(self.vault_shares, mint_shares);
@@ -0,0 +1,315 @@
+//! Helpers for clients of the vault program
+//!
+//! Instruction builders take the vault key and its decoded state; the
+//! accounts recorded in the vault are filled in from the state and the
+//! `AccountMeta`s are laid out following the `ACCOUNTS` of the context that
+//! loads them. Builders moving tokens take the token program of the vault
+//! mints, `spl_token::id()` or `spl_token_2022::id()`.
+use std::mem::size_of;
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+
+use crate::{
+    instruction::VaultInstruction,
+    loaders::{
+        AccountSpec, CollectFeeContext, DepositContext, DepositWithFeeContext, MigrateVaultContext,
+        RedeemSharesContext, SlashContext, UpdateRewardContext,
+    },
+    state::{
+        legacy::{self, VaultV0},
+        Vault, VaultPda,
+    },
+    VaultError, VaultResult,
+};
+
+/// Address and bump of the PDA token account holding the vault assets
+pub fn find_vault_assets_account_address(vault_pk: &Pubkey) -> (Pubkey, u8) {
+    VaultPda::AssetsAccount.find_address(vault_pk)
+}
+
+/// Address and bump of the PDA mint of the vault shares
+pub fn find_vault_shares_mint_address(vault_pk: &Pubkey) -> (Pubkey, u8) {
+    VaultPda::SharesMint.find_address(vault_pk)
+}
+
+/// Decode vault account data
+///
+/// Accounts still in a legacy layout are returned upgraded to the current
+/// one. The caller is responsible for checking the account owner.
+pub fn decode_vault(data: &[u8]) -> VaultResult<Vault> {
+    if data.len() == size_of::<VaultV0>() {
+        return legacy::migrate_vault(data);
+    }
+    let vault = data
+        .get(..size_of::<Vault>())
+        .and_then(|data| bytemuck::try_pod_read_unaligned::<Vault>(data).ok())
+        .ok_or(VaultError::InvalidVaultData)?;
+    vault.validate()?;
+    Ok(vault)
+}
+
+/// Account metas in the order of `specs`, with keys looked up by name
+fn account_metas(specs: &[AccountSpec], keys: &[(&str, Pubkey)]) -> Vec<AccountMeta> {
+    specs
+        .iter()
+        .map(|spec| {
+            let (_, pubkey) = keys
+                .iter()
+                .find(|(name, _)| *name == spec.name)
+                .unwrap_or_else(|| panic!("no key for account {}", spec.name));
+            AccountMeta {
+                pubkey: *pubkey,
+                is_signer: spec.signer,
+                is_writable: spec.writable,
+            }
+        })
+        .collect()
+}
+
+fn instruction(
+    ix: VaultInstruction,
+    specs: &[AccountSpec],
+    keys: &[(&str, Pubkey)],
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: account_metas(specs, keys),
+        data: ix.pack(),
+    }
+}
+
+/// Accounts of a user depositing into or redeeming from a vault
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UserAccounts {
+    // signer, owner of the token accounts
+    pub authority: Pubkey,
+    pub assets_account: Pubkey,
+    pub shares_account: Pubkey,
+}
+
+fn deposit_keys(
+    vault_pk: &Pubkey,
+    vault: &Vault,
+    user: &UserAccounts,
+    token_program: &Pubkey,
+) -> [(&'static str, Pubkey); 9] {
+    [
+        ("vault_info", *vault_pk),
+        ("vault_assets_account", vault.vault_assets_account),
+        ("vault_fee_account", vault.fee_token_account),
+        ("assets_mint", vault.assets_mint),
+        ("shares_mint", vault.shares_mint),
+        ("user_assets_account", user.assets_account),
+        ("authority", user.authority),
+        ("user_shares_account", user.shares_account),
+        ("spl_token_program", *token_program),
+    ]
+}
+
+pub fn deposit(
+    vault_pk: &Pubkey,
+    vault: &Vault,
+    user: &UserAccounts,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        VaultInstruction::Deposit { amount },
+        DepositContext::ACCOUNTS,
+        &deposit_keys(vault_pk, vault, user, token_program),
+    )
+}
+
+pub fn deposit_exact(
+    vault_pk: &Pubkey,
+    vault: &Vault,
+    user: &UserAccounts,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        VaultInstruction::DepositExact { amount },
+        DepositContext::ACCOUNTS,
+        &deposit_keys(vault_pk, vault, user, token_program),
+    )
+}
+
+pub fn deposit_with_fee(
+    vault_pk: &Pubkey,
+    vault: &Vault,
+    user: &UserAccounts,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        VaultInstruction::DepositWithFee { amount },
+        DepositWithFeeContext::ACCOUNTS,
+        &deposit_keys(vault_pk, vault, user, token_program),
+    )
+}
+
+pub fn deposit_with_fee_exact(
+    vault_pk: &Pubkey,
+    vault: &Vault,
+    user: &UserAccounts,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        VaultInstruction::DepositWithFeeExact { amount },
+        DepositWithFeeContext::ACCOUNTS,
+        &deposit_keys(vault_pk, vault, user, token_program),
+    )
+}
+
+pub fn redeem_shares(
+    vault_pk: &Pubkey,
+    vault: &Vault,
+    user: &UserAccounts,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        VaultInstruction::RedeemShares { amount },
+        RedeemSharesContext::ACCOUNTS,
+        &deposit_keys(vault_pk, vault, user, token_program),
+    )
+}
+
+/// Permissionless update of the vault assets from its token account
+pub fn update_reward(vault_pk: &Pubkey, vault: &Vault) -> Instruction {
+    instruction(
+        VaultInstruction::UpdateReward,
+        UpdateRewardContext::ACCOUNTS,
+        &[
+            ("vault_info", *vault_pk),
+            ("vault_assets_account", vault.vault_assets_account),
+        ],
+    )
+}
+
+/// Slash `amount` assets of the vault into `user_token_account`
+pub fn slash(
+    vault_pk: &Pubkey,
+    vault: &Vault,
+    user_token_account: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        VaultInstruction::Slash { amount },
+        SlashContext::ACCOUNTS,
+        &[
+            ("vault_info", *vault_pk),
+            ("vault_assets_account", vault.vault_assets_account),
+            ("user_token_account", *user_token_account),
+            ("assets_mint", vault.assets_mint),
+            ("authority", vault.slash_admin),
+            ("spl_token_program", *token_program),
+        ],
+    )
+}
+
+/// Transfer the collected fee into `fee_collect_account`
+pub fn collect_fee(
+    vault_pk: &Pubkey,
+    vault: &Vault,
+    fee_collect_account: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    instruction(
+        VaultInstruction::CollectFee,
+        CollectFeeContext::ACCOUNTS,
+        &[
+            ("vault_info", *vault_pk),
+            ("vault_assets_account", vault.vault_assets_account),
+            ("assets_mint", vault.assets_mint),
+            ("fee_collect_account", *fee_collect_account),
+            ("authority", vault.admin),
+            ("spl_token_program", *token_program),
+        ],
+    )
+}
+
+/// Upgrade a vault in a legacy layout; `admin` pays for the extra rent
+pub fn migrate_vault(vault_pk: &Pubkey, admin: &Pubkey) -> Instruction {
+    instruction(
+        VaultInstruction::MigrateVault,
+        MigrateVaultContext::ACCOUNTS,
+        &[
+            ("vault_info", *vault_pk),
+            ("admin", *admin),
+            ("system_program", system_program::id()),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deposit_instruction() {
+        let vault_pk = Pubkey::new_unique();
+        let vault = Vault {
+            vault_assets_account: find_vault_assets_account_address(&vault_pk).0,
+            shares_mint: find_vault_shares_mint_address(&vault_pk).0,
+            assets_mint: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let user = UserAccounts {
+            authority: Pubkey::new_unique(),
+            assets_account: Pubkey::new_unique(),
+            shares_account: Pubkey::new_unique(),
+        };
+
+        let ix = deposit(&vault_pk, &vault, &user, &spl_token_2022::id(), 42);
+        let keys: Vec<_> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+
+        assert_eq!(ix.program_id, crate::id());
+        assert_eq!(
+            VaultInstruction::unpack(&ix.data),
+            Ok(VaultInstruction::Deposit { amount: 42 })
+        );
+        assert_eq!(
+            keys,
+            [
+                vault_pk,
+                vault.vault_assets_account,
+                vault.assets_mint,
+                vault.shares_mint,
+                user.assets_account,
+                user.authority,
+                user.shares_account,
+                spl_token_2022::id(),
+            ]
+        );
+        assert!(ix.accounts[5].is_signer);
+        assert!(ix.accounts[0].is_writable);
+    }
+
+    #[test]
+    fn test_decode_vault() {
+        let vault = Vault {
+            shares: 10.into(),
+            assets_mint: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let data = bytemuck::bytes_of(&vault);
+
+        assert_eq!(decode_vault(data).unwrap().num_shares(), 10);
+        assert_eq!(
+            decode_vault(&data[1..]).err(),
+            Some(VaultError::InvalidVaultData)
+        );
+        let legacy = VaultV0::default();
+        assert_eq!(
+            decode_vault(bytemuck::bytes_of(&legacy)).unwrap().version,
+            Vault::default().version
+        );
+    }
+}
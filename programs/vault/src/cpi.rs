@@ -0,0 +1,324 @@
+//! Calling the vault from other programs
+//!
+//! Every call takes the vault program account, a typed accounts struct and
+//! the seeds of PDA signers, if any. The `VaultEffect` of the instruction is
+//! read back from the return data, e.g. to learn how many shares a deposit
+//! minted.
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program::{get_return_data, invoke_signed},
+    program_error::ProgramError,
+};
+
+use crate::{
+    instruction::VaultInstruction,
+    loaders::{
+        AccountSpec, CollectFeeContext, DepositContext, DepositWithFeeContext, MigrateVaultContext,
+        RedeemSharesContext, SlashContext, UpdateRewardContext,
+    },
+    operations::VaultEffect,
+    utils::guards::require_eq,
+    VaultError,
+};
+
+/// Declares the accounts of a CPI to the instruction loaded by `$context`;
+/// fields are in the order of the `ACCOUNTS` of the context
+macro_rules! cpi_accounts {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident for $context:ident { $(pub $field:ident,)* }
+    ) => {
+        $(#[$meta])*
+        pub struct $name<'a, 'info> {
+            $(pub $field: &'a AccountInfo<'info>,)*
+        }
+
+        impl<'a, 'info> $name<'a, 'info> {
+            const ACCOUNTS: &'static [AccountSpec] = $context::ACCOUNTS;
+
+            #[cfg(test)]
+            const FIELDS: &'static [&'static str] = &[$(stringify!($field),)*];
+
+            fn infos(&self) -> [&'a AccountInfo<'info>; cpi_accounts!(@count $($field)*)] {
+                [$(self.$field,)*]
+            }
+        }
+    };
+    (@count) => { 0 };
+    (@count $head:ident $($tail:ident)*) => { 1 + cpi_accounts!(@count $($tail)*) };
+}
+
+cpi_accounts! {
+    /// Accounts of `deposit` and `deposit_exact`
+    pub struct Deposit for DepositContext {
+        pub vault_info,
+        pub vault_assets_account,
+        pub assets_mint,
+        pub shares_mint,
+        pub user_assets_account,
+        pub authority,
+        pub user_shares_account,
+        pub spl_token_program,
+    }
+}
+
+cpi_accounts! {
+    /// Accounts of `deposit_with_fee` and `deposit_with_fee_exact`
+    pub struct DepositWithFee for DepositWithFeeContext {
+        pub vault_info,
+        pub vault_assets_account,
+        pub vault_fee_account,
+        pub assets_mint,
+        pub shares_mint,
+        pub user_assets_account,
+        pub authority,
+        pub user_shares_account,
+        pub spl_token_program,
+    }
+}
+
+cpi_accounts! {
+    pub struct Redeem for RedeemSharesContext {
+        pub vault_info,
+        pub vault_assets_account,
+        pub assets_mint,
+        pub shares_mint,
+        pub user_shares_account,
+        pub authority,
+        pub user_assets_account,
+        pub spl_token_program,
+    }
+}
+
+cpi_accounts! {
+    pub struct UpdateReward for UpdateRewardContext {
+        pub vault_info,
+        pub vault_assets_account,
+    }
+}
+
+cpi_accounts! {
+    pub struct Slash for SlashContext {
+        pub vault_info,
+        pub vault_assets_account,
+        pub user_token_account,
+        pub assets_mint,
+        pub authority,
+        pub spl_token_program,
+    }
+}
+
+cpi_accounts! {
+    pub struct CollectFee for CollectFeeContext {
+        pub vault_info,
+        pub vault_assets_account,
+        pub assets_mint,
+        pub fee_collect_account,
+        pub authority,
+        pub spl_token_program,
+    }
+}
+
+cpi_accounts! {
+    pub struct MigrateVault for MigrateVaultContext {
+        pub vault_info,
+        pub admin,
+        pub system_program,
+    }
+}
+
+fn invoke_vault<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    ix: VaultInstruction,
+    specs: &[AccountSpec],
+    infos: &[&'a AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    require_eq!(program.key, &crate::id(), ProgramError::IncorrectProgramId);
+
+    let metas = specs
+        .iter()
+        .zip(infos)
+        .map(|(spec, info)| AccountMeta {
+            pubkey: *info.key,
+            is_signer: spec.signer,
+            is_writable: spec.writable,
+        })
+        .collect();
+    let mut account_infos: Vec<AccountInfo<'info>> = infos.iter().copied().cloned().collect();
+    account_infos.push(program.clone());
+
+    invoke_signed(
+        &Instruction {
+            program_id: *program.key,
+            accounts: metas,
+            data: ix.pack(),
+        },
+        &account_infos,
+        signer_seeds,
+    )?;
+
+    let (program_id, data) = get_return_data().ok_or(VaultError::MissingReturnData)?;
+    require_eq!(
+        program_id,
+        crate::id(),
+        VaultError::MissingReturnData.into()
+    );
+    let effect = bytemuck::try_pod_read_unaligned::<VaultEffect>(&data)
+        .map_err(|_| VaultError::MissingReturnData)?;
+    Ok(effect)
+}
+
+pub fn deposit<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &Deposit<'a, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::Deposit { amount },
+        Deposit::ACCOUNTS,
+        &accounts.infos(),
+        signer_seeds,
+    )
+}
+
+pub fn deposit_exact<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &Deposit<'a, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::DepositExact { amount },
+        Deposit::ACCOUNTS,
+        &accounts.infos(),
+        signer_seeds,
+    )
+}
+
+pub fn deposit_with_fee<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &DepositWithFee<'a, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::DepositWithFee { amount },
+        DepositWithFee::ACCOUNTS,
+        &accounts.infos(),
+        signer_seeds,
+    )
+}
+
+pub fn deposit_with_fee_exact<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &DepositWithFee<'a, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::DepositWithFeeExact { amount },
+        DepositWithFee::ACCOUNTS,
+        &accounts.infos(),
+        signer_seeds,
+    )
+}
+
+/// Redeem `shares` for assets of the vault
+pub fn redeem<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &Redeem<'a, 'info>,
+    shares: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::RedeemShares { amount: shares },
+        Redeem::ACCOUNTS,
+        &accounts.infos(),
+        signer_seeds,
+    )
+}
+
+pub fn update_reward<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &UpdateReward<'a, 'info>,
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::UpdateReward,
+        UpdateReward::ACCOUNTS,
+        &accounts.infos(),
+        &[],
+    )
+}
+
+pub fn slash<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &Slash<'a, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::Slash { amount },
+        Slash::ACCOUNTS,
+        &accounts.infos(),
+        signer_seeds,
+    )
+}
+
+pub fn collect_fee<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &CollectFee<'a, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::CollectFee,
+        CollectFee::ACCOUNTS,
+        &accounts.infos(),
+        signer_seeds,
+    )
+}
+
+pub fn migrate_vault<'a, 'info>(
+    program: &'a AccountInfo<'info>,
+    accounts: &MigrateVault<'a, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<VaultEffect, ProgramError> {
+    invoke_vault(
+        program,
+        VaultInstruction::MigrateVault,
+        MigrateVault::ACCOUNTS,
+        &accounts.infos(),
+        signer_seeds,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accounts_follow_the_loader_contexts() {
+        for (fields, specs) in [
+            (Deposit::FIELDS, Deposit::ACCOUNTS),
+            (DepositWithFee::FIELDS, DepositWithFee::ACCOUNTS),
+            (Redeem::FIELDS, Redeem::ACCOUNTS),
+            (UpdateReward::FIELDS, UpdateReward::ACCOUNTS),
+            (Slash::FIELDS, Slash::ACCOUNTS),
+            (CollectFee::FIELDS, CollectFee::ACCOUNTS),
+            (MigrateVault::FIELDS, MigrateVault::ACCOUNTS),
+        ] {
+            let names: Vec<_> = specs.iter().map(|spec| spec.name).collect();
+            assert_eq!(fields, names);
+        }
+    }
+}
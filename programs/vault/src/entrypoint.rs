@@ -0,0 +1,15 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
+};
+
+use crate::{processor::process_instruction, VaultError};
+
+entrypoint!(process_entrypoint);
+
+fn process_entrypoint(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    process_instruction(program_id, accounts, data).inspect_err(|err| {
+        if let Some(err) = VaultError::decode(err) {
+            err.print();
+        }
+    })
+}
@@ -1,11 +1,48 @@
-use solana_program::program_error::ProgramError;
+use num_enum::TryFromPrimitive;
+use solana_program::{decode_error::DecodeError, msg, program_error::ProgramError};
 
-#[derive(Debug, PartialEq)]
+/// Errors of the vault program
+///
+/// Codes are part of the program interface and are reported to clients as
+/// `ProgramError::Custom(code)`. Never renumber a variant; add new ones at
+/// the end, and reserve the code of a removed variant.
+#[repr(u32)]
+#[derive(TryFromPrimitive, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum VaultError {
-    Unspecified,
-    MathOverflow,
-    GuardFail,
-    SelfTransfer,
+    Unspecified = 0,
+    MathOverflow = 1,
+    // -- 2 is reserved, it was the code of `GuardFail`
+    SelfTransfer = 3,
+    /// Amount of an operation is zero
+    ZeroAmount = 4,
+    /// Fee is above 100%
+    FeeOutOfRange = 5,
+    /// Vault has fewer assets than shares after a slash
+    InsolventAfterSlash = 6,
+    /// Operation exceeds a vault cap
+    CapExceeded = 7,
+    /// Token account has an unexpected mint
+    WrongMint = 8,
+    /// Account is not the expected PDA of the vault
+    WrongPda = 9,
+    /// Account is not the one recorded in the vault
+    WrongAccount = 10,
+    /// Signer is not the admin of the vault
+    UnauthorizedAdmin = 11,
+    /// Account is expected to be writable
+    AccountNotWritable = 12,
+    /// Account data is not a vault
+    InvalidVaultData = 13,
+    /// Vault layout version is not supported
+    InvalidVaultVersion = 14,
+    /// Assets and shares use the same mint
+    SameMint = 15,
+    /// Vault is not solvent
+    Insolvent = 16,
+    /// Log data is not a vault event
+    InvalidEventData = 17,
+    /// Vault instruction called through CPI returned no `VaultEffect`
+    MissingReturnData = 18,
 }
 
 // Define a custom Result type
@@ -16,3 +53,82 @@ impl From<VaultError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+#[cfg(feature = "anchor")]
+impl From<VaultError> for anchor_lang::error::Error {
+    fn from(e: VaultError) -> Self {
+        ProgramError::from(e).into()
+    }
+}
+
+impl<T> DecodeError<T> for VaultError {
+    fn type_of() -> &'static str {
+        "VaultError"
+    }
+}
+
+impl std::fmt::Display for VaultError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            VaultError::Unspecified => "unspecified error",
+            VaultError::MathOverflow => "arithmetic overflow",
+            VaultError::SelfTransfer => "source and destination accounts are the same",
+            VaultError::ZeroAmount => "amount must be greater than zero",
+            VaultError::FeeOutOfRange => "fee must be at most 10000 bps",
+            VaultError::InsolventAfterSlash => "slash leaves the vault insolvent",
+            VaultError::CapExceeded => "vault cap exceeded",
+            VaultError::WrongMint => "token account has the wrong mint",
+            VaultError::WrongPda => "account is not the vault PDA",
+            VaultError::WrongAccount => "account does not match the vault",
+            VaultError::UnauthorizedAdmin => "signer is not the vault admin",
+            VaultError::AccountNotWritable => "account is not writable",
+            VaultError::InvalidVaultData => "account data is not a vault",
+            VaultError::InvalidVaultVersion => "unsupported vault version",
+            VaultError::SameMint => "assets and shares mints are the same",
+            VaultError::Insolvent => "vault is insolvent",
+            VaultError::InvalidEventData => "log data is not a vault event",
+            VaultError::MissingReturnData => "vault instruction returned no effect",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl std::error::Error for VaultError {}
+
+impl VaultError {
+    /// Log the error in the style of `PrintProgramError`
+    pub fn print(&self) {
+        msg!("VaultError::{:?} ({}): {}", self, *self as u32, self);
+    }
+
+    /// Decode a `VaultError` reported by the program
+    pub fn decode(err: &ProgramError) -> Option<Self> {
+        match err {
+            ProgramError::Custom(code) => Self::try_from(*code).ok(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_error_codes() {
+        assert_eq!(
+            ProgramError::from(VaultError::CapExceeded),
+            ProgramError::Custom(7)
+        );
+        assert_eq!(
+            ProgramError::from(VaultError::InsolventAfterSlash),
+            ProgramError::Custom(6)
+        );
+        for code in (0..=18).filter(|code| *code != 2) {
+            let err = VaultError::decode(&ProgramError::Custom(code)).unwrap();
+            assert_eq!(err as u32, code);
+        }
+        assert_eq!(VaultError::decode(&ProgramError::Custom(2)), None);
+        assert_eq!(VaultError::decode(&ProgramError::Custom(19)), None);
+    }
+}
@@ -0,0 +1,139 @@
+//! Events emitted by vault instructions
+//!
+//! Every event is logged with `sol_log_data` as two fields: the header
+//! `[EVENT_VERSION, VaultEventKind]` followed by the bytes of a `VaultEvent`.
+//! Off-chain code should parse them with `decode_event`.
+use bytemuck::{Pod, Zeroable};
+use num_enum::TryFromPrimitive;
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    operations::VaultEffect, state::Vault, utils::guards::require_eq, VaultError, VaultResult,
+};
+
+/// Version of the event layout
+pub const EVENT_VERSION: u8 = 1;
+
+#[repr(u8)]
+#[derive(TryFromPrimitive, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VaultEventKind {
+    Deposit = 0,
+    DepositExact = 1,
+    DepositWithFee = 2,
+    DepositWithFeeExact = 3,
+    RedeemShares = 4,
+    UpdateReward = 5,
+    Slash = 6,
+    CollectFee = 7,
+    MigrateVault = 8,
+}
+
+/// Totals of a vault at one point of an instruction
+#[repr(C)]
+#[derive(Default, Pod, Zeroable, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VaultSnapshot {
+    pub num_shares: u64,
+    pub num_assets: u64,
+    pub fee_amount: u64,
+}
+
+impl From<&Vault> for VaultSnapshot {
+    fn from(vault: &Vault) -> Self {
+        Self {
+            num_shares: vault.num_shares(),
+            num_assets: vault.num_assets(),
+            fee_amount: vault.fee_amount(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Pod, Zeroable, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VaultEvent {
+    pub vault: Pubkey,
+    // signer of the instruction, default for permissionless instructions
+    pub actor: Pubkey,
+    pub effect: VaultEffect,
+    pub pre: VaultSnapshot,
+    pub post: VaultSnapshot,
+}
+
+impl VaultEvent {
+    pub fn emit(&self, kind: VaultEventKind) {
+        emit_event(kind, self)
+    }
+}
+
+#[cfg_attr(feature = "certora",
+    cvlr::mock_fn(with=crate::certora::mocks::events::emit_event))]
+pub fn emit_event(kind: VaultEventKind, event: &VaultEvent) {
+    solana_program::log::sol_log_data(&[&[EVENT_VERSION, kind as u8], bytemuck::bytes_of(event)]);
+}
+
+/// Decode the data fields of a vault event
+pub fn decode_event(fields: &[&[u8]]) -> VaultResult<(VaultEventKind, VaultEvent)> {
+    let [header, body] = fields else {
+        return Err(VaultError::InvalidEventData);
+    };
+    let [version, kind] = header[..] else {
+        return Err(VaultError::InvalidEventData);
+    };
+    require_eq!(version, EVENT_VERSION, VaultError::InvalidEventData);
+    let kind = VaultEventKind::try_from(kind).map_err(|_| VaultError::InvalidEventData)?;
+    let event = bytemuck::try_pod_read_unaligned::<VaultEvent>(body)
+        .map_err(|_| VaultError::InvalidEventData)?;
+    Ok((kind, event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    fn test_event() -> VaultEvent {
+        VaultEvent {
+            vault: Pubkey::new_from_array([1; 32]),
+            actor: Pubkey::new_from_array([2; 32]),
+            effect: VaultEffect {
+                shares_to_user: 50,
+                assets_to_vault: 50,
+                ..Default::default()
+            },
+            pre: VaultSnapshot {
+                num_shares: 100,
+                num_assets: 100,
+                fee_amount: 0,
+            },
+            post: VaultSnapshot {
+                num_shares: 150,
+                num_assets: 150,
+                fee_amount: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_decode_event() {
+        let event = test_event();
+        let header = [EVENT_VERSION, VaultEventKind::Deposit as u8];
+        let fields: [&[u8]; 2] = [&header, bytemuck::bytes_of(&event)];
+
+        assert_eq!(size_of::<VaultEvent>(), 152);
+        assert_eq!(decode_event(&fields), Ok((VaultEventKind::Deposit, event)));
+    }
+
+    #[test]
+    fn test_decode_event_invalid() {
+        let event = test_event();
+        let body = bytemuck::bytes_of(&event);
+
+        let wrong_version = [EVENT_VERSION + 1, VaultEventKind::Deposit as u8];
+        let wrong_kind = [EVENT_VERSION, 0xff];
+        let header = [EVENT_VERSION, VaultEventKind::Deposit as u8];
+
+        assert!(decode_event(&[&wrong_version, body]).is_err());
+        assert!(decode_event(&[&wrong_kind, body]).is_err());
+        assert!(decode_event(&[&header, &body[1..]]).is_err());
+        assert!(decode_event(&[&header]).is_err());
+    }
+}
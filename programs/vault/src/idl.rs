@@ -0,0 +1,338 @@
+//! Interface descriptors of the vault program
+//!
+//! Plain data describing instructions, account layouts, events and errors,
+//! collected from the definitions used by the program itself: the account
+//! lists come from the loader contexts, the arguments from
+//! `VaultInstruction::unpack`, and the layouts are checked against the
+//! structs by the compiler. The `vault-idl` crate renders them as JSON.
+use std::mem::size_of;
+
+use solana_program::pubkey::Pubkey;
+use spl_pod::primitives::PodU64;
+
+use crate::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
+    instruction::{CertoraVaultInstruction, VaultInstruction},
+    loaders::{
+        AccountSpec, CollectFeeContext, DepositContext, DepositWithFeeContext, MigrateVaultContext,
+        RedeemSharesContext, SlashContext, UpdateRewardContext,
+    },
+    operations::VaultEffect,
+    state::Vault,
+    VaultError,
+};
+
+/// Type of a field or an argument
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TypeDesc {
+    U8,
+    U64,
+    PublicKey,
+    Bytes(usize),
+    /// One of the structs returned by `types`
+    Defined(&'static str),
+}
+
+pub trait IdlType {
+    const TYPE: TypeDesc;
+}
+
+impl IdlType for u8 {
+    const TYPE: TypeDesc = TypeDesc::U8;
+}
+
+impl IdlType for u64 {
+    const TYPE: TypeDesc = TypeDesc::U64;
+}
+
+impl IdlType for PodU64 {
+    const TYPE: TypeDesc = TypeDesc::U64;
+}
+
+impl IdlType for Pubkey {
+    const TYPE: TypeDesc = TypeDesc::PublicKey;
+}
+
+impl<const N: usize> IdlType for [u8; N] {
+    const TYPE: TypeDesc = TypeDesc::Bytes(N);
+}
+
+impl IdlType for VaultEffect {
+    const TYPE: TypeDesc = TypeDesc::Defined("VaultEffect");
+}
+
+impl IdlType for VaultSnapshot {
+    const TYPE: TypeDesc = TypeDesc::Defined("VaultSnapshot");
+}
+
+/// Field of a `#[repr(C)]` struct; integers are little-endian
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FieldDesc {
+    pub name: &'static str,
+    pub ty: TypeDesc,
+    pub offset: usize,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructDesc {
+    pub name: &'static str,
+    pub size: usize,
+    pub fields: Vec<FieldDesc>,
+}
+
+/// Describes a struct; fails to compile when a field has another type
+macro_rules! struct_desc {
+    ($name:ident { $($field:ident: $ty:ty,)* }) => {
+        StructDesc {
+            name: stringify!($name),
+            size: size_of::<$name>(),
+            fields: vec![$({
+                let _check_type = |s: &$name| -> $ty { s.$field };
+                FieldDesc {
+                    name: stringify!($field),
+                    ty: <$ty as IdlType>::TYPE,
+                    offset: std::mem::offset_of!($name, $field),
+                    size: size_of::<$ty>(),
+                }
+            },)*],
+        }
+    };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ArgDesc {
+    pub name: &'static str,
+    pub ty: TypeDesc,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InstructionDesc {
+    pub name: &'static str,
+    /// First byte of the instruction data
+    pub tag: u8,
+    pub args: &'static [ArgDesc],
+    pub accounts: &'static [AccountSpec],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EventDesc {
+    pub name: &'static str,
+    /// Second byte of the event header, after `EVENT_VERSION`
+    pub kind: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorDesc {
+    pub code: u32,
+    pub name: &'static str,
+    pub msg: String,
+}
+
+/// Accounts of an instruction, `None` if the program does not process it
+pub fn instruction_accounts(tag: CertoraVaultInstruction) -> Option<&'static [AccountSpec]> {
+    let accounts = match tag {
+        CertoraVaultInstruction::CreateVault => return None,
+        CertoraVaultInstruction::Deposit | CertoraVaultInstruction::DepositExact => {
+            DepositContext::ACCOUNTS
+        }
+        CertoraVaultInstruction::DepositWithFee | CertoraVaultInstruction::DepositWithFeeExact => {
+            DepositWithFeeContext::ACCOUNTS
+        }
+        CertoraVaultInstruction::MigrateVault => MigrateVaultContext::ACCOUNTS,
+        CertoraVaultInstruction::RedeemShares => RedeemSharesContext::ACCOUNTS,
+        CertoraVaultInstruction::UpdateReward => UpdateRewardContext::ACCOUNTS,
+        CertoraVaultInstruction::Slash => SlashContext::ACCOUNTS,
+        CertoraVaultInstruction::CollectFee => CollectFeeContext::ACCOUNTS,
+    };
+    Some(accounts)
+}
+
+/// Instructions processed by the program, in tag order
+pub fn instructions() -> Vec<InstructionDesc> {
+    const AMOUNT: &[ArgDesc] = &[ArgDesc {
+        name: "amount",
+        ty: TypeDesc::U64,
+    }];
+
+    (0..=u8::MAX)
+        .filter_map(|tag| CertoraVaultInstruction::try_from(tag).ok())
+        .filter_map(|tag| {
+            let accounts = instruction_accounts(tag)?;
+            // -- an instruction takes an amount iff the bare tag does not unpack
+            let args = match VaultInstruction::unpack(&[tag as u8]) {
+                Ok(_) => &[],
+                Err(_) => AMOUNT,
+            };
+            Some(InstructionDesc {
+                name: tag.name(),
+                tag: tag as u8,
+                args,
+                accounts,
+            })
+        })
+        .collect()
+}
+
+/// Layout of the accounts owned by the program
+pub fn accounts() -> Vec<StructDesc> {
+    vec![struct_desc!(Vault {
+        version: u8,
+        admin: Pubkey,
+        slash_admin: Pubkey,
+        shares_mint: Pubkey,
+        vault_shares_mint_bump: u8,
+        assets_mint: Pubkey,
+        shares: PodU64,
+        assets: PodU64,
+        fee_bps: PodU64,
+        fee_amount: PodU64,
+        fee_token_account: Pubkey,
+        vault_assets_account: Pubkey,
+        vault_assets_account_bump: u8,
+        reserved: [u8; crate::state::VAULT_RESERVED_BYTES],
+    })]
+}
+
+/// Structs used by accounts and events
+pub fn types() -> Vec<StructDesc> {
+    vec![
+        struct_desc!(VaultEffect {
+            shares_to_burn: u64,
+            shares_to_user: u64,
+            assets_to_vault: u64,
+            assets_to_user: u64,
+            assets_to_fee: u64,
+        }),
+        struct_desc!(VaultSnapshot {
+            num_shares: u64,
+            num_assets: u64,
+            fee_amount: u64,
+        }),
+    ]
+}
+
+/// Layout of the body shared by all events
+pub fn event_body() -> StructDesc {
+    struct_desc!(VaultEvent {
+        vault: Pubkey,
+        actor: Pubkey,
+        effect: VaultEffect,
+        pre: VaultSnapshot,
+        post: VaultSnapshot,
+    })
+}
+
+pub fn events() -> Vec<EventDesc> {
+    (0..=u8::MAX)
+        .filter_map(|kind| VaultEventKind::try_from(kind).ok())
+        .map(|kind| EventDesc {
+            name: kind.name(),
+            kind: kind as u8,
+        })
+        .collect()
+}
+
+pub fn errors() -> Vec<ErrorDesc> {
+    // -- codes of removed variants are reserved, so the codes have gaps
+    (0..=u32::from(u8::MAX))
+        .filter_map(|code| VaultError::try_from(code).ok())
+        .map(|err| ErrorDesc {
+            code: err as u32,
+            name: err.name(),
+            msg: err.to_string(),
+        })
+        .collect()
+}
+
+/// Variant names of the interface enums
+macro_rules! impl_name {
+    ($ty:ty { $($variant:ident),* $(,)? }) => {
+        impl $ty {
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(<$ty>::$variant => stringify!($variant),)*
+                }
+            }
+        }
+    };
+}
+
+impl_name!(CertoraVaultInstruction {
+    CreateVault,
+    Deposit,
+    DepositWithFee,
+    MigrateVault,
+    DepositExact,
+    DepositWithFeeExact,
+    RedeemShares,
+    UpdateReward,
+    Slash,
+    CollectFee,
+});
+
+impl_name!(VaultEventKind {
+    Deposit,
+    DepositExact,
+    DepositWithFee,
+    DepositWithFeeExact,
+    RedeemShares,
+    UpdateReward,
+    Slash,
+    CollectFee,
+    MigrateVault,
+});
+
+impl_name!(VaultError {
+    Unspecified,
+    MathOverflow,
+    SelfTransfer,
+    ZeroAmount,
+    FeeOutOfRange,
+    InsolventAfterSlash,
+    CapExceeded,
+    WrongMint,
+    WrongPda,
+    WrongAccount,
+    UnauthorizedAdmin,
+    AccountNotWritable,
+    InvalidVaultData,
+    InvalidVaultVersion,
+    SameMint,
+    Insolvent,
+    InvalidEventData,
+    MissingReturnData,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layouts_are_complete() {
+        for desc in accounts().iter().chain(&types()).chain([&event_body()]) {
+            let mut offset = 0;
+            for field in &desc.fields {
+                assert_eq!(field.offset, offset, "{}.{}", desc.name, field.name);
+                offset += field.size;
+            }
+            assert_eq!(offset, desc.size, "{} has undescribed fields", desc.name);
+        }
+    }
+
+    #[test]
+    fn test_instructions() {
+        let ixs = instructions();
+        let deposit = ixs.iter().find(|ix| ix.name == "Deposit").unwrap();
+
+        assert!(ixs.iter().all(|ix| ix.name != "CreateVault"));
+        assert_eq!(deposit.args.len(), 1);
+        assert_eq!(deposit.accounts, DepositContext::ACCOUNTS);
+        assert!(ixs
+            .iter()
+            .find(|ix| ix.name == "CollectFee")
+            .unwrap()
+            .args
+            .is_empty());
+    }
+}
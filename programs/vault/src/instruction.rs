@@ -1,9 +1,140 @@
 use num_enum::TryFromPrimitive;
+use solana_program::program_error::ProgramError;
 
+use crate::operations::VaultOperation;
+
+/// Tag of an instruction, the first byte of the instruction data
 #[repr(u8)]
 #[derive(TryFromPrimitive, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum CertoraVaultInstruction {
     CreateVault = 0,
     Deposit = 1,
     DepositWithFee = 2,
+    MigrateVault = 3,
+    DepositExact = 4,
+    DepositWithFeeExact = 5,
+    RedeemShares = 6,
+    UpdateReward = 7,
+    Slash = 8,
+    CollectFee = 9,
+}
+
+/// Instruction with its arguments
+///
+/// Instruction data is the tag followed by the amount, if any, as a
+/// little-endian `u64`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VaultInstruction {
+    CreateVault,
+    Deposit { amount: u64 },
+    DepositWithFee { amount: u64 },
+    MigrateVault,
+    DepositExact { amount: u64 },
+    DepositWithFeeExact { amount: u64 },
+    RedeemShares { amount: u64 },
+    UpdateReward,
+    Slash { amount: u64 },
+    CollectFee,
+}
+
+impl VaultInstruction {
+    pub fn tag(&self) -> CertoraVaultInstruction {
+        match self {
+            VaultInstruction::CreateVault => CertoraVaultInstruction::CreateVault,
+            VaultInstruction::Deposit { .. } => CertoraVaultInstruction::Deposit,
+            VaultInstruction::DepositWithFee { .. } => CertoraVaultInstruction::DepositWithFee,
+            VaultInstruction::MigrateVault => CertoraVaultInstruction::MigrateVault,
+            VaultInstruction::DepositExact { .. } => CertoraVaultInstruction::DepositExact,
+            VaultInstruction::DepositWithFeeExact { .. } => {
+                CertoraVaultInstruction::DepositWithFeeExact
+            }
+            VaultInstruction::RedeemShares { .. } => CertoraVaultInstruction::RedeemShares,
+            VaultInstruction::UpdateReward => CertoraVaultInstruction::UpdateReward,
+            VaultInstruction::Slash { .. } => CertoraVaultInstruction::Slash,
+            VaultInstruction::CollectFee => CertoraVaultInstruction::CollectFee,
+        }
+    }
+
+    pub fn amount(&self) -> Option<u64> {
+        match *self {
+            VaultInstruction::Deposit { amount }
+            | VaultInstruction::DepositWithFee { amount }
+            | VaultInstruction::DepositExact { amount }
+            | VaultInstruction::DepositWithFeeExact { amount }
+            | VaultInstruction::RedeemShares { amount }
+            | VaultInstruction::Slash { amount } => Some(amount),
+            VaultInstruction::CreateVault
+            | VaultInstruction::MigrateVault
+            | VaultInstruction::UpdateReward
+            | VaultInstruction::CollectFee => None,
+        }
+    }
+
+    /// Vault operation run by the instruction; `UpdateReward` reads
+    /// `vault_balance`, the balance of the vault assets account
+    pub fn operation(&self, vault_balance: u64) -> Option<VaultOperation> {
+        match *self {
+            VaultInstruction::Deposit { amount } => Some(VaultOperation::Deposit { amount }),
+            VaultInstruction::DepositWithFee { amount } => {
+                Some(VaultOperation::DepositWithFee { amount })
+            }
+            VaultInstruction::DepositExact { amount } => {
+                Some(VaultOperation::DepositExact { amount })
+            }
+            VaultInstruction::DepositWithFeeExact { amount } => {
+                Some(VaultOperation::DepositWithFeeExact { amount })
+            }
+            VaultInstruction::RedeemShares { amount } => {
+                Some(VaultOperation::Redeem { shares: amount })
+            }
+            VaultInstruction::UpdateReward => Some(VaultOperation::UpdateReward {
+                balance: vault_balance,
+            }),
+            VaultInstruction::Slash { amount } => Some(VaultOperation::Slash { amount }),
+            VaultInstruction::CollectFee => Some(VaultOperation::CollectFee),
+            VaultInstruction::CreateVault | VaultInstruction::MigrateVault => None,
+        }
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![self.tag() as u8];
+        if let Some(amount) = self.amount() {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        data
+    }
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = data
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let tag = CertoraVaultInstruction::try_from(tag)
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+        let amount = || -> Result<u64, ProgramError> {
+            rest.try_into()
+                .map(u64::from_le_bytes)
+                .map_err(|_| ProgramError::InvalidInstructionData)
+        };
+        let no_args = || -> Result<(), ProgramError> {
+            rest.is_empty()
+                .then_some(())
+                .ok_or(ProgramError::InvalidInstructionData)
+        };
+
+        let ix = match tag {
+            CertoraVaultInstruction::CreateVault => no_args().map(|_| Self::CreateVault)?,
+            CertoraVaultInstruction::Deposit => Self::Deposit { amount: amount()? },
+            CertoraVaultInstruction::DepositWithFee => Self::DepositWithFee { amount: amount()? },
+            CertoraVaultInstruction::MigrateVault => no_args().map(|_| Self::MigrateVault)?,
+            CertoraVaultInstruction::DepositExact => Self::DepositExact { amount: amount()? },
+            CertoraVaultInstruction::DepositWithFeeExact => {
+                Self::DepositWithFeeExact { amount: amount()? }
+            }
+            CertoraVaultInstruction::RedeemShares => Self::RedeemShares { amount: amount()? },
+            CertoraVaultInstruction::UpdateReward => no_args().map(|_| Self::UpdateReward)?,
+            CertoraVaultInstruction::Slash => Self::Slash { amount: amount()? },
+            CertoraVaultInstruction::CollectFee => no_args().map(|_| Self::CollectFee)?,
+        };
+        Ok(ix)
+    }
 }
@@ -1,15 +1,42 @@
+#[cfg(feature = "anchor")]
+pub mod anchor;
+pub mod client;
 pub mod constants;
+#[cfg(feature = "cpi")]
+pub mod cpi;
+#[cfg(not(any(feature = "no-entrypoint", feature = "anchor")))]
+mod entrypoint;
 pub mod errors;
+pub mod events;
+pub mod idl;
 pub mod instruction;
 pub mod loaders;
 pub mod operations;
 pub mod processor;
 pub mod state;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod utils;
 
 pub mod certora;
 
+// -- `cpi` encodes native vault instructions, which the Anchor build of the
+// program does not dispatch
+#[cfg(all(feature = "anchor", feature = "cpi"))]
+compile_error!("features `anchor` and `cpi` are mutually exclusive");
+
+// -- unit tests check the CVLR specs natively, see `certora::native`
+#[cfg(all(test, not(feature = "certora")))]
+extern crate cvlr_native as cvlr;
+#[cfg(all(test, not(feature = "certora")))]
+extern crate cvlr_native as cvlr_solana;
+
 pub use errors::{VaultError, VaultResult};
+
+// -- `#[program]` looks up the modules generated by `#[derive(Accounts)]` at
+// the crate root
+#[cfg(feature = "anchor")]
+use anchor::contexts::*;
 use solana_program::declare_id;
 
 declare_id!("CRTRcNtiG8u4EFNkVnQkKcFYRRkLa2LtFPbihbsrcbJY");
@@ -0,0 +1,254 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+
+use crate::{state::VaultPda, VaultError};
+
+/// An account that can be loaded from a borrowed `AccountInfo`
+///
+/// Loaded accounts keep a reference to the `AccountInfo` instead of a clone.
+pub trait LoadAccount<'a, 'info>: Sized {
+    fn load(info: &'a AccountInfo<'info>) -> Result<Self, ProgramError>;
+    fn info(&self) -> &'a AccountInfo<'info>;
+}
+
+impl<'a, 'info> LoadAccount<'a, 'info> for &'a AccountInfo<'info> {
+    fn load(info: &'a AccountInfo<'info>) -> Result<Self, ProgramError> {
+        Ok(info)
+    }
+
+    fn info(&self) -> &'a AccountInfo<'info> {
+        self
+    }
+}
+
+/// Machine-readable description of an account expected by a context
+///
+/// Constraints are relative to the vault of the context: `has_one` and
+/// `token_mint` name a `Vault` field holding the expected key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AccountSpec {
+    pub name: &'static str,
+    pub signer: bool,
+    pub writable: bool,
+    pub has_one: Option<&'static str>,
+    /// Error of a failed `has_one`
+    pub has_one_error: VaultError,
+    pub pda: Option<VaultPda>,
+    pub token_mint: Option<&'static str>,
+}
+
+impl AccountSpec {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            signer: false,
+            writable: false,
+            has_one: None,
+            has_one_error: VaultError::WrongAccount,
+            pda: None,
+            token_mint: None,
+        }
+    }
+
+    pub const fn signer(self) -> Self {
+        Self {
+            signer: true,
+            ..self
+        }
+    }
+
+    pub const fn writable(self) -> Self {
+        Self {
+            writable: true,
+            ..self
+        }
+    }
+
+    pub const fn has_one(self, field: &'static str) -> Self {
+        Self {
+            has_one: Some(field),
+            ..self
+        }
+    }
+
+    pub const fn has_one_error(self, error: VaultError) -> Self {
+        Self {
+            has_one_error: error,
+            ..self
+        }
+    }
+
+    pub const fn pda(self, pda: VaultPda) -> Self {
+        Self {
+            pda: Some(pda),
+            ..self
+        }
+    }
+
+    pub const fn token_mint(self, field: &'static str) -> Self {
+        Self {
+            token_mint: Some(field),
+            ..self
+        }
+    }
+}
+
+/// Declares an accounts context
+///
+/// Generates the struct, `ACCOUNTS` (the account list in instruction order),
+/// `load` and `validate`. Every context has a `vault_info: VaultInfo` field
+/// and the constraints below are checked against that vault:
+///
+/// - `signer` -- account signed the transaction
+/// - `writable` -- account is writable
+/// - `has_one = f` -- account key is `vault.f`; fails with `WrongAccount`
+///   unless another `VaultError` is given as `has_one = f @ Error`
+/// - `pda = p` -- account key is the `VaultPda` `p` of the vault
+/// - `token_mint = f` -- account is a token account with mint `vault.f`
+macro_rules! vault_context {
+    (
+        pub struct $name:ident<'a, 'info> {
+            $(
+                #[account($($constraint:tt)*)]
+                pub $field:ident: $ty:ty,
+            )*
+        }
+    ) => {
+        pub struct $name<'a, 'info> {
+            $(pub $field: $ty,)*
+        }
+
+        impl<'a, 'info> $name<'a, 'info> {
+            pub const ACCOUNTS: &'static [$crate::loaders::AccountSpec] = &[
+                $(
+                    $crate::loaders::vault_context_spec!(
+                        $crate::loaders::AccountSpec::new(stringify!($field));
+                        $($constraint)*
+                    ),
+                )*
+            ];
+
+            pub fn validate(self) -> Result<Self, solana_program::program_error::ProgramError> {
+                let vault = self.vault_info.get()?;
+                $(
+                    $crate::loaders::vault_context_check!(
+                        self, vault, $field;
+                        $($constraint)*
+                    );
+                )*
+                drop(vault);
+                Ok(self)
+            }
+
+            pub fn load(
+                accounts: &'a [solana_program::account_info::AccountInfo<'info>],
+            ) -> Result<Self, solana_program::program_error::ProgramError> {
+                let iter = &mut accounts.iter();
+                Self {
+                    $(
+                        $field: $crate::loaders::LoadAccount::load(
+                            solana_program::account_info::next_account_info(iter)?,
+                        )?,
+                    )*
+                }
+                .validate()
+            }
+        }
+    };
+}
+pub(crate) use vault_context;
+
+macro_rules! vault_context_spec {
+    ($spec:expr;) => { $spec };
+    ($spec:expr; signer $(, $($rest:tt)*)?) => {
+        $crate::loaders::vault_context_spec!($spec.signer(); $($($rest)*)?)
+    };
+    ($spec:expr; writable $(, $($rest:tt)*)?) => {
+        $crate::loaders::vault_context_spec!($spec.writable(); $($($rest)*)?)
+    };
+    ($spec:expr; has_one = $vault_field:ident $(, $($rest:tt)*)?) => {
+        $crate::loaders::vault_context_spec!(
+            $spec.has_one(stringify!($vault_field)); $($($rest)*)?
+        )
+    };
+    ($spec:expr; has_one = $vault_field:ident @ $err:ident $(, $($rest:tt)*)?) => {
+        $crate::loaders::vault_context_spec!(
+            $spec
+                .has_one(stringify!($vault_field))
+                .has_one_error($crate::VaultError::$err);
+            $($($rest)*)?
+        )
+    };
+    ($spec:expr; pda = $pda:ident $(, $($rest:tt)*)?) => {
+        $crate::loaders::vault_context_spec!(
+            $spec.pda($crate::state::VaultPda::$pda); $($($rest)*)?
+        )
+    };
+    ($spec:expr; token_mint = $vault_field:ident $(, $($rest:tt)*)?) => {
+        $crate::loaders::vault_context_spec!(
+            $spec.token_mint(stringify!($vault_field)); $($($rest)*)?
+        )
+    };
+}
+pub(crate) use vault_context_spec;
+
+macro_rules! vault_context_check {
+    ($ctx:ident, $vault:ident, $field:ident;) => {};
+    ($ctx:ident, $vault:ident, $field:ident; signer $(, $($rest:tt)*)?) => {
+        $crate::utils::guards::require!(
+            $crate::loaders::LoadAccount::info(&$ctx.$field).is_signer,
+            solana_program::program_error::ProgramError::MissingRequiredSignature
+        );
+        $crate::loaders::vault_context_check!($ctx, $vault, $field; $($($rest)*)?);
+    };
+    ($ctx:ident, $vault:ident, $field:ident; writable $(, $($rest:tt)*)?) => {
+        $crate::utils::guards::require!(
+            $crate::loaders::LoadAccount::info(&$ctx.$field).is_writable,
+            $crate::VaultError::AccountNotWritable.into()
+        );
+        $crate::loaders::vault_context_check!($ctx, $vault, $field; $($($rest)*)?);
+    };
+    (
+        $ctx:ident, $vault:ident, $field:ident;
+        has_one = $vault_field:ident $(, $($rest:tt)*)?
+    ) => {
+        $crate::loaders::vault_context_check!(
+            $ctx, $vault, $field;
+            has_one = $vault_field @ WrongAccount $(, $($rest)*)?
+        );
+    };
+    (
+        $ctx:ident, $vault:ident, $field:ident;
+        has_one = $vault_field:ident @ $err:ident $(, $($rest:tt)*)?
+    ) => {
+        $crate::utils::guards::require_eq!(
+            &$vault.$vault_field,
+            $crate::loaders::LoadAccount::info(&$ctx.$field).key,
+            $crate::VaultError::$err.into()
+        );
+        $crate::loaders::vault_context_check!($ctx, $vault, $field; $($($rest)*)?);
+    };
+    (
+        $ctx:ident, $vault:ident, $field:ident;
+        pda = $pda:ident $(, $($rest:tt)*)?
+    ) => {
+        let expected_pk = $crate::state::VaultPda::$pda
+            .create_address($ctx.vault_info.as_ref().key, &$vault)?;
+        $crate::utils::guards::require_eq!(
+            $crate::loaders::LoadAccount::info(&$ctx.$field).key,
+            &expected_pk,
+            $crate::VaultError::WrongPda.into()
+        );
+        $crate::loaders::vault_context_check!($ctx, $vault, $field; $($($rest)*)?);
+    };
+    (
+        $ctx:ident, $vault:ident, $field:ident;
+        token_mint = $vault_field:ident $(, $($rest:tt)*)?
+    ) => {
+        $crate::loaders::check_token_mint(
+            $crate::loaders::LoadAccount::info(&$ctx.$field),
+            &$vault.$vault_field,
+        )?;
+        $crate::loaders::vault_context_check!($ctx, $vault, $field; $($($rest)*)?);
+    };
+}
+pub(crate) use vault_context_check;
@@ -1,7 +1,303 @@
+pub mod context;
 pub mod utils;
 pub mod vault_loaders;
 pub mod vault_with_fee_loaders;
 
+pub use context::*;
 pub use utils::*;
 pub use vault_loaders::*;
 pub use vault_with_fee_loaders::*;
+
+/// Account substitution
+///
+/// Every account of every context is replaced in turn by a foreign mint, a
+/// token account that is not a PDA, the unsigned authority, a wrong program
+/// and each other account of the context. The loader must fail with the
+/// error of the first check the substitute breaks, as computed by
+/// `expected_load` from the `ACCOUNTS` of the context and the `role` of each
+/// account. Substitutes that are valid for an account, e.g. any token
+/// account of the assets mint as the destination of a redemption, must load.
+#[cfg(test)]
+mod tests {
+    use solana_program::{
+        account_info::AccountInfo,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_program,
+    };
+    use spl_token::state::Account as TokenAccount;
+
+    use super::*;
+    use crate::{
+        client::{self, UserAccounts},
+        state::{legacy::VaultV0, Vault, VaultBuilder, VaultPda},
+        test_support::{TestAccount, TokenBank, VaultFixture},
+        VaultError,
+    };
+
+    type Load = fn(&[AccountInfo]) -> Result<(), ProgramError>;
+
+    struct Context {
+        name: &'static str,
+        ix: Instruction,
+        accounts: &'static [AccountSpec],
+        load: Load,
+    }
+
+    struct Setup {
+        bank: TokenBank,
+        vault_pk: Pubkey,
+        vault: Vault,
+        user: UserAccounts,
+        slashed_account: Pubkey,
+        legacy_vault_pk: Pubkey,
+        foreign_mint: Pubkey,
+        non_pda_token_account: Pubkey,
+        wrong_program: Pubkey,
+    }
+
+    impl Setup {
+        fn new() -> Self {
+            let VaultFixture {
+                mut bank,
+                vault_pk,
+                vault,
+                user,
+                slashed_account,
+            } = VaultFixture::unique(VaultBuilder::new());
+            let legacy_vault = VaultV0 {
+                admin: vault.admin,
+                ..Default::default()
+            };
+            let legacy_vault_pk = Pubkey::new_unique();
+            bank.add(
+                TestAccount::new(legacy_vault_pk, crate::id())
+                    .data(bytemuck::bytes_of(&legacy_vault).to_vec()),
+            );
+            bank.add(TestAccount::new(system_program::id(), Pubkey::default()).executable());
+            let wrong_program = Pubkey::new_unique();
+            bank.add(TestAccount::new(wrong_program, Pubkey::default()).executable());
+
+            let owner = Pubkey::new_unique();
+            let (foreign_mint, non_pda_token_account) =
+                (Pubkey::new_unique(), Pubkey::new_unique());
+            bank.create_mint(foreign_mint, owner);
+            bank.create_token_account(non_pda_token_account, vault.assets_mint, owner);
+
+            Self {
+                bank,
+                vault_pk,
+                vault,
+                user,
+                slashed_account,
+                legacy_vault_pk,
+                foreign_mint,
+                non_pda_token_account,
+                wrong_program,
+            }
+        }
+
+        /// A valid instruction for each context
+        fn contexts(&self) -> Vec<Context> {
+            let (vault_pk, vault, user) = (&self.vault_pk, &self.vault, &self.user);
+            vec![
+                Context {
+                    name: "DepositContext",
+                    ix: client::deposit(vault_pk, vault, user, &spl_token::id(), 1),
+                    accounts: DepositContext::ACCOUNTS,
+                    load: |accounts| DepositContext::load(accounts).map(drop),
+                },
+                Context {
+                    name: "DepositWithFeeContext",
+                    ix: client::deposit_with_fee(vault_pk, vault, user, &spl_token::id(), 1),
+                    accounts: DepositWithFeeContext::ACCOUNTS,
+                    load: |accounts| DepositWithFeeContext::load(accounts).map(drop),
+                },
+                Context {
+                    name: "RedeemSharesContext",
+                    ix: client::redeem_shares(vault_pk, vault, user, &spl_token::id(), 1),
+                    accounts: RedeemSharesContext::ACCOUNTS,
+                    load: |accounts| RedeemSharesContext::load(accounts).map(drop),
+                },
+                Context {
+                    name: "UpdateRewardContext",
+                    ix: client::update_reward(vault_pk, vault),
+                    accounts: UpdateRewardContext::ACCOUNTS,
+                    load: |accounts| UpdateRewardContext::load(accounts).map(drop),
+                },
+                Context {
+                    name: "SlashContext",
+                    ix: client::slash(vault_pk, vault, &self.slashed_account, &spl_token::id(), 1),
+                    accounts: SlashContext::ACCOUNTS,
+                    load: |accounts| SlashContext::load(accounts).map(drop),
+                },
+                Context {
+                    name: "CollectFeeContext",
+                    ix: client::collect_fee(
+                        vault_pk,
+                        vault,
+                        &vault.fee_token_account,
+                        &spl_token::id(),
+                    ),
+                    accounts: CollectFeeContext::ACCOUNTS,
+                    load: |accounts| CollectFeeContext::load(accounts).map(drop),
+                },
+                Context {
+                    name: "MigrateVaultContext",
+                    ix: client::migrate_vault(&self.legacy_vault_pk, &vault.admin),
+                    accounts: MigrateVaultContext::ACCOUNTS,
+                    load: |accounts| MigrateVaultContext::load(accounts).map(drop),
+                },
+            ]
+        }
+    }
+
+    /// Constraints that an account of this name needs in any context
+    ///
+    /// A PDA of the vault is checked against its seeds as well as against
+    /// the key recorded in the vault.
+    fn role(spec: &AccountSpec) -> AccountSpec {
+        match spec.name {
+            "vault_assets_account" => spec
+                .has_one("vault_assets_account")
+                .pda(VaultPda::AssetsAccount),
+            "shares_mint" => spec.has_one("shares_mint").pda(VaultPda::SharesMint),
+            "assets_mint" => spec.has_one("assets_mint"),
+            "vault_fee_account" => spec.has_one("fee_token_account"),
+            _ => *spec,
+        }
+    }
+
+    fn vault_key<'v>(vault: &'v mut Vault, field: &str) -> &'v mut Pubkey {
+        match field {
+            "admin" => &mut vault.admin,
+            "slash_admin" => &mut vault.slash_admin,
+            "shares_mint" => &mut vault.shares_mint,
+            "assets_mint" => &mut vault.assets_mint,
+            "vault_assets_account" => &mut vault.vault_assets_account,
+            "fee_token_account" => &mut vault.fee_token_account,
+            _ => panic!("no key {} in vault", field),
+        }
+    }
+
+    /// Result of loading `metas`: accounts are loaded in order, then each
+    /// account is checked against its `role` in order
+    fn expected_load(
+        bank: &TokenBank,
+        accounts: &[AccountSpec],
+        metas: &[AccountMeta],
+    ) -> Result<(), ProgramError> {
+        let specs = || accounts.iter().map(role).zip(metas);
+        let vault_pk = metas[0].pubkey;
+        for (spec, meta) in specs() {
+            let account = bank.account(&meta.pubkey);
+            match spec.name {
+                "vault_info" if account.owner != crate::id() => {
+                    return Err(ProgramError::IllegalOwner)
+                }
+                "spl_token_program" => {
+                    spl_token_2022::check_spl_token_program_account(&meta.pubkey)?
+                }
+                "system_program" if meta.pubkey != system_program::id() => {
+                    return Err(ProgramError::IncorrectProgramId)
+                }
+                _ => {}
+            }
+        }
+
+        let mut vault = client::decode_vault(&bank.account(&vault_pk).data)?;
+        for (spec, meta) in specs() {
+            if spec.signer && !meta.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if let Some(field) = spec.has_one {
+                if *vault_key(&mut vault, field) != meta.pubkey {
+                    return Err(spec.has_one_error.into());
+                }
+            }
+            if let Some(pda) = spec.pda {
+                if pda.create_address(&vault_pk, &vault)? != meta.pubkey {
+                    return Err(VaultError::WrongPda.into());
+                }
+            }
+            if let Some(field) = spec.token_mint {
+                let account = TokenAccount::unpack(&bank.account(&meta.pubkey).data)
+                    .map_err(|_| ProgramError::InvalidAccountData)?;
+                if account.mint != *vault_key(&mut vault, field) {
+                    return Err(VaultError::WrongMint.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the accounts of `ix` in `context` and compare with the model
+    fn check(mut bank: TokenBank, context: &Context, ix: &Instruction, case: &str) {
+        let expected = expected_load(&bank, context.accounts, &ix.accounts);
+        let result = (context.load)(&bank.infos(&ix.accounts));
+        assert_eq!(result, expected, "{}: {}", context.name, case);
+    }
+
+    #[test]
+    fn test_account_substitution() {
+        let setup = Setup::new();
+        for context in setup.contexts() {
+            let metas = &context.ix.accounts;
+            assert_eq!(
+                (context.load)(&setup.bank.clone().infos(metas)),
+                Ok(()),
+                "{}",
+                context.name
+            );
+
+            let signers: Vec<Pubkey> = metas
+                .iter()
+                .filter(|meta| meta.is_signer)
+                .map(|meta| meta.pubkey)
+                .collect();
+            let authority = signers.first().copied().unwrap_or(setup.user.authority);
+            let mut substitutes = vec![
+                ("foreign mint", setup.foreign_mint, false),
+                ("non-PDA token account", setup.non_pda_token_account, false),
+                ("unsigned authority", authority, false),
+                ("wrong token program", setup.wrong_program, false),
+            ];
+            substitutes.extend(metas.iter().map(|meta| {
+                let signer = signers.contains(&meta.pubkey);
+                ("duplicated account", meta.pubkey, signer)
+            }));
+
+            for (position, spec) in context.accounts.iter().enumerate() {
+                for &(substitute, key, is_signer) in &substitutes {
+                    let mut ix = context.ix.clone();
+                    let meta = &mut ix.accounts[position];
+                    if (meta.pubkey, meta.is_signer) == (key, is_signer) {
+                        continue;
+                    }
+                    meta.pubkey = key;
+                    meta.is_signer = is_signer;
+                    let case = format!("{} as {}", substitute, spec.name);
+                    check(setup.bank.clone(), &context, &ix, &case);
+
+                    // -- even if the vault records the substitute, a PDA must
+                    // match its seeds
+                    if let (Some(field), Some(_)) = (role(spec).has_one, role(spec).pda) {
+                        let mut bank = setup.bank.clone();
+                        let mut vault = setup.vault;
+                        *vault_key(&mut vault, field) = key;
+                        bank.account_mut(&setup.vault_pk).data =
+                            bytemuck::bytes_of(&vault).to_vec();
+                        check(
+                            bank,
+                            &context,
+                            &ix,
+                            &format!("{} recorded by the vault", case),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
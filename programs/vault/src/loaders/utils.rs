@@ -1,41 +1,51 @@
 use {
-    crate::utils::guards::require,
-    solana_program::{account_info::AccountInfo, program_error::ProgramError},
+    crate::{utils::guards::require_eq, VaultError},
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_token_2022::{generic_token_account::GenericTokenAccount, state::Account as TokenAccount},
     std::result::Result,
 };
 
-pub struct Signer<'info> {
-    pub info: AccountInfo<'info>,
+use super::LoadAccount;
+
+/// SPL token or Token-2022 program
+#[derive(Copy, Clone)]
+pub struct SplTokenProgramInfo<'a, 'info> {
+    pub info: &'a AccountInfo<'info>,
 }
 
-impl<'info> TryFrom<&AccountInfo<'info>> for Signer<'info> {
+impl<'a, 'info> TryFrom<&'a AccountInfo<'info>> for SplTokenProgramInfo<'a, 'info> {
     type Error = ProgramError;
-    fn try_from(info: &AccountInfo<'info>) -> Result<Self, Self::Error> {
-        require!(info.is_signer, ProgramError::MissingRequiredSignature);
-        Ok(Self { info: info.clone() })
+    fn try_from(info: &'a AccountInfo<'info>) -> Result<Self, Self::Error> {
+        spl_token_2022::check_spl_token_program_account(info.key)?;
+        Ok(Self { info })
     }
 }
 
-impl<'info> AsRef<AccountInfo<'info>> for Signer<'info> {
+impl<'a, 'info> AsRef<AccountInfo<'info>> for SplTokenProgramInfo<'a, 'info> {
     fn as_ref(&self) -> &AccountInfo<'info> {
-        &self.info
+        self.info
     }
 }
 
-pub struct SplTokenProgramInfo<'info> {
-    pub info: AccountInfo<'info>,
-}
+impl<'a, 'info> LoadAccount<'a, 'info> for SplTokenProgramInfo<'a, 'info> {
+    fn load(info: &'a AccountInfo<'info>) -> Result<Self, ProgramError> {
+        info.try_into()
+    }
 
-impl<'info> TryFrom<&AccountInfo<'info>> for SplTokenProgramInfo<'info> {
-    type Error = ProgramError;
-    fn try_from(info: &AccountInfo<'info>) -> Result<Self, Self::Error> {
-        spl_token::check_program_account(info.key)?;
-        Ok(Self { info: info.clone() })
+    fn info(&self) -> &'a AccountInfo<'info> {
+        self.info
     }
 }
 
-impl<'info> AsRef<AccountInfo<'info>> for SplTokenProgramInfo<'info> {
-    fn as_ref(&self) -> &AccountInfo<'info> {
-        &self.info
-    }
+/// Check that `info` is a token account of `mint`, with or without
+/// Token-2022 extensions
+pub fn check_token_mint(info: &AccountInfo, mint: &Pubkey) -> ProgramResult {
+    let data = info.try_borrow_data()?;
+    let account_mint =
+        TokenAccount::unpack_account_mint(&data).ok_or(ProgramError::InvalidAccountData)?;
+    require_eq!(account_mint, mint, VaultError::WrongMint.into());
+    Ok(())
 }
@@ -5,298 +5,209 @@ use std::{
 
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    entrypoint::ProgramResult,
     program_error::ProgramError,
-    pubkey::Pubkey,
+    system_program,
 };
 
 use crate::{
-    state::{create_vault_assets_account_address, create_vault_shares_mint_address, Vault},
-    utils::guards::require_eq,
+    state::{legacy::legacy_vault, Vault},
+    utils::guards::{require, require_eq},
+    VaultError,
 };
 
-use super::{Signer, SplTokenProgramInfo};
+use super::{vault_context, AccountSpec, LoadAccount, SplTokenProgramInfo};
 
-pub struct VaultInfo<'info> {
-    info: AccountInfo<'info>,
+#[derive(Copy, Clone)]
+pub struct VaultInfo<'a, 'info> {
+    info: &'a AccountInfo<'info>,
 }
 
-impl<'info> AsRef<AccountInfo<'info>> for VaultInfo<'info> {
+impl<'a, 'info> AsRef<AccountInfo<'info>> for VaultInfo<'a, 'info> {
     fn as_ref(&self) -> &AccountInfo<'info> {
-        &self.info
+        self.info
     }
 }
 
-impl<'info> TryFrom<&AccountInfo<'info>> for VaultInfo<'info> {
+impl<'a, 'info> TryFrom<&'a AccountInfo<'info>> for VaultInfo<'a, 'info> {
     type Error = ProgramError;
 
-    fn try_from(info: &AccountInfo<'info>) -> Result<Self, Self::Error> {
+    fn try_from(info: &'a AccountInfo<'info>) -> Result<Self, Self::Error> {
         // owned by vault program
         // has discriminant
-        Self { info: info.clone() }.validate()
+        Self { info }.validate()
     }
 }
 
-impl<'info> VaultInfo<'info> {
-    pub fn validate(self) -> Result<Self, ProgramError> {
-        self.get()?.validate()?;
-        Ok(self)
+impl<'a, 'info> LoadAccount<'a, 'info> for VaultInfo<'a, 'info> {
+    fn load(info: &'a AccountInfo<'info>) -> Result<Self, ProgramError> {
+        info.try_into()
     }
 
-    pub fn get(&self) -> Result<Ref<'_, Vault>, ProgramError> {
-        let data = self.info.try_borrow_data()?;
-        let res = Ref::map(data, |data| {
-            bytemuck::from_bytes::<Vault>(&data[0..size_of::<Vault>()])
-        });
-        Ok(res)
+    fn info(&self) -> &'a AccountInfo<'info> {
+        self.info
     }
-
-    pub fn get_mut(&self) -> Result<RefMut<'_, Vault>, ProgramError> {
-        let data = self.info.try_borrow_mut_data()?;
-        let res = RefMut::map(data, |data| {
-            bytemuck::from_bytes_mut::<Vault>(&mut data[0..size_of::<Vault>()])
-        });
-        Ok(res)
-    }
-}
-
-pub struct VaultAssetsAccount<'info> {
-    pub info: AccountInfo<'info>,
 }
 
-impl<'info> VaultAssetsAccount<'info> {
-    pub fn validate(self) -> Result<Self, ProgramError> {
-        Ok(self)
-    }
-
-    pub fn check_vault(&self, vault_pk: &Pubkey, vault: &Vault) -> ProgramResult {
-        let expected_pk = create_vault_assets_account_address(vault_pk, vault)?;
-        require_eq!(self.info.key, &expected_pk, ProgramError::InvalidArgument);
-        Ok(())
-    }
-}
-
-impl<'info> AsRef<AccountInfo<'info>> for VaultAssetsAccount<'info> {
-    fn as_ref(&self) -> &AccountInfo<'info> {
-        &self.info
-    }
-}
-
-impl<'info> TryFrom<&AccountInfo<'info>> for VaultAssetsAccount<'info> {
-    type Error = ProgramError;
-    fn try_from(info: &AccountInfo<'info>) -> Result<Self, Self::Error> {
-        Self { info: info.clone() }.validate()
-    }
-}
-
-pub struct VaultSharesMint<'info> {
-    pub info: AccountInfo<'info>,
-}
-
-impl<'info> VaultSharesMint<'info> {
+impl<'a, 'info> VaultInfo<'a, 'info> {
     pub fn validate(self) -> Result<Self, ProgramError> {
+        require!(self.info.owner == &crate::id(), ProgramError::IllegalOwner);
+        self.get()?.validate()?;
         Ok(self)
     }
 
-    pub fn check_vault(&self, vault_pk: &Pubkey, vault: &Vault) -> ProgramResult {
-        let expected_pk = create_vault_shares_mint_address(vault_pk, vault)?;
-        require_eq!(self.info.key, &expected_pk, ProgramError::InvalidArgument);
-        Ok(())
-    }
-}
-
-impl<'info> AsRef<AccountInfo<'info>> for VaultSharesMint<'info> {
-    fn as_ref(&self) -> &AccountInfo<'info> {
-        &self.info
+    pub fn get(&self) -> Result<Ref<'_, Vault>, ProgramError> {
+        let data = self.info.try_borrow_data()?;
+        Ref::filter_map(data, |data| {
+            data.get(0..size_of::<Vault>())
+                .and_then(|data| bytemuck::try_from_bytes::<Vault>(data).ok())
+        })
+        .map_err(|_| VaultError::InvalidVaultData.into())
     }
-}
 
-impl<'info> TryFrom<&AccountInfo<'info>> for VaultSharesMint<'info> {
-    type Error = ProgramError;
-    fn try_from(info: &AccountInfo<'info>) -> Result<Self, Self::Error> {
-        Self { info: info.clone() }.validate()
-    }
-}
-
-pub struct DepositContext<'info> {
-    // the vault
-    pub vault_info: VaultInfo<'info>,
-    // token account of the vault deposit
-    pub vault_assets_account: VaultAssetsAccount<'info>,
-    // mint for assets token
-    pub assets_mint: AccountInfo<'info>,
-    pub shares_mint: VaultSharesMint<'info>,
-    // token account for the user making a deposit
-    pub user_assets_account: AccountInfo<'info>,
-    // signing authority for the user assets account
-    pub authority: Signer<'info>,
-    pub user_shares_account: AccountInfo<'info>,
-    // SPL token program to make the transfer
-    pub spl_token_program: SplTokenProgramInfo<'info>,
-}
+    pub fn get_mut(&self) -> Result<RefMut<'_, Vault>, ProgramError> {
+        let data = self.info.try_borrow_mut_data()?;
+        RefMut::filter_map(data, |data| {
+            data.get_mut(0..size_of::<Vault>())
+                .and_then(|data| bytemuck::try_from_bytes_mut::<Vault>(data).ok())
+        })
+        .map_err(|_| VaultError::InvalidVaultData.into())
+    }
+}
+
+vault_context! {
+    pub struct DepositContext<'a, 'info> {
+        // the vault
+        #[account(writable)]
+        pub vault_info: VaultInfo<'a, 'info>,
+        // token account of the vault deposit
+        #[account(writable, has_one = vault_assets_account, pda = AssetsAccount)]
+        pub vault_assets_account: &'a AccountInfo<'info>,
+        // mint for assets token
+        #[account(has_one = assets_mint)]
+        pub assets_mint: &'a AccountInfo<'info>,
+        #[account(writable, has_one = shares_mint, pda = SharesMint)]
+        pub shares_mint: &'a AccountInfo<'info>,
+        // token account for the user making a deposit
+        #[account(writable, token_mint = assets_mint)]
+        pub user_assets_account: &'a AccountInfo<'info>,
+        // signing authority for the user assets account
+        #[account(signer)]
+        pub authority: &'a AccountInfo<'info>,
+        #[account(writable, token_mint = shares_mint)]
+        pub user_shares_account: &'a AccountInfo<'info>,
+        // SPL token program to make the transfer
+        #[account()]
+        pub spl_token_program: SplTokenProgramInfo<'a, 'info>,
+    }
+}
+
+vault_context! {
+    pub struct RedeemSharesContext<'a, 'info> {
+        #[account(writable)]
+        pub vault_info: VaultInfo<'a, 'info>,
+        #[account(writable, has_one = vault_assets_account, pda = AssetsAccount)]
+        pub vault_assets_account: &'a AccountInfo<'info>,
+        #[account(has_one = assets_mint)]
+        pub assets_mint: &'a AccountInfo<'info>,
+        #[account(writable, has_one = shares_mint, pda = SharesMint)]
+        pub shares_mint: &'a AccountInfo<'info>,
+        #[account(writable, token_mint = shares_mint)]
+        pub user_shares_account: &'a AccountInfo<'info>,
+        #[account(signer)]
+        pub authority: &'a AccountInfo<'info>,
+        #[account(writable, token_mint = assets_mint)]
+        pub user_assets_account: &'a AccountInfo<'info>,
+        #[account()]
+        pub spl_token_program: SplTokenProgramInfo<'a, 'info>,
+    }
+}
+
+vault_context! {
+    pub struct UpdateRewardContext<'a, 'info> {
+        #[account(writable)]
+        pub vault_info: VaultInfo<'a, 'info>,
+        #[account(has_one = vault_assets_account, pda = AssetsAccount)]
+        pub vault_assets_account: &'a AccountInfo<'info>,
+    }
+}
+
+vault_context! {
+    pub struct SlashContext<'a, 'info> {
+        #[account(writable)]
+        pub vault_info: VaultInfo<'a, 'info>,
+        #[account(writable, has_one = vault_assets_account, pda = AssetsAccount)]
+        pub vault_assets_account: &'a AccountInfo<'info>,
+        #[account(writable, token_mint = assets_mint)]
+        pub user_token_account: &'a AccountInfo<'info>,
+        #[account(has_one = assets_mint)]
+        pub assets_mint: &'a AccountInfo<'info>,
+        #[account(signer, has_one = slash_admin @ UnauthorizedAdmin)]
+        pub authority: &'a AccountInfo<'info>,
+        #[account()]
+        pub spl_token_program: SplTokenProgramInfo<'a, 'info>,
+    }
+}
+
+/// Accounts of `MigrateVault`
+///
+/// Written by hand since `vault_info` still has a legacy layout and cannot be
+/// loaded as a `VaultInfo`.
+pub struct MigrateVaultContext<'a, 'info> {
+    // the vault, in a legacy layout
+    pub vault_info: &'a AccountInfo<'info>,
+    // vault admin, pays for the extra rent
+    pub admin: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> MigrateVaultContext<'a, 'info> {
+    pub const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::new("vault_info").writable(),
+        AccountSpec::new("admin")
+            .signer()
+            .writable()
+            .has_one("admin")
+            .has_one_error(VaultError::UnauthorizedAdmin),
+        AccountSpec::new("system_program"),
+    ];
 
-impl<'info> DepositContext<'info> {
     pub fn validate(self) -> Result<Self, ProgramError> {
-        let vault = self.vault_info.get()?;
-        require_eq!(
-            &vault.assets_mint,
-            self.assets_mint.key,
-            ProgramError::InvalidArgument
+        require!(
+            self.vault_info.owner == &crate::id(),
+            ProgramError::IllegalOwner
         );
-
-        require_eq!(
-            &vault.shares_mint,
-            self.shares_mint.as_ref().key,
-            ProgramError::InvalidArgument
+        require!(
+            self.vault_info.is_writable,
+            VaultError::AccountNotWritable.into()
         );
-
-        require_eq!(
-            &vault.vault_assets_account,
-            self.vault_assets_account.as_ref().key,
-            ProgramError::InvalidArgument
+        require!(self.admin.is_signer, ProgramError::MissingRequiredSignature);
+        require!(
+            self.admin.is_writable,
+            VaultError::AccountNotWritable.into()
         );
-
-        self.vault_assets_account
-            .check_vault(self.vault_info.as_ref().key, &vault)?;
-
-        self.shares_mint
-            .check_vault(self.vault_info.as_ref().key, &vault)?;
-
-        drop(vault);
-        Ok(self)
-    }
-
-    pub fn load(accounts: &[AccountInfo<'info>]) -> Result<Self, ProgramError> {
-        let iter = &mut accounts.iter();
-        Self {
-            vault_info: next_account_info(iter)?.try_into()?,
-            vault_assets_account: next_account_info(iter)?.try_into()?,
-            assets_mint: next_account_info(iter)?.clone(),
-            shares_mint: next_account_info(iter)?.try_into()?,
-            user_assets_account: next_account_info(iter)?.clone(),
-            authority: next_account_info(iter)?.try_into()?,
-            user_shares_account: next_account_info(iter)?.clone(),
-            spl_token_program: next_account_info(iter)?.try_into()?,
-        }
-        .validate()
-    }
-}
-
-pub struct RedeemSharesContext<'info> {
-    pub vault_info: VaultInfo<'info>,
-    pub vault_assets_account: AccountInfo<'info>,
-    pub assets_mint: AccountInfo<'info>,
-    pub shares_mint: VaultSharesMint<'info>,
-    pub user_shares_account: AccountInfo<'info>,
-    pub authority: Signer<'info>,
-    pub user_assets_account: AccountInfo<'info>,
-    pub spl_token_program: SplTokenProgramInfo<'info>,
-}
-
-impl<'info> RedeemSharesContext<'info> {
-    pub fn validate(self) -> Result<Self, ProgramError> {
-        let vault = self.vault_info.get()?;
         require_eq!(
-            &vault.assets_mint,
-            self.assets_mint.key,
-            ProgramError::InvalidArgument
+            self.system_program.key,
+            &system_program::ID,
+            ProgramError::IncorrectProgramId
         );
 
+        let data = self.vault_info.try_borrow_data()?;
+        let vault = legacy_vault(&data)?;
         require_eq!(
-            &vault.shares_mint,
-            self.shares_mint.as_ref().key,
-            ProgramError::InvalidArgument
-        );
-
-        require_eq!(
-            &vault.vault_assets_account,
-            self.vault_assets_account.key,
-            ProgramError::InvalidArgument
-        );
-
-        drop(vault);
-        Ok(self)
-    }
-    pub fn load(accounts: &[AccountInfo<'info>]) -> Result<Self, ProgramError> {
-        let iter = &mut accounts.iter();
-        Self {
-            vault_info: next_account_info(iter)?.try_into()?,
-            vault_assets_account: next_account_info(iter)?.clone(),
-            assets_mint: next_account_info(iter)?.clone(),
-            shares_mint: next_account_info(iter)?.try_into()?,
-            user_shares_account: next_account_info(iter)?.clone(),
-            authority: next_account_info(iter)?.try_into()?,
-            user_assets_account: next_account_info(iter)?.clone(),
-            spl_token_program: next_account_info(iter)?.try_into()?,
-        }
-        .validate()
-    }
-}
-
-pub struct UpdateRewardContext<'info> {
-    pub vault_info: VaultInfo<'info>,
-    pub vault_assets_account: AccountInfo<'info>,
-}
-
-impl<'info> UpdateRewardContext<'info> {
-    pub fn validate(self) -> Result<Self, ProgramError> {
-        let vault = self.vault_info.get()?;
-        require_eq!(
-            &vault.vault_assets_account,
-            self.vault_assets_account.key,
-            ProgramError::InvalidArgument
+            &vault.admin,
+            self.admin.key,
+            VaultError::UnauthorizedAdmin.into()
         );
-
-        drop(vault);
+        drop(data);
         Ok(self)
     }
-}
 
-impl<'info> UpdateRewardContext<'info> {
-    pub fn load(accounts: &[AccountInfo<'info>]) -> Result<Self, ProgramError> {
-        let iter = &mut accounts.iter();
-        Self {
-            vault_info: next_account_info(iter)?.try_into()?,
-            vault_assets_account: next_account_info(iter)?.clone(),
-        }
-        .validate()
-    }
-}
-
-pub struct SlashContext<'info> {
-    pub vault_info: VaultInfo<'info>,
-    pub vault_assets_account: VaultAssetsAccount<'info>,
-    pub user_token_account: AccountInfo<'info>,
-    pub assets_mint: AccountInfo<'info>,
-    pub authority: Signer<'info>,
-    pub spl_token_program: SplTokenProgramInfo<'info>,
-}
-
-impl<'info> SlashContext<'info> {
-    pub fn validate(self) -> Result<Self, ProgramError> {
-        let vault_pk = self.vault_info.as_ref().key;
-        let vault = self.vault_info.get()?;
-
-        require_eq!(
-            &vault.slash_admin,
-            self.authority.as_ref().key,
-            ProgramError::InvalidArgument
-        );
-
-        self.vault_assets_account.check_vault(vault_pk, &vault)?;
-
-        drop(vault);
-        Ok(self)
-    }
-    pub fn load(accounts: &[AccountInfo<'info>]) -> Result<Self, ProgramError> {
+    pub fn load(accounts: &'a [AccountInfo<'info>]) -> Result<Self, ProgramError> {
         let iter = &mut accounts.iter();
         Self {
-            vault_info: next_account_info(iter)?.try_into()?,
-            vault_assets_account: next_account_info(iter)?.try_into()?,
-            user_token_account: next_account_info(iter)?.clone(),
-            assets_mint: next_account_info(iter)?.clone(),
-            authority: next_account_info(iter)?.try_into()?,
-            spl_token_program: next_account_info(iter)?.try_into()?,
+            vault_info: next_account_info(iter)?,
+            admin: next_account_info(iter)?,
+            system_program: next_account_info(iter)?,
         }
         .validate()
     }
@@ -1,7 +1,38 @@
 pub mod vault_exact_deposit_operations;
 pub mod vault_fee_operations;
+pub mod vault_operation;
 pub mod vault_operations;
 
 pub use vault_exact_deposit_operations::*;
 pub use vault_fee_operations::*;
+pub use vault_operation::*;
 pub use vault_operations::*;
+
+/// Rounding direction of every division done by vault operations
+///
+/// | operation     | quantity                           | rounding |
+/// |---------------|------------------------------------|----------|
+/// | deposit       | shares minted for deposited assets | `Down`   |
+/// | redeem        | assets paid out for burned shares  | `Down`   |
+/// | exact deposit | shares minted for offered assets   | `Down`   |
+/// | exact deposit | assets charged for minted shares   | `Up`     |
+/// | fee           | fee taken from deposited assets    | `Down`   |
+///
+/// Every direction favours the vault: shares minted and assets paid out round
+/// down and assets charged round up, so the vault never gives away more than
+/// it receives. The exact deposit charges the assets backing the minted
+/// shares rounded up, which never exceeds the offered assets and bounds the
+/// loss of a depositor to an inflation attack by one atom. The fee is revenue
+/// of the fee account and does not affect the share price.
+///
+/// Operations must take the direction from this module; the rounding specs
+/// in `certora/specs/rounding.rs` check the table.
+pub mod rounding_policy {
+    use crate::utils::math::Rounding;
+
+    pub const DEPOSIT_SHARES: Rounding = Rounding::Down;
+    pub const REDEEM_ASSETS: Rounding = Rounding::Down;
+    pub const EXACT_DEPOSIT_SHARES: Rounding = Rounding::Down;
+    pub const EXACT_DEPOSIT_ASSETS: Rounding = Rounding::Up;
+    pub const FEE: Rounding = Rounding::Down;
+}
@@ -1,10 +1,12 @@
 use crate::{state::Vault, utils::math::FeeBps, VaultResult};
 
-use super::VaultEffect;
+use super::{rounding_policy, VaultEffect};
 
 pub fn vault_deposit_assets_exact(vault: &mut Vault, tkn_amt: u64) -> VaultResult<VaultEffect> {
-    let shares_to_user = vault.convert_assets_to_shares(tkn_amt)?;
-    let assets_to_vault = vault.convert_shares_to_assets(shares_to_user)?;
+    let shares_to_user =
+        vault.convert_assets_to_shares(tkn_amt, rounding_policy::EXACT_DEPOSIT_SHARES)?;
+    let assets_to_vault =
+        vault.convert_shares_to_assets(shares_to_user, rounding_policy::EXACT_DEPOSIT_ASSETS)?;
 
     vault.mint_shares(shares_to_user)?;
     vault.add_token(assets_to_vault)?;
@@ -22,13 +24,15 @@ pub fn vault_deposit_assets_with_fee_exact(
 ) -> VaultResult<VaultEffect> {
     let fee_bps: FeeBps = vault.fee_in_bps()?;
     // -- maximum possible fee
-    let gross = fee_bps.apply(tkn_amt)?;
+    let gross = fee_bps.apply(tkn_amt, rounding_policy::FEE)?;
 
-    let shares_to_user = vault.convert_assets_to_shares(gross.net_amount)?;
-    let assets_to_vault = vault.convert_shares_to_assets(shares_to_user)?;
+    let shares_to_user =
+        vault.convert_assets_to_shares(gross.net_amount, rounding_policy::EXACT_DEPOSIT_SHARES)?;
+    let assets_to_vault =
+        vault.convert_shares_to_assets(shares_to_user, rounding_policy::EXACT_DEPOSIT_ASSETS)?;
 
     // -- compute fee based on actual use
-    let actual_gross = fee_bps.apply(assets_to_vault)?;
+    let actual_gross = fee_bps.apply(assets_to_vault, rounding_policy::FEE)?;
 
     Ok(VaultEffect {
         shares_to_user,
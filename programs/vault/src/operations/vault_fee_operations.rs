@@ -1,12 +1,13 @@
 use crate::{state::Vault, utils::math::FeeBps, VaultResult};
 
-use super::VaultEffect;
+use super::{rounding_policy, VaultEffect};
 
 pub fn vault_deposit_assets_with_fee(vault: &mut Vault, tkn_amt: u64) -> VaultResult<VaultEffect> {
     let fee_bps: FeeBps = vault.fee_in_bps()?;
-    let gross = fee_bps.apply(tkn_amt)?;
+    let gross = fee_bps.apply(tkn_amt, rounding_policy::FEE)?;
 
-    let shares_to_user = vault.convert_assets_to_shares(gross.net_amount)?;
+    let shares_to_user =
+        vault.convert_assets_to_shares(gross.net_amount, rounding_policy::DEPOSIT_SHARES)?;
 
     vault.mint_shares(shares_to_user)?;
     vault.add_token(gross.net_amount)?;
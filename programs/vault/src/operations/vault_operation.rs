@@ -0,0 +1,122 @@
+use crate::{state::Vault, VaultResult};
+
+use super::*;
+
+/// A vault operation with its arguments
+///
+/// Every operation of this module as a value, so that the processors, the
+/// specs, the fuzz targets and off-chain tools run them the same way.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum VaultOperation {
+    Deposit {
+        amount: u64,
+    },
+    DepositExact {
+        amount: u64,
+    },
+    DepositWithFee {
+        amount: u64,
+    },
+    DepositWithFeeExact {
+        amount: u64,
+    },
+    Redeem {
+        shares: u64,
+    },
+    /// `balance` is the balance of the vault assets account
+    UpdateReward {
+        balance: u64,
+    },
+    Slash {
+        amount: u64,
+    },
+    CollectFee,
+}
+
+impl VaultOperation {
+    #[inline(always)]
+    pub fn apply(&self, vault: &mut Vault) -> VaultResult<VaultEffect> {
+        match *self {
+            VaultOperation::Deposit { amount } => vault_deposit_assets(vault, amount),
+            VaultOperation::DepositExact { amount } => vault_deposit_assets_exact(vault, amount),
+            VaultOperation::DepositWithFee { amount } => {
+                vault_deposit_assets_with_fee(vault, amount)
+            }
+            VaultOperation::DepositWithFeeExact { amount } => {
+                vault_deposit_assets_with_fee_exact(vault, amount)
+            }
+            VaultOperation::Redeem { shares } => vault_redeem_shares(vault, shares),
+            VaultOperation::UpdateReward { balance } => vault_update_reward(vault, balance),
+            VaultOperation::Slash { amount } => vault_process_slash(vault, amount),
+            VaultOperation::CollectFee => vault_collect_fee(vault),
+        }
+    }
+
+    /// Effect of `apply` on `vault`, leaving `vault` untouched
+    #[inline(always)]
+    pub fn preview(&self, vault: &Vault) -> VaultResult<VaultEffect> {
+        let mut vault = *vault;
+        self.apply(&mut vault)
+    }
+
+    pub fn is_deposit(&self) -> bool {
+        matches!(
+            self,
+            VaultOperation::Deposit { .. }
+                | VaultOperation::DepositExact { .. }
+                | VaultOperation::DepositWithFee { .. }
+                | VaultOperation::DepositWithFeeExact { .. }
+        )
+    }
+
+    /// The amount argument, tokens or shares depending on the operation
+    pub fn amount(&self) -> Option<u64> {
+        match *self {
+            VaultOperation::Deposit { amount }
+            | VaultOperation::DepositExact { amount }
+            | VaultOperation::DepositWithFee { amount }
+            | VaultOperation::DepositWithFeeExact { amount }
+            | VaultOperation::Slash { amount } => Some(amount),
+            VaultOperation::Redeem { shares } => Some(shares),
+            VaultOperation::UpdateReward { balance } => Some(balance),
+            VaultOperation::CollectFee => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::VaultBuilder;
+
+    #[test]
+    fn test_preview_matches_apply() {
+        let vault = VaultBuilder::appreciated().fee_bps(100).build();
+        for op in [
+            VaultOperation::Deposit { amount: 300 },
+            VaultOperation::DepositExact { amount: 300 },
+            VaultOperation::DepositWithFee { amount: 300 },
+            VaultOperation::DepositWithFeeExact { amount: 300 },
+            VaultOperation::Redeem { shares: 300 },
+            VaultOperation::UpdateReward { balance: 1_800 },
+            VaultOperation::Slash { amount: 300 },
+            VaultOperation::CollectFee,
+        ] {
+            let mut applied = vault;
+            let effect = op.apply(&mut applied).unwrap();
+            assert_eq!(op.preview(&vault), Ok(effect), "{:?}", op);
+        }
+    }
+
+    #[test]
+    fn test_deposit_exact_charges_rounded_up() {
+        let mut vault = VaultBuilder::appreciated().build();
+        let effect = VaultOperation::DepositExact { amount: 101 }
+            .apply(&mut vault)
+            .unwrap();
+        // -- 67 shares are worth 100.5 assets
+        assert_eq!(effect.shares_to_user, 67);
+        assert_eq!(effect.assets_to_vault, 101);
+    }
+}
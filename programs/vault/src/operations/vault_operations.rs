@@ -1,8 +1,12 @@
+use bytemuck::{Pod, Zeroable};
+
 use crate::{state::Vault, VaultError, VaultResult};
 
-/// Vault operations
+use super::rounding_policy;
 
-#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+/// Changes to token balances requested by a vault operation
+#[repr(C)]
+#[derive(Default, Pod, Zeroable, Copy, Clone, PartialEq, Eq, Debug)]
 pub struct VaultEffect {
     pub shares_to_burn: u64,
     pub shares_to_user: u64,
@@ -15,7 +19,8 @@ pub fn vault_deposit_assets(vault: &mut Vault, tkn_amt: u64) -> VaultResult<Vaul
     //let fee = vault.fee_in_bps()?;
     //require!(fee.is_zero(), crate::errors::VaultError::GuardFail);
 
-    let shares_to_user = vault.convert_assets_to_shares(tkn_amt)?;
+    let shares_to_user =
+        vault.convert_assets_to_shares(tkn_amt, rounding_policy::DEPOSIT_SHARES)?;
 
     vault.mint_shares(shares_to_user)?;
     vault.add_token(tkn_amt)?;
@@ -28,7 +33,8 @@ pub fn vault_deposit_assets(vault: &mut Vault, tkn_amt: u64) -> VaultResult<Vaul
 }
 
 pub fn vault_redeem_shares(vault: &mut Vault, shares_amt: u64) -> VaultResult<VaultEffect> {
-    let assets_to_user = vault.convert_shares_to_assets(shares_amt)?;
+    let assets_to_user =
+        vault.convert_shares_to_assets(shares_amt, rounding_policy::REDEEM_ASSETS)?;
     vault.burn_shares(shares_amt)?;
     vault.del_token(assets_to_user)?;
 
@@ -62,23 +68,12 @@ pub fn vault_process_slash(vault: &mut Vault, slash_amt: u64) -> VaultResult<Vau
 #[cfg(test)]
 mod tests {
     use super::*;
-    use solana_program::pubkey::Pubkey;
+    use crate::state::VaultBuilder;
 
     fn new_test_vault() -> Vault {
-        Vault {
-            admin: Pubkey::default(),
-            slash_admin: Pubkey::default(),
-            shares_mint: Pubkey::default(),
-            vault_shares_mint_bump: 0,
-            assets_mint: Pubkey::default(),
-            shares: 100u64.into(),
-            assets: 100u64.into(),
-            vault_assets_account: Pubkey::default(),
-            vault_assets_account_bump: 0,
-            fee_amount: 0u64.into(),
-            fee_bps: 500u64.into(), // 5%
-            fee_token_account: Pubkey::default(),
-        }
+        VaultBuilder::one_to_one(100)
+            .fee_bps(500) // 5%
+            .build()
     }
 
     #[test]
@@ -1,25 +1,52 @@
-pub fn process_collect_fee(accounts: &[AccountInfo]) -> ProgramResult {
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+
+use crate::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
+    loaders::CollectFeeContext,
+    operations::VaultOperation,
+    vault_assets_account_seeds_with_bump,
+};
+
+use super::{set_effect_return_data, TokenBackend};
+
+pub fn process_collect_fee<T: TokenBackend>(accounts: &[AccountInfo]) -> ProgramResult {
     let CollectFeeContext {
         vault_info,
         vault_assets_account,
         assets_mint,
         fee_collect_account,
-        authority: _,
+        authority,
         spl_token_program,
     } = CollectFeeContext::load(accounts)?;
 
-    let effect = {
+    let (effect, pre, post) = {
         let mut vault = vault_info.get_mut()?;
-        vault_collect_fee(&mut vault)?
+        let pre = VaultSnapshot::from(&*vault);
+        let effect = VaultOperation::CollectFee.apply(&mut vault)?;
+        (effect, pre, VaultSnapshot::from(&*vault))
     };
 
-    spl_transfer_assets_from_vault(
+    let vault_pk = vault_info.as_ref().key;
+    let vault_assets_bump = vault_info.get()?.vault_assets_account_bump;
+
+    T::transfer_out(
         effect.assets_to_user,
-        &vault_assets_account,
-        &fee_collect_account,
-        &assets_mint,
+        vault_assets_account,
+        fee_collect_account,
+        assets_mint,
         spl_token_program.as_ref(),
+        vault_assets_account_seeds_with_bump!(vault_pk, vault_assets_bump),
     )?;
 
+    VaultEvent {
+        vault: *vault_info.as_ref().key,
+        actor: *authority.key,
+        effect,
+        pre,
+        post,
+    }
+    .emit(VaultEventKind::CollectFee);
+    set_effect_return_data(&effect);
+
     Ok(())
-}
\ No newline at end of file
+}
@@ -3,13 +3,18 @@ use solana_program::{
 };
 
 use crate::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
     loaders::DepositContext,
-    operations::vault_deposit_assets_exact,
-    processor::{spl_mint_shares, spl_transfer_assets_from_user},
+    operations::VaultOperation,
+    processor::{set_effect_return_data, TokenBackend},
     utils::guards::require_ne,
+    vault_shares_mint_seeds_with_bump,
 };
 
-pub fn process_deposit_exact(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+pub fn process_deposit_exact<T: TokenBackend>(
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
     let DepositContext {
         vault_info,
         vault_assets_account,
@@ -22,31 +27,49 @@ pub fn process_deposit_exact(accounts: &[AccountInfo], amount: u64) -> ProgramRe
     } = DepositContext::load(accounts)?;
 
     require_ne!(
-        vault_assets_account.as_ref().key,
+        vault_assets_account.key,
         user_assets_account.key,
         crate::errors::VaultError::SelfTransfer.into()
     );
 
-    let effect = {
+    let (effect, pre, post) = {
         let mut vault = vault_info.get_mut()?;
-        vault_deposit_assets_exact(&mut vault, amount).map_err(|e| -> ProgramError { e.into() })?
+        let pre = VaultSnapshot::from(&*vault);
+        let effect = VaultOperation::DepositExact { amount }
+            .apply(&mut vault)
+            .map_err(|e| -> ProgramError { e.into() })?;
+        (effect, pre, VaultSnapshot::from(&*vault))
     };
 
-    spl_transfer_assets_from_user(
+    let vault_pk = vault_info.as_ref().key;
+    let shares_mint_bump = vault_info.get()?.vault_shares_mint_bump;
+
+    T::transfer_in(
         effect.assets_to_vault,
-        vault_assets_account.as_ref(),
-        &user_assets_account,
-        &assets_mint,
-        authority.as_ref(),
+        vault_assets_account,
+        user_assets_account,
+        assets_mint,
+        authority,
         spl_token_program.as_ref(),
     )?;
 
-    spl_mint_shares(
+    T::mint(
         effect.shares_to_user,
-        &user_shares_account,
-        shares_mint.as_ref(),
+        user_shares_account,
+        shares_mint,
         spl_token_program.as_ref(),
+        vault_shares_mint_seeds_with_bump!(vault_pk, shares_mint_bump),
     )?;
 
+    VaultEvent {
+        vault: *vault_info.as_ref().key,
+        actor: *authority.key,
+        effect,
+        pre,
+        post,
+    }
+    .emit(VaultEventKind::DepositExact);
+    set_effect_return_data(&effect);
+
     Ok(())
 }
@@ -1,7 +1,9 @@
 use crate::{
-    operations::vault_deposit_assets_with_fee,
-    processor::{spl_mint_shares, spl_transfer_assets_from_user},
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
+    operations::VaultOperation,
+    processor::{set_effect_return_data, TokenBackend},
     utils::guards::require_ne,
+    vault_shares_mint_seeds_with_bump,
 };
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
@@ -9,7 +11,10 @@ use solana_program::{
 
 use crate::loaders::DepositWithFeeContext;
 
-pub fn process_deposit_with_fee(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+pub fn process_deposit_with_fee<T: TokenBackend>(
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
     let DepositWithFeeContext {
         vault_info,
         vault_assets_account,
@@ -28,38 +33,55 @@ pub fn process_deposit_with_fee(accounts: &[AccountInfo], amount: u64) -> Progra
         crate::errors::VaultError::SelfTransfer.into()
     );
 
-    let effect = {
+    let (effect, pre, post) = {
         let mut vault = vault_info.get_mut()?;
-        vault_deposit_assets_with_fee(&mut vault, amount)
-            .map_err(|e| -> ProgramError { e.into() })?
+        let pre = VaultSnapshot::from(&*vault);
+        let effect = VaultOperation::DepositWithFee { amount }
+            .apply(&mut vault)
+            .map_err(|e| -> ProgramError { e.into() })?;
+        (effect, pre, VaultSnapshot::from(&*vault))
     };
 
+    let vault_pk = vault_info.as_ref().key;
+    let shares_mint_bump = vault_info.get()?.vault_shares_mint_bump;
+
     // -- transfer assets into vault
-    spl_transfer_assets_from_user(
+    T::transfer_in(
         effect.assets_to_vault,
-        &vault_assets_account,
-        &user_assets_account,
-        &assets_mint,
-        authority.as_ref(),
+        vault_assets_account,
+        user_assets_account,
+        assets_mint,
+        authority,
         spl_token_program.as_ref(),
     )?;
 
     // -- transfer fee from user to vault
-    spl_transfer_assets_from_user(
+    T::transfer_in(
         effect.assets_to_fee,
-        &vault_fee_account,
-        &user_assets_account,
-        &assets_mint,
-        authority.as_ref(),
+        vault_fee_account,
+        user_assets_account,
+        assets_mint,
+        authority,
         spl_token_program.as_ref(),
     )?;
 
-    spl_mint_shares(
+    T::mint(
         effect.shares_to_user,
-        &user_shares_account,
-        &shares_mint,
+        user_shares_account,
+        shares_mint,
         spl_token_program.as_ref(),
+        vault_shares_mint_seeds_with_bump!(vault_pk, shares_mint_bump),
     )?;
 
+    VaultEvent {
+        vault: *vault_info.as_ref().key,
+        actor: *authority.key,
+        effect,
+        pre,
+        post,
+    }
+    .emit(VaultEventKind::DepositWithFee);
+    set_effect_return_data(&effect);
+
     Ok(())
 }
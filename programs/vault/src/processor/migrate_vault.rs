@@ -0,0 +1,188 @@
+use std::mem::size_of;
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke, rent::Rent,
+    system_instruction, sysvar::Sysvar,
+};
+
+use crate::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
+    loaders::MigrateVaultContext,
+    operations::VaultEffect,
+    processor::set_effect_return_data,
+    state::legacy::migrate_vault,
+    state::Vault,
+};
+
+pub fn process_migrate_vault(accounts: &[AccountInfo]) -> ProgramResult {
+    let MigrateVaultContext {
+        vault_info,
+        admin,
+        system_program,
+    } = MigrateVaultContext::load(accounts)?;
+
+    let vault = migrate_vault(&vault_info.try_borrow_data()?)?;
+    vault.validate()?;
+
+    // -- admin pays for the larger account
+    let min_balance = Rent::get()?.minimum_balance(size_of::<Vault>());
+    let top_up = min_balance.saturating_sub(vault_info.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(admin.key, vault_info.key, top_up),
+            &[admin.clone(), vault_info.clone(), system_program.clone()],
+        )?;
+    }
+
+    vault_info.realloc(size_of::<Vault>(), false)?;
+    vault_info
+        .try_borrow_mut_data()?
+        .copy_from_slice(bytemuck::bytes_of(&vault));
+
+    // -- migration does not change the totals
+    let effect = VaultEffect::default();
+    let snapshot = VaultSnapshot::from(&vault);
+    VaultEvent {
+        vault: *vault_info.key,
+        actor: *admin.key,
+        effect,
+        pre: snapshot,
+        post: snapshot,
+    }
+    .emit(VaultEventKind::MigrateVault);
+    set_effect_return_data(&effect);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{
+        entrypoint::{deserialize, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER},
+        pubkey::Pubkey,
+        system_program,
+    };
+    use spl_pod::primitives::PodU64;
+
+    use super::*;
+    use crate::{
+        client,
+        events::decode_event,
+        processor::process_instruction,
+        state::{legacy::VaultV0, VAULT_VERSION},
+        test_support::{RecordingStubs, TestAccount},
+    };
+
+    /// Accounts in the input layout of the runtime, which leaves room after
+    /// the data of each account for `realloc`
+    fn serialize(accounts: &[TestAccount]) -> Vec<u64> {
+        let mut input = (accounts.len() as u64).to_le_bytes().to_vec();
+        for account in accounts {
+            input.extend_from_slice(&[
+                NON_DUP_MARKER,
+                account.is_signer.into(),
+                account.is_writable.into(),
+                account.executable.into(),
+            ]);
+            // -- original data length, filled in by `deserialize`
+            input.extend_from_slice(&[0; 4]);
+            input.extend_from_slice(account.key.as_ref());
+            input.extend_from_slice(account.owner.as_ref());
+            input.extend_from_slice(&account.lamports.to_le_bytes());
+            input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            input.extend_from_slice(&account.data);
+            input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            input.resize(input.len().next_multiple_of(8), 0);
+            // -- rent epoch
+            input.extend_from_slice(&0u64.to_le_bytes());
+        }
+        // -- no instruction data, and the program id
+        input.extend_from_slice(&0u64.to_le_bytes());
+        input.extend_from_slice(crate::id().as_ref());
+
+        let mut aligned = vec![0u64; input.len().div_ceil(8)];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut aligned)[..input.len()].copy_from_slice(&input);
+        aligned
+    }
+
+    fn legacy_vault() -> VaultV0 {
+        VaultV0 {
+            admin: Pubkey::new_unique(),
+            slash_admin: Pubkey::new_unique(),
+            shares_mint: Pubkey::new_unique(),
+            vault_shares_mint_bump: 254,
+            assets_mint: Pubkey::new_unique(),
+            shares: PodU64::from(1_000),
+            assets: PodU64::from(1_500),
+            fee_bps: PodU64::from(100),
+            fee_amount: PodU64::from(7),
+            fee_token_account: Pubkey::new_unique(),
+            vault_assets_account: Pubkey::new_unique(),
+            vault_assets_account_bump: 253,
+        }
+    }
+
+    #[test]
+    fn test_migrate_vault_v0() {
+        RecordingStubs::install();
+        let rent = Rent::default();
+        let v0 = legacy_vault();
+        let vault_pk = Pubkey::new_unique();
+        let v0_lamports = rent.minimum_balance(size_of::<VaultV0>());
+        let admin_lamports = 1_000_000_000;
+        let accounts = [
+            TestAccount::new(vault_pk, crate::id())
+                .data(bytemuck::bytes_of(&v0).to_vec())
+                .lamports(v0_lamports)
+                .writable(),
+            TestAccount::new(v0.admin, system_program::id())
+                .lamports(admin_lamports)
+                .signer()
+                .writable(),
+            TestAccount::new(system_program::id(), Pubkey::default()).executable(),
+        ];
+        let ix = client::migrate_vault(&vault_pk, &v0.admin);
+        assert!(ix
+            .accounts
+            .iter()
+            .zip(&accounts)
+            .all(|(meta, account)| meta.pubkey == account.key));
+
+        let mut input = serialize(&accounts);
+        let (_, infos, _) = unsafe { deserialize(input.as_mut_ptr() as *mut u8) };
+        assert_eq!(infos[0].data_len(), 226);
+        process_instruction(&crate::id(), &infos, &ix.data).unwrap();
+
+        let (vault_info, admin) = (&infos[0], &infos[1]);
+        let top_up = rent.minimum_balance(size_of::<Vault>()) - v0_lamports;
+        assert!(top_up > 0);
+        assert_eq!(vault_info.lamports(), v0_lamports + top_up);
+        assert_eq!(admin.lamports(), admin_lamports - top_up);
+        assert_eq!(vault_info.data_len(), 355);
+
+        let data = vault_info.try_borrow_data().unwrap();
+        assert_eq!(data[0], VAULT_VERSION);
+        assert_eq!(&data[..], bytemuck::bytes_of(&Vault::from(&v0)));
+        let vault: &Vault = bytemuck::from_bytes(&data);
+        assert_eq!(vault.admin, v0.admin);
+        assert_eq!(vault.shares_mint, v0.shares_mint);
+        assert_eq!(vault.vault_assets_account, v0.vault_assets_account);
+        assert_eq!(vault.num_shares(), 1_000);
+        assert_eq!(vault.num_assets(), 1_500);
+        assert_eq!(vault.fee_amount(), 7);
+        assert_eq!(vault.vault_assets_account_bump, 253);
+
+        let logged = RecordingStubs::take_logged_data();
+        let [fields] = &logged[..] else {
+            panic!("expected one event, logged {:?}", logged);
+        };
+        let fields: Vec<&[u8]> = fields.iter().map(Vec::as_slice).collect();
+        let (kind, event) = decode_event(&fields).unwrap();
+        let snapshot = VaultSnapshot::from(vault);
+        assert_eq!(kind, VaultEventKind::MigrateVault);
+        assert_eq!(event.vault, vault_pk);
+        assert_eq!(event.actor, v0.admin);
+        assert_eq!(event.effect, VaultEffect::default());
+        assert_eq!((event.pre, event.post), (snapshot, snapshot));
+    }
+}
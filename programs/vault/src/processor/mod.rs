@@ -1,18 +1,162 @@
+pub mod collect_fee;
 pub mod deposit;
 pub mod deposit_exact;
 pub mod deposit_with_fee;
 pub mod deposit_with_fee_exact;
+pub mod migrate_vault;
 pub mod redeem_shares;
+pub mod return_data;
 pub mod slash;
+pub mod token_backend;
 pub mod update_reward;
 
-pub mod spl_token_utils;
-
+pub use collect_fee::*;
 pub use deposit::*;
 pub use deposit_exact::*;
 pub use deposit_with_fee::*;
 pub use deposit_with_fee_exact::*;
+pub use migrate_vault::*;
 pub use redeem_shares::*;
+pub use return_data::*;
 pub use slash::*;
-pub use spl_token_utils::*;
+pub use token_backend::*;
 pub use update_reward::*;
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{instruction::VaultInstruction, utils::guards::require_eq};
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    process_instruction_with::<SplTokenBackend>(program_id, accounts, instruction_data)
+}
+
+/// `process_instruction` with token operations run by `T`
+pub fn process_instruction_with<T: TokenBackend>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    require_eq!(program_id, &crate::id(), ProgramError::IncorrectProgramId);
+
+    match VaultInstruction::unpack(instruction_data)? {
+        // -- `vault_deposit_assets_with_fee_exact` does not record the shares
+        // it mints in the vault yet
+        VaultInstruction::DepositWithFeeExact { .. } => Err(ProgramError::InvalidInstructionData),
+        instruction => process_vault_instruction::<T>(accounts, instruction),
+    }
+}
+
+/// Run the processor of `instruction` on `accounts`
+///
+/// Runs every processor, including those `process_instruction` rejects, so
+/// that the specs can check them.
+pub fn process_vault_instruction<T: TokenBackend>(
+    accounts: &[AccountInfo],
+    instruction: VaultInstruction,
+) -> ProgramResult {
+    match instruction {
+        // -- vaults are created off-chain for now
+        VaultInstruction::CreateVault => Err(ProgramError::InvalidInstructionData),
+        VaultInstruction::Deposit { amount } => process_deposit::<T>(accounts, amount),
+        VaultInstruction::DepositWithFee { amount } => {
+            process_deposit_with_fee::<T>(accounts, amount)
+        }
+        VaultInstruction::MigrateVault => process_migrate_vault(accounts),
+        VaultInstruction::DepositExact { amount } => process_deposit_exact::<T>(accounts, amount),
+        VaultInstruction::DepositWithFeeExact { amount } => {
+            process_deposit_with_fee_exact::<T>(accounts, amount)
+        }
+        VaultInstruction::RedeemShares { amount } => process_redeem_shares::<T>(accounts, amount),
+        VaultInstruction::UpdateReward => process_update_reward::<T>(accounts),
+        VaultInstruction::Slash { amount } => process_slash::<T>(accounts, amount),
+        VaultInstruction::CollectFee => process_collect_fee::<T>(accounts),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::program_error::ProgramError;
+
+    use crate::{client, state::VaultBuilder, test_support::VaultFixture};
+
+    /// A vault without assets and a user holding 10_000 assets
+    fn setup() -> VaultFixture {
+        let mut setup = VaultFixture::unique(VaultBuilder::new());
+        setup.bank.mint_to(&setup.user.assets_account, 10_000);
+        setup
+    }
+
+    #[test]
+    fn test_deposit_reward_slash_redeem() {
+        let mut setup = setup();
+        let vault = setup.vault;
+        let (vault_pk, user) = (setup.vault_pk, setup.user);
+
+        let ix = client::deposit(&vault_pk, &vault, &user, &spl_token::id(), 1_000);
+        setup.bank.process(&ix).unwrap();
+        assert_eq!(setup.bank.balance(&user.shares_account), 1_000);
+
+        // -- reward paid straight into the vault token account
+        setup.bank.mint_to(&vault.vault_assets_account, 500);
+        let ix = client::update_reward(&vault_pk, &vault);
+        setup.bank.process(&ix).unwrap();
+        assert_eq!(setup.stored_vault().num_assets(), 1_500);
+
+        let ix = client::slash(
+            &vault_pk,
+            &vault,
+            &setup.slashed_account,
+            &spl_token::id(),
+            300,
+        );
+        setup.bank.process(&ix).unwrap();
+        assert_eq!(setup.bank.balance(&setup.slashed_account), 300);
+
+        let ix = client::redeem_shares(&vault_pk, &vault, &user, &spl_token::id(), 500);
+        setup.bank.process(&ix).unwrap();
+
+        let bank = &setup.bank;
+        assert_eq!(bank.balance(&user.assets_account), 10_000 - 1_000 + 600);
+        assert_eq!(bank.balance(&user.shares_account), 500);
+        assert_eq!(bank.balance(&vault.vault_assets_account), 600);
+        assert_eq!(bank.supply(&vault.shares_mint), 500);
+        assert_eq!(setup.stored_vault().num_assets(), 600);
+        assert_eq!(setup.stored_vault().num_shares(), 500);
+    }
+
+    #[test]
+    fn test_redeem_more_than_balance_fails() {
+        let mut setup = setup();
+        let vault = setup.vault;
+        let (vault_pk, user) = (setup.vault_pk, setup.user);
+
+        let ix = client::deposit(&vault_pk, &vault, &user, &spl_token::id(), 100);
+        setup.bank.process(&ix).unwrap();
+        let ix = client::redeem_shares(&vault_pk, &vault, &user, &spl_token::id(), 101);
+
+        assert!(setup.bank.process(&ix).is_err());
+        assert_eq!(setup.bank.balance(&user.assets_account), 10_000 - 100);
+    }
+
+    #[test]
+    fn test_deposit_with_fee_exact_is_rejected() {
+        let mut setup = setup();
+        let vault = setup.vault;
+        let (vault_pk, user) = (setup.vault_pk, setup.user);
+
+        let ix = client::deposit_with_fee_exact(&vault_pk, &vault, &user, &spl_token::id(), 1_000);
+        assert_eq!(
+            setup.bank.process(&ix),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(setup.bank.balance(&user.shares_account), 0);
+        assert_eq!(setup.stored_vault().num_shares(), 0);
+    }
+}
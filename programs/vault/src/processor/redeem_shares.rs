@@ -1,13 +1,18 @@
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
 
 use crate::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
     loaders::RedeemSharesContext,
-    operations::vault_redeem_shares,
-    processor::{spl_burn_shares, spl_transfer_assets_from_vault},
+    operations::VaultOperation,
+    processor::{set_effect_return_data, TokenBackend},
     utils::guards::require_ne,
+    vault_assets_account_seeds_with_bump,
 };
 
-pub fn process_redeem_shares(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+pub fn process_redeem_shares<T: TokenBackend>(
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
     let context = RedeemSharesContext::load(accounts)?;
     let RedeemSharesContext {
         vault_info,
@@ -26,26 +31,42 @@ pub fn process_redeem_shares(accounts: &[AccountInfo], amount: u64) -> ProgramRe
         crate::errors::VaultError::SelfTransfer.into()
     );
 
-    let effect = {
+    let (effect, pre, post) = {
         let mut vault = vault_info.get_mut()?;
-        vault_redeem_shares(&mut vault, amount)?
+        let pre = VaultSnapshot::from(&*vault);
+        let effect = VaultOperation::Redeem { shares: amount }.apply(&mut vault)?;
+        (effect, pre, VaultSnapshot::from(&*vault))
     };
 
-    spl_burn_shares(
+    let vault_pk = vault_info.as_ref().key;
+    let vault_assets_bump = vault_info.get()?.vault_assets_account_bump;
+
+    T::burn(
         effect.shares_to_burn,
-        &user_shares_account,
-        shares_mint.as_ref(),
-        authority.as_ref(),
+        user_shares_account,
+        shares_mint,
+        authority,
         spl_token_program.as_ref(),
     )?;
 
-    spl_transfer_assets_from_vault(
+    T::transfer_out(
         effect.assets_to_user,
-        &vault_assets_account,
-        &user_assets_account,
-        &assets_mint,
+        vault_assets_account,
+        user_assets_account,
+        assets_mint,
         spl_token_program.as_ref(),
+        vault_assets_account_seeds_with_bump!(vault_pk, vault_assets_bump),
     )?;
 
+    VaultEvent {
+        vault: *vault_info.as_ref().key,
+        actor: *authority.key,
+        effect,
+        pre,
+        post,
+    }
+    .emit(VaultEventKind::RedeemShares);
+    set_effect_return_data(&effect);
+
     Ok(())
 }
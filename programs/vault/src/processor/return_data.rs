@@ -0,0 +1,8 @@
+/// Return `effect` to the caller of the instruction
+///
+/// Programs calling the vault through CPI read it with `get_return_data`.
+#[cfg_attr(feature = "certora",
+    cvlr::mock_fn(with=crate::certora::mocks::processor::set_effect_return_data))]
+pub fn set_effect_return_data(effect: &crate::operations::VaultEffect) {
+    solana_program::program::set_return_data(bytemuck::bytes_of(effect));
+}
@@ -1,31 +1,52 @@
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
 
-use crate::{loaders::SlashContext, operations::vault_process_slash};
+use crate::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
+    loaders::SlashContext,
+    operations::VaultOperation,
+    vault_assets_account_seeds_with_bump,
+};
 
-use super::spl_transfer_assets_from_vault;
+use super::{set_effect_return_data, TokenBackend};
 
-pub fn process_slash(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+pub fn process_slash<T: TokenBackend>(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
     let SlashContext {
         vault_info,
         vault_assets_account,
         user_token_account,
         assets_mint,
-        authority: _,
+        authority,
         spl_token_program,
     } = SlashContext::load(accounts)?;
 
-    let effects = {
+    let (effect, pre, post) = {
         let mut vault = vault_info.get_mut()?;
-        vault_process_slash(&mut vault, amount)?
+        let pre = VaultSnapshot::from(&*vault);
+        let effect = VaultOperation::Slash { amount }.apply(&mut vault)?;
+        (effect, pre, VaultSnapshot::from(&*vault))
     };
 
-    spl_transfer_assets_from_vault(
-        effects.assets_to_user,
-        vault_assets_account.as_ref(),
-        &user_token_account,
-        &assets_mint,
+    let vault_pk = vault_info.as_ref().key;
+    let vault_assets_bump = vault_info.get()?.vault_assets_account_bump;
+
+    T::transfer_out(
+        effect.assets_to_user,
+        vault_assets_account,
+        user_token_account,
+        assets_mint,
         spl_token_program.as_ref(),
+        vault_assets_account_seeds_with_bump!(vault_pk, vault_assets_bump),
     )?;
 
+    VaultEvent {
+        vault: *vault_info.as_ref().key,
+        actor: *authority.key,
+        effect,
+        pre,
+        post,
+    }
+    .emit(VaultEventKind::Slash);
+    set_effect_return_data(&effect);
+
     Ok(())
 }
@@ -0,0 +1,467 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as Token2022Account, Mint as Token2022Mint},
+};
+
+/// Token operations of the processors
+///
+/// Processors are generic over the backend; `process_instruction` uses
+/// `SplTokenBackend`. PDAs of the vault sign with `signer_seeds`: the vault
+/// assets account is its own owner and the shares mint is its own mint
+/// authority.
+pub trait TokenBackend {
+    /// Transfer `amount` from a user token account into the vault
+    fn transfer_in<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult;
+
+    /// Transfer `amount` out of the vault assets account
+    fn transfer_out<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult;
+
+    /// Mint `amount` shares to a user
+    fn mint<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult;
+
+    /// Burn `amount` shares of a user
+    fn burn<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult;
+
+    fn balance_of(info: &AccountInfo) -> Result<u64, ProgramError>;
+}
+
+/// CPI into the SPL token program
+///
+/// Instructions that pass the Token-2022 program, and token accounts it
+/// owns, go through `SplToken2022Backend` instead.
+pub struct SplTokenBackend;
+
+fn is_token_2022(program: &Pubkey) -> bool {
+    *program == spl_token_2022::id()
+}
+
+impl TokenBackend for SplTokenBackend {
+    fn transfer_in<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        if is_token_2022(token_program.key) {
+            return SplToken2022Backend::transfer_in(
+                amount,
+                vault_assets,
+                user_assets,
+                mint,
+                authority,
+                token_program,
+            );
+        }
+        let ix = spl_token::instruction::transfer(
+            token_program.key,
+            user_assets.key,
+            vault_assets.key,
+            authority.key,
+            &[],
+            amount,
+        )?;
+        invoke(
+            &ix,
+            &[
+                user_assets.clone(),
+                vault_assets.clone(),
+                authority.clone(),
+                token_program.clone(),
+            ],
+        )
+    }
+
+    fn transfer_out<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        if is_token_2022(token_program.key) {
+            return SplToken2022Backend::transfer_out(
+                amount,
+                vault_assets,
+                user_assets,
+                mint,
+                token_program,
+                signer_seeds,
+            );
+        }
+        let ix = spl_token::instruction::transfer(
+            token_program.key,
+            vault_assets.key,
+            user_assets.key,
+            vault_assets.key,
+            &[],
+            amount,
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                vault_assets.clone(),
+                user_assets.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
+    fn mint<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        if is_token_2022(token_program.key) {
+            return SplToken2022Backend::mint(
+                amount,
+                user_shares,
+                mint,
+                token_program,
+                signer_seeds,
+            );
+        }
+        let ix = spl_token::instruction::mint_to(
+            token_program.key,
+            mint.key,
+            user_shares.key,
+            mint.key,
+            &[],
+            amount,
+        )?;
+        invoke_signed(
+            &ix,
+            &[mint.clone(), user_shares.clone(), token_program.clone()],
+            &[signer_seeds],
+        )
+    }
+
+    fn burn<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        if is_token_2022(token_program.key) {
+            return SplToken2022Backend::burn(amount, user_shares, mint, authority, token_program);
+        }
+        let ix = spl_token::instruction::burn(
+            token_program.key,
+            user_shares.key,
+            mint.key,
+            authority.key,
+            &[],
+            amount,
+        )?;
+        invoke(
+            &ix,
+            &[
+                user_shares.clone(),
+                mint.clone(),
+                authority.clone(),
+                token_program.clone(),
+            ],
+        )
+    }
+
+    fn balance_of(info: &AccountInfo) -> Result<u64, ProgramError> {
+        if is_token_2022(info.owner) {
+            return SplToken2022Backend::balance_of(info);
+        }
+        Ok(spl_token::state::Account::unpack(&info.try_borrow_data()?)?.amount)
+    }
+}
+
+/// CPI into the Token-2022 program
+///
+/// Transfers use `transfer_checked`. Extensions that change the transferred
+/// amount, such as transfer fees, are not accounted for by the vault.
+pub struct SplToken2022Backend;
+
+impl SplToken2022Backend {
+    fn decimals(mint: &AccountInfo) -> Result<u8, ProgramError> {
+        let data = mint.try_borrow_data()?;
+        Ok(StateWithExtensions::<Token2022Mint>::unpack(&data)?
+            .base
+            .decimals)
+    }
+}
+
+impl TokenBackend for SplToken2022Backend {
+    fn transfer_in<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        let ix = spl_token_2022::instruction::transfer_checked(
+            token_program.key,
+            user_assets.key,
+            mint.key,
+            vault_assets.key,
+            authority.key,
+            &[],
+            amount,
+            Self::decimals(mint)?,
+        )?;
+        invoke(
+            &ix,
+            &[
+                user_assets.clone(),
+                mint.clone(),
+                vault_assets.clone(),
+                authority.clone(),
+                token_program.clone(),
+            ],
+        )
+    }
+
+    fn transfer_out<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let ix = spl_token_2022::instruction::transfer_checked(
+            token_program.key,
+            vault_assets.key,
+            mint.key,
+            user_assets.key,
+            vault_assets.key,
+            &[],
+            amount,
+            Self::decimals(mint)?,
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                vault_assets.clone(),
+                mint.clone(),
+                user_assets.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
+    fn mint<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let ix = spl_token_2022::instruction::mint_to(
+            token_program.key,
+            mint.key,
+            user_shares.key,
+            mint.key,
+            &[],
+            amount,
+        )?;
+        invoke_signed(
+            &ix,
+            &[mint.clone(), user_shares.clone(), token_program.clone()],
+            &[signer_seeds],
+        )
+    }
+
+    fn burn<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        let ix = spl_token_2022::instruction::burn(
+            token_program.key,
+            user_shares.key,
+            mint.key,
+            authority.key,
+            &[],
+            amount,
+        )?;
+        invoke(
+            &ix,
+            &[
+                user_shares.clone(),
+                mint.clone(),
+                authority.clone(),
+                token_program.clone(),
+            ],
+        )
+    }
+
+    fn balance_of(info: &AccountInfo) -> Result<u64, ProgramError> {
+        let data = info.try_borrow_data()?;
+        Ok(StateWithExtensions::<Token2022Account>::unpack(&data)?
+            .base
+            .amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{instruction::Instruction, program_option::COption};
+    use spl_token::state::{Account, AccountState, Mint};
+
+    use super::*;
+    use crate::test_support::{RecordingStubs, TestAccount};
+
+    fn pack<T: Pack>(state: T) -> Vec<u8> {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        data
+    }
+
+    /// Token account of `program`; the base layouts of SPL Token and
+    /// Token-2022 are the same
+    fn token_account(program: Pubkey, amount: u64) -> TestAccount {
+        let account = Account {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount,
+            state: AccountState::Initialized,
+            ..Default::default()
+        };
+        TestAccount::new(Pubkey::new_unique(), program)
+            .data(pack(account))
+            .writable()
+    }
+
+    /// Run every operation of `SplTokenBackend` on accounts of `program` and
+    /// return the instructions it invokes
+    fn invoke_all(program: Pubkey) -> Vec<Instruction> {
+        RecordingStubs::install();
+
+        let mint = Mint {
+            mint_authority: COption::Some(Pubkey::new_unique()),
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut mint = TestAccount::new(Pubkey::new_unique(), program)
+            .data(pack(mint))
+            .writable();
+        let mut vault_assets = token_account(program, 0);
+        let mut user = token_account(program, 10);
+        let mut authority = TestAccount::new(Pubkey::new_unique(), Pubkey::default()).signer();
+        let mut token_program = TestAccount::new(program, Pubkey::default()).executable();
+        let (mint, vault_assets, user, authority, token_program) = (
+            mint.info(),
+            vault_assets.info(),
+            user.info(),
+            authority.info(),
+            token_program.info(),
+        );
+        let seeds: &[&[u8]] = &[b"seed"];
+
+        SplTokenBackend::transfer_in(1, &vault_assets, &user, &mint, &authority, &token_program)
+            .unwrap();
+        SplTokenBackend::transfer_out(2, &vault_assets, &user, &mint, &token_program, seeds)
+            .unwrap();
+        SplTokenBackend::mint(3, &user, &mint, &token_program, seeds).unwrap();
+        SplTokenBackend::burn(4, &user, &mint, &authority, &token_program).unwrap();
+        RecordingStubs::take_invoked()
+    }
+
+    #[test]
+    fn test_spl_token_cpi() {
+        use spl_token::instruction::TokenInstruction;
+
+        let ixs = invoke_all(spl_token::id());
+        assert!(ixs.iter().all(|ix| ix.program_id == spl_token::id()));
+        let ixs: Vec<_> = ixs
+            .iter()
+            .map(|ix| TokenInstruction::unpack(&ix.data).unwrap())
+            .collect();
+        assert_eq!(
+            ixs,
+            [
+                TokenInstruction::Transfer { amount: 1 },
+                TokenInstruction::Transfer { amount: 2 },
+                TokenInstruction::MintTo { amount: 3 },
+                TokenInstruction::Burn { amount: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_token_2022_cpi() {
+        use spl_token_2022::instruction::TokenInstruction;
+
+        let ixs = invoke_all(spl_token_2022::id());
+        assert!(ixs.iter().all(|ix| ix.program_id == spl_token_2022::id()));
+        let ixs: Vec<_> = ixs
+            .iter()
+            .map(|ix| TokenInstruction::unpack(&ix.data).unwrap())
+            .collect();
+        assert_eq!(
+            ixs,
+            [
+                TokenInstruction::TransferChecked {
+                    amount: 1,
+                    decimals: 6
+                },
+                TokenInstruction::TransferChecked {
+                    amount: 2,
+                    decimals: 6
+                },
+                TokenInstruction::MintTo { amount: 3 },
+                TokenInstruction::Burn { amount: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_balance_of() {
+        for program in [spl_token::id(), spl_token_2022::id()] {
+            let mut account = token_account(program, 7);
+            assert_eq!(SplTokenBackend::balance_of(&account.info()), Ok(7));
+        }
+    }
+}
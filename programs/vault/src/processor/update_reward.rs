@@ -1,10 +1,14 @@
-use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
 
-use crate::{loaders::UpdateRewardContext, operations::vault_update_reward};
+use crate::{
+    events::{VaultEvent, VaultEventKind, VaultSnapshot},
+    loaders::UpdateRewardContext,
+    operations::VaultOperation,
+};
 
-use super::spl_token_account_amount;
+use super::{set_effect_return_data, TokenBackend};
 
-pub fn process_update_reward(accounts: &[AccountInfo]) -> ProgramResult {
+pub fn process_update_reward<T: TokenBackend>(accounts: &[AccountInfo]) -> ProgramResult {
     let context = UpdateRewardContext::load(accounts)?;
 
     // This instruction is permissionless. Anyone can run it to update vault state.
@@ -15,12 +19,27 @@ pub fn process_update_reward(accounts: &[AccountInfo]) -> ProgramResult {
         vault_assets_account,
     } = context;
 
-    let vault_asset_account_amount = spl_token_account_amount(&vault_assets_account)?;
+    let vault_asset_account_amount = T::balance_of(vault_assets_account)?;
 
-    let _effect = {
+    let (effect, pre, post) = {
         let mut vault = vault_info.get_mut()?;
-        vault_update_reward(&mut vault, vault_asset_account_amount)?
+        let pre = VaultSnapshot::from(&*vault);
+        let effect = VaultOperation::UpdateReward {
+            balance: vault_asset_account_amount,
+        }
+        .apply(&mut vault)?;
+        (effect, pre, VaultSnapshot::from(&*vault))
     };
 
+    VaultEvent {
+        vault: *vault_info.as_ref().key,
+        actor: Pubkey::default(),
+        effect,
+        pre,
+        post,
+    }
+    .emit(VaultEventKind::UpdateReward);
+    set_effect_return_data(&effect);
+
     Ok(())
 }
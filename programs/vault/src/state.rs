@@ -1,17 +1,31 @@
 use bytemuck::{Pod, Zeroable};
 use solana_program::pubkey::{Pubkey, PubkeyError};
 
-use crate::utils::math::{mul_div_ceil, FeeBps};
 use crate::utils::{
-    guards::{require_gt, require_ne},
-    math::mul_div_floor,
+    guards::{require, require_eq, require_gt, require_ne},
+    math::{mul_div, FeeBps, Rounding},
 };
 use crate::{VaultError, VaultResult};
 use spl_pod::primitives::PodU64;
 
+pub mod builder;
+pub mod legacy;
+
+pub use builder::VaultBuilder;
+
+/// Version of the `Vault` layout written by this program
+pub const VAULT_VERSION: u8 = 1;
+/// Bytes at the end of `Vault` kept zeroed for future fields
+pub const VAULT_RESERVED_BYTES: usize = 128;
+
+/// Vault state
+///
+/// The first byte is the layout version. New fields are carved out of
+/// `reserved` so that the account size does not change between versions.
 #[repr(C)]
-#[derive(Default, Pod, Copy, Clone, Zeroable)]
+#[derive(Pod, Copy, Clone, Zeroable)]
 pub struct Vault {
+    pub version: u8,
     pub admin: Pubkey,
     pub slash_admin: Pubkey,
     pub shares_mint: Pubkey,
@@ -27,6 +41,17 @@ pub struct Vault {
 
     pub vault_assets_account: Pubkey,
     pub vault_assets_account_bump: u8,
+
+    pub reserved: [u8; VAULT_RESERVED_BYTES],
+}
+
+impl Default for Vault {
+    fn default() -> Self {
+        Self {
+            version: VAULT_VERSION,
+            ..Zeroable::zeroed()
+        }
+    }
 }
 
 impl Vault {
@@ -64,41 +89,25 @@ impl Vault {
     }
 
     pub fn check_invariant(&self) -> VaultResult<()> {
-        self.is_solvent().then_some(()).ok_or(VaultError::GuardFail)
+        self.is_solvent().then_some(()).ok_or(VaultError::Insolvent)
     }
 
-    pub fn convert_shares_to_assets(&self, shares: u64) -> VaultResult<u64> {
-        let assets = if self.num_shares() == self.num_assets() {
-            shares
-        } else {
-            mul_div_floor(shares, self.num_assets(), self.num_shares())?
-        };
-        Ok(assets)
-    }
-    pub fn convert_shares_to_assets_ceil(&self, shares: u64) -> VaultResult<u64> {
+    /// Assets backing `shares`, rounded by `rounding`
+    pub fn convert_shares_to_assets(&self, shares: u64, rounding: Rounding) -> VaultResult<u64> {
         let assets = if self.num_shares() == self.num_assets() {
             shares
         } else {
-            mul_div_ceil(shares, self.num_assets(), self.num_shares())?
+            mul_div(shares, self.num_assets(), self.num_shares(), rounding)?
         };
         Ok(assets)
     }
 
-    pub fn convert_assets_to_shares(&self, token: u64) -> VaultResult<u64> {
+    /// Shares backed by `token` assets, rounded by `rounding`
+    pub fn convert_assets_to_shares(&self, token: u64, rounding: Rounding) -> VaultResult<u64> {
         let shares = if self.num_shares() == self.num_assets() {
             token
         } else {
-            mul_div_floor(token, self.num_shares(), self.num_assets())?
-        };
-
-        Ok(shares)
-    }
-
-    pub fn convert_assets_to_shares_ceil(&self, token: u64) -> VaultResult<u64> {
-        let shares = if self.num_shares() == self.num_assets() {
-            token
-        } else {
-            mul_div_ceil(token, self.num_shares(), self.num_assets())?
+            mul_div(token, self.num_shares(), self.num_assets(), rounding)?
         };
 
         Ok(shares)
@@ -114,7 +123,7 @@ impl Vault {
     }
 
     pub fn mint_shares(&mut self, amt: u64) -> VaultResult<()> {
-        require_gt!(amt, 0, VaultError::GuardFail);
+        require_gt!(amt, 0, VaultError::ZeroAmount);
         self.shares = self
             .num_shares()
             .checked_add(amt)
@@ -124,7 +133,7 @@ impl Vault {
     }
 
     pub fn add_token(&mut self, amt: u64) -> VaultResult<()> {
-        require_gt!(amt, 0, VaultError::GuardFail);
+        require_gt!(amt, 0, VaultError::ZeroAmount);
         self.assets = self
             .num_assets()
             .checked_add(amt)
@@ -141,21 +150,43 @@ impl Vault {
             .into();
 
         // ensure that vault is still solvent after slashing
-        self.check_invariant()?;
+        require!(self.is_solvent(), VaultError::InsolventAfterSlash);
         Ok(())
     }
 
     pub fn validate(&self) -> VaultResult<()> {
-        require_ne!(self.assets_mint, self.shares_mint, VaultError::GuardFail);
+        require_eq!(self.version, VAULT_VERSION, VaultError::InvalidVaultVersion);
+        require_ne!(self.assets_mint, self.shares_mint, VaultError::SameMint);
         Ok(())
     }
 }
 
+/// Anchor flavour: a vault account is the discriminator followed by `Vault`
+#[cfg(feature = "anchor")]
+mod anchor_account {
+    use anchor_lang::{Discriminator, Owner, ZeroCopy};
+
+    use super::*;
+
+    impl Discriminator for Vault {
+        // sha256("account:Vault")[..8]
+        const DISCRIMINATOR: [u8; 8] = [211, 8, 232, 43, 2, 152, 117, 119];
+    }
+
+    impl Owner for Vault {
+        fn owner() -> Pubkey {
+            crate::ID
+        }
+    }
+
+    impl ZeroCopy for Vault {}
+}
+
 /// Seeds for the PDA vault token account
 #[macro_export]
 macro_rules! vault_assets_account_seeds {
     ($vault_pk: expr) => {
-        &[$crate::constants::VAULT_ASSETS, vault_pk.as_ref()]
+        &[$crate::constants::VAULT_ASSETS, $vault_pk.as_ref()]
     };
 }
 
@@ -185,7 +216,7 @@ pub fn create_vault_assets_account_address(
 #[macro_export]
 macro_rules! vault_mint_seeds {
     ($vault_pk: expr) => {
-        &[$crate::constants::SHARES_MINT, vault_pk.as_ref()]
+        &[$crate::constants::SHARES_MINT, $vault_pk.as_ref()]
     };
 }
 
@@ -206,3 +237,100 @@ pub fn create_vault_shares_mint_address(
         &crate::ID,
     )
 }
+
+/// PDAs derived from the vault key and a bump stored in the vault
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VaultPda {
+    AssetsAccount,
+    SharesMint,
+}
+
+impl VaultPda {
+    pub fn seed_prefix(self) -> &'static [u8] {
+        match self {
+            VaultPda::AssetsAccount => crate::constants::VAULT_ASSETS,
+            VaultPda::SharesMint => crate::constants::SHARES_MINT,
+        }
+    }
+
+    /// Name of the `Vault` field that stores the bump
+    pub fn bump_field(self) -> &'static str {
+        match self {
+            VaultPda::AssetsAccount => "vault_assets_account_bump",
+            VaultPda::SharesMint => "vault_shares_mint_bump",
+        }
+    }
+
+    pub fn create_address(self, vault_pk: &Pubkey, vault: &Vault) -> Result<Pubkey, PubkeyError> {
+        match self {
+            VaultPda::AssetsAccount => create_vault_assets_account_address(vault_pk, vault),
+            VaultPda::SharesMint => create_vault_shares_mint_address(vault_pk, vault),
+        }
+    }
+
+    pub fn find_address(self, vault_pk: &Pubkey) -> (Pubkey, u8) {
+        match self {
+            VaultPda::AssetsAccount => {
+                Pubkey::find_program_address(vault_assets_account_seeds!(vault_pk), &crate::ID)
+            }
+            VaultPda::SharesMint => {
+                Pubkey::find_program_address(vault_mint_seeds!(vault_pk), &crate::ID)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn test_vault_v1_layout() {
+        let vault = Vault {
+            version: VAULT_VERSION,
+            admin: Pubkey::new_from_array([1; 32]),
+            slash_admin: Pubkey::new_from_array([2; 32]),
+            shares_mint: Pubkey::new_from_array([3; 32]),
+            vault_shares_mint_bump: 4,
+            assets_mint: Pubkey::new_from_array([5; 32]),
+            shares: 6u64.into(),
+            assets: 7u64.into(),
+            fee_bps: 8u64.into(),
+            fee_amount: 9u64.into(),
+            fee_token_account: Pubkey::new_from_array([10; 32]),
+            vault_assets_account: Pubkey::new_from_array([11; 32]),
+            vault_assets_account_bump: 12,
+            reserved: [13; VAULT_RESERVED_BYTES],
+        };
+        let golden = [
+            &[1u8][..],
+            &[1; 32],
+            &[2; 32],
+            &[3; 32],
+            &[4],
+            &[5; 32],
+            &6u64.to_le_bytes(),
+            &7u64.to_le_bytes(),
+            &8u64.to_le_bytes(),
+            &9u64.to_le_bytes(),
+            &[10; 32],
+            &[11; 32],
+            &[12],
+            &[13; 128],
+        ]
+        .concat();
+
+        assert_eq!(size_of::<Vault>(), 355);
+        assert_eq!(bytemuck::bytes_of(&vault), &golden[..]);
+    }
+
+    #[cfg(feature = "anchor")]
+    #[test]
+    fn test_anchor_discriminator() {
+        use anchor_lang::Discriminator;
+        use solana_program::hash::hash;
+
+        assert_eq!(Vault::DISCRIMINATOR, hash(b"account:Vault").to_bytes()[..8]);
+    }
+}
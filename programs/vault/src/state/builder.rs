@@ -0,0 +1,178 @@
+use solana_program::pubkey::Pubkey;
+
+use super::{Vault, VaultPda};
+use crate::utils::math::MAX_FEE_BPS;
+
+/// Keys of a vault built with the default `VaultBuilder`
+pub mod default_keys {
+    use solana_program::pubkey::Pubkey;
+
+    pub const VAULT: Pubkey = Pubkey::new_from_array([1; 32]);
+    pub const ADMIN: Pubkey = Pubkey::new_from_array([2; 32]);
+    pub const SLASH_ADMIN: Pubkey = Pubkey::new_from_array([3; 32]);
+    pub const ASSETS_MINT: Pubkey = Pubkey::new_from_array([4; 32]);
+    pub const FEE_TOKEN_ACCOUNT: Pubkey = Pubkey::new_from_array([5; 32]);
+}
+
+/// Builder of `Vault` values for tests and specs
+///
+/// Starts from the current layout version with distinct fixed keys for the
+/// admins, the assets mint and the fee account, no shares, no assets and no
+/// fee. The shares mint and the vault assets account are left zeroed until
+/// `pdas` derives them, as finding a PDA is too costly to do by default.
+///
+/// Fixtures are builders too, so that a test can adjust them further.
+#[derive(Copy, Clone)]
+pub struct VaultBuilder {
+    vault: Vault,
+}
+
+impl Default for VaultBuilder {
+    fn default() -> Self {
+        Self {
+            vault: Vault {
+                admin: default_keys::ADMIN,
+                slash_admin: default_keys::SLASH_ADMIN,
+                assets_mint: default_keys::ASSETS_MINT,
+                fee_token_account: default_keys::FEE_TOKEN_ACCOUNT,
+                ..Vault::default()
+            },
+        }
+    }
+}
+
+impl From<Vault> for VaultBuilder {
+    fn from(vault: Vault) -> Self {
+        Self { vault }
+    }
+}
+
+impl VaultBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// No shares and no assets
+    pub fn empty() -> Self {
+        Self::new()
+    }
+
+    /// `amount` shares backed by `amount` assets
+    pub fn one_to_one(amount: u64) -> Self {
+        Self::new().shares(amount).assets(amount)
+    }
+
+    /// A share is worth 1.5 assets, as after a reward
+    pub fn appreciated() -> Self {
+        Self::new().shares(1_000).assets(1_500)
+    }
+
+    /// A slash left the vault barely solvent: a share is worth just over one
+    /// asset, and any slash of more than one atom fails
+    pub fn post_slash() -> Self {
+        Self::new().shares(1_000).assets(1_001)
+    }
+
+    /// Every amount at its largest value the vault accepts; the fee amount
+    /// stays zero so that the gross assets do not overflow
+    pub fn max() -> Self {
+        Self::new()
+            .shares(u64::MAX)
+            .assets(u64::MAX)
+            .fee_bps(MAX_FEE_BPS)
+    }
+
+    pub fn admin(mut self, admin: Pubkey) -> Self {
+        self.vault.admin = admin;
+        self
+    }
+
+    pub fn slash_admin(mut self, slash_admin: Pubkey) -> Self {
+        self.vault.slash_admin = slash_admin;
+        self
+    }
+
+    pub fn assets_mint(mut self, assets_mint: Pubkey) -> Self {
+        self.vault.assets_mint = assets_mint;
+        self
+    }
+
+    pub fn fee_token_account(mut self, fee_token_account: Pubkey) -> Self {
+        self.vault.fee_token_account = fee_token_account;
+        self
+    }
+
+    /// Shares mint and its bump, taken as is
+    pub fn shares_mint(mut self, shares_mint: Pubkey, bump: u8) -> Self {
+        self.vault.shares_mint = shares_mint;
+        self.vault.vault_shares_mint_bump = bump;
+        self
+    }
+
+    /// Vault assets account and its bump, taken as is
+    pub fn vault_assets_account(mut self, vault_assets_account: Pubkey, bump: u8) -> Self {
+        self.vault.vault_assets_account = vault_assets_account;
+        self.vault.vault_assets_account_bump = bump;
+        self
+    }
+
+    /// Shares mint and vault assets account derived from `vault_pk`, with
+    /// their canonical bumps
+    pub fn pdas(self, vault_pk: &Pubkey) -> Self {
+        let (shares_mint, shares_mint_bump) = VaultPda::SharesMint.find_address(vault_pk);
+        let (assets_account, assets_account_bump) = VaultPda::AssetsAccount.find_address(vault_pk);
+        self.shares_mint(shares_mint, shares_mint_bump)
+            .vault_assets_account(assets_account, assets_account_bump)
+    }
+
+    pub fn shares(mut self, shares: u64) -> Self {
+        self.vault.shares = shares.into();
+        self
+    }
+
+    pub fn assets(mut self, assets: u64) -> Self {
+        self.vault.assets = assets.into();
+        self
+    }
+
+    pub fn fee_bps(mut self, fee_bps: u64) -> Self {
+        self.vault.fee_bps = fee_bps.into();
+        self
+    }
+
+    pub fn fee_amount(mut self, fee_amount: u64) -> Self {
+        self.vault.fee_amount = fee_amount.into();
+        self
+    }
+
+    pub fn build(self) -> Vault {
+        self.vault
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_are_valid() {
+        for builder in [
+            VaultBuilder::empty(),
+            VaultBuilder::one_to_one(100),
+            VaultBuilder::appreciated(),
+            VaultBuilder::post_slash(),
+            VaultBuilder::max(),
+        ] {
+            let vault = builder.pdas(&default_keys::VAULT).build();
+            assert!(vault.validate().is_ok());
+            assert!(vault.check_invariant().is_ok());
+            assert!(vault.fee_in_bps().is_ok());
+            for pda in [VaultPda::AssetsAccount, VaultPda::SharesMint] {
+                assert_eq!(
+                    pda.create_address(&default_keys::VAULT, &vault),
+                    Ok(pda.find_address(&default_keys::VAULT).0)
+                );
+            }
+        }
+    }
+}
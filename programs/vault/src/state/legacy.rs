@@ -0,0 +1,127 @@
+use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
+use spl_pod::primitives::PodU64;
+
+use super::{Vault, VAULT_VERSION};
+use crate::{VaultError, VaultResult};
+
+/// Layout of `Vault` before versioning was introduced
+///
+/// Accounts with this layout have no version byte and are recognized by
+/// their size.
+#[repr(C)]
+#[derive(Default, Pod, Copy, Clone, Zeroable)]
+pub struct VaultV0 {
+    pub admin: Pubkey,
+    pub slash_admin: Pubkey,
+    pub shares_mint: Pubkey,
+    pub vault_shares_mint_bump: u8,
+    pub assets_mint: Pubkey,
+
+    pub shares: PodU64,
+    pub assets: PodU64,
+
+    pub fee_bps: PodU64,
+    pub fee_amount: PodU64,
+    pub fee_token_account: Pubkey,
+
+    pub vault_assets_account: Pubkey,
+    pub vault_assets_account_bump: u8,
+}
+
+impl From<&VaultV0> for Vault {
+    fn from(v0: &VaultV0) -> Self {
+        Self {
+            version: VAULT_VERSION,
+            admin: v0.admin,
+            slash_admin: v0.slash_admin,
+            shares_mint: v0.shares_mint,
+            vault_shares_mint_bump: v0.vault_shares_mint_bump,
+            assets_mint: v0.assets_mint,
+            shares: v0.shares,
+            assets: v0.assets,
+            fee_bps: v0.fee_bps,
+            fee_amount: v0.fee_amount,
+            fee_token_account: v0.fee_token_account,
+            vault_assets_account: v0.vault_assets_account,
+            vault_assets_account_bump: v0.vault_assets_account_bump,
+            ..Zeroable::zeroed()
+        }
+    }
+}
+
+/// Legacy vault stored in `data`, if `data` has the layout of `VaultV0`
+pub fn legacy_vault(data: &[u8]) -> VaultResult<&VaultV0> {
+    bytemuck::try_from_bytes::<VaultV0>(data).map_err(|_| VaultError::InvalidVaultData)
+}
+
+/// Upgrade vault account data of an older layout to the current `Vault`
+pub fn migrate_vault(data: &[u8]) -> VaultResult<Vault> {
+    Ok(legacy_vault(data)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    fn golden_v0() -> VaultV0 {
+        VaultV0 {
+            admin: Pubkey::new_from_array([1; 32]),
+            slash_admin: Pubkey::new_from_array([2; 32]),
+            shares_mint: Pubkey::new_from_array([3; 32]),
+            vault_shares_mint_bump: 4,
+            assets_mint: Pubkey::new_from_array([5; 32]),
+            shares: 6u64.into(),
+            assets: 7u64.into(),
+            fee_bps: 8u64.into(),
+            fee_amount: 9u64.into(),
+            fee_token_account: Pubkey::new_from_array([10; 32]),
+            vault_assets_account: Pubkey::new_from_array([11; 32]),
+            vault_assets_account_bump: 12,
+        }
+    }
+
+    #[test]
+    fn test_vault_v0_layout() {
+        let golden = [
+            &[1u8; 32][..],
+            &[2; 32],
+            &[3; 32],
+            &[4],
+            &[5; 32],
+            &6u64.to_le_bytes(),
+            &7u64.to_le_bytes(),
+            &8u64.to_le_bytes(),
+            &9u64.to_le_bytes(),
+            &[10; 32],
+            &[11; 32],
+            &[12],
+        ]
+        .concat();
+
+        assert_eq!(size_of::<VaultV0>(), 226);
+        assert_eq!(bytemuck::bytes_of(&golden_v0()), &golden[..]);
+    }
+
+    #[test]
+    fn test_migrate_vault_v0() {
+        let v0 = golden_v0();
+        let vault = migrate_vault(bytemuck::bytes_of(&v0)).unwrap();
+
+        assert_eq!(vault.version, VAULT_VERSION);
+        assert_eq!(vault.admin, v0.admin);
+        assert_eq!(vault.num_shares(), 6);
+        assert_eq!(vault.num_assets(), 7);
+        assert_eq!(vault.fee_amount(), 9);
+        assert_eq!(vault.vault_assets_account, v0.vault_assets_account);
+        assert_eq!(vault.vault_assets_account_bump, 12);
+        assert!(vault.reserved.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_migrate_vault_current() {
+        let vault = Vault::default();
+        assert!(migrate_vault(bytemuck::bytes_of(&vault)).is_err());
+    }
+}
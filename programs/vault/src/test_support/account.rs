@@ -0,0 +1,69 @@
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+/// Owned storage of an account from which `AccountInfo`s are built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub executable: bool,
+}
+
+impl TestAccount {
+    pub fn new(key: Pubkey, owner: Pubkey) -> Self {
+        Self {
+            key,
+            owner,
+            lamports: 0,
+            data: vec![],
+            is_signer: false,
+            is_writable: false,
+            executable: false,
+        }
+    }
+
+    pub fn data(self, data: Vec<u8>) -> Self {
+        Self { data, ..self }
+    }
+
+    pub fn lamports(self, lamports: u64) -> Self {
+        Self { lamports, ..self }
+    }
+
+    pub fn signer(self) -> Self {
+        Self {
+            is_signer: true,
+            ..self
+        }
+    }
+
+    pub fn writable(self) -> Self {
+        Self {
+            is_writable: true,
+            ..self
+        }
+    }
+
+    pub fn executable(self) -> Self {
+        Self {
+            executable: true,
+            ..self
+        }
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            self.is_writable,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            self.executable,
+            0,
+        )
+    }
+}
@@ -0,0 +1,87 @@
+use solana_program::{pubkey::Pubkey, system_program};
+
+use super::{TestAccount, TokenBank};
+use crate::{
+    client::{self, UserAccounts},
+    state::{Vault, VaultBuilder},
+};
+
+/// A vault and the accounts around it, held by a `TokenBank`
+///
+/// The bank holds the vault account, the system accounts of the admins and
+/// of the user, both mints, and empty token accounts: the vault assets
+/// account owned by itself, the fee account of the admin, the slashed account
+/// of the slash admin and the assets and shares accounts of the user. Tests
+/// fund them with `TokenBank::mint_to`.
+#[derive(Clone)]
+pub struct VaultFixture {
+    pub bank: TokenBank,
+    pub vault_pk: Pubkey,
+    pub vault: Vault,
+    pub user: UserAccounts,
+    pub slashed_account: Pubkey,
+}
+
+impl VaultFixture {
+    /// Accounts of `vault`, whose PDAs must derive from `vault_pk`
+    ///
+    /// The user assets account may alias an account of the vault, in which
+    /// case the user owns it.
+    pub fn new(
+        vault_pk: Pubkey,
+        vault: Vault,
+        user: UserAccounts,
+        slashed_account: Pubkey,
+    ) -> Self {
+        let mut bank = TokenBank::new();
+        bank.add(TestAccount::new(vault_pk, crate::id()).data(bytemuck::bytes_of(&vault).to_vec()));
+        for key in [vault.admin, vault.slash_admin, user.authority] {
+            bank.add(TestAccount::new(key, system_program::id()));
+        }
+        bank.create_mint(vault.assets_mint, vault.admin);
+        bank.create_mint(vault.shares_mint, vault.shares_mint);
+        bank.create_token_account(
+            vault.vault_assets_account,
+            vault.assets_mint,
+            vault.vault_assets_account,
+        );
+        bank.create_token_account(vault.fee_token_account, vault.assets_mint, vault.admin);
+        bank.create_token_account(slashed_account, vault.assets_mint, vault.slash_admin);
+        bank.create_token_account(user.assets_account, vault.assets_mint, user.authority);
+        bank.create_token_account(user.shares_account, vault.shares_mint, user.authority);
+
+        Self {
+            bank,
+            vault_pk,
+            vault,
+            user,
+            slashed_account,
+        }
+    }
+
+    /// Accounts of a vault built from `builder`, under fresh keys
+    ///
+    /// Replaces the keys of `builder`, so that accounts of different
+    /// fixtures never collide.
+    pub fn unique(builder: VaultBuilder) -> Self {
+        let vault_pk = Pubkey::new_unique();
+        let vault = builder
+            .admin(Pubkey::new_unique())
+            .slash_admin(Pubkey::new_unique())
+            .assets_mint(Pubkey::new_unique())
+            .fee_token_account(Pubkey::new_unique())
+            .pdas(&vault_pk)
+            .build();
+        let user = UserAccounts {
+            authority: Pubkey::new_unique(),
+            assets_account: Pubkey::new_unique(),
+            shares_account: Pubkey::new_unique(),
+        };
+        Self::new(vault_pk, vault, user, Pubkey::new_unique())
+    }
+
+    /// The vault as stored in the bank
+    pub fn stored_vault(&self) -> Vault {
+        client::decode_vault(&self.bank.account(&self.vault_pk).data).unwrap()
+    }
+}
@@ -0,0 +1,17 @@
+//! Support for native end-to-end tests of the processors
+//!
+//! Enabled for unit tests and by the `test-support` feature. `TokenBank` is
+//! the in-memory `TokenBackend` of the processors: it keeps mints and token
+//! accounts as packed SPL state in `TestAccount`s, so instructions can be
+//! run with `TokenBank::process` and checked against real token balances.
+//! `VaultFixture` sets up a vault and the accounts around it in a bank.
+//! `RecordingStubs` stand in for the runtime in tests that make CPIs.
+pub mod account;
+pub mod fixture;
+pub mod stubs;
+pub mod token_bank;
+
+pub use account::*;
+pub use fixture::*;
+pub use stubs::*;
+pub use token_bank::*;
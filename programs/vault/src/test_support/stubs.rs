@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{ProgramResult, SUCCESS},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    program_utils::limited_deserialize,
+    rent::Rent,
+    system_instruction::SystemInstruction,
+    system_program,
+};
+
+thread_local! {
+    // -- the stubs are global, so each test thread records its own calls
+    static INVOKED: RefCell<Vec<Instruction>> = const { RefCell::new(Vec::new()) };
+    static LOGGED_DATA: RefCell<Vec<Vec<Vec<u8>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Syscall stubs for instructions that call into the runtime
+///
+/// Records the instructions invoked through CPI and the fields logged with
+/// `sol_log_data`, returns the default `Rent`, and runs system transfers on
+/// the accounts passed to the CPI. Other CPIs are only recorded.
+pub struct RecordingStubs;
+
+impl RecordingStubs {
+    /// Install the stubs and clear the records of the calling thread
+    pub fn install() {
+        set_syscall_stubs(Box::new(RecordingStubs));
+        INVOKED.with(|invoked| invoked.borrow_mut().clear());
+        LOGGED_DATA.with(|logged| logged.borrow_mut().clear());
+    }
+
+    /// Instructions invoked by the calling thread since `install`
+    pub fn take_invoked() -> Vec<Instruction> {
+        INVOKED.with(|invoked| invoked.take())
+    }
+
+    /// Fields logged by the calling thread since `install`
+    pub fn take_logged_data() -> Vec<Vec<Vec<u8>>> {
+        LOGGED_DATA.with(|logged| logged.take())
+    }
+}
+
+fn system_transfer(instruction: &Instruction, account_infos: &[AccountInfo]) -> ProgramResult {
+    let SystemInstruction::Transfer { lamports } = limited_deserialize(&instruction.data, 1024)
+        .map_err(|_| ProgramError::InvalidInstructionData)?
+    else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let info = |index: usize| {
+        let key = instruction.accounts[index].pubkey;
+        account_infos
+            .iter()
+            .find(|info| *info.key == key)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+    let (from, to) = (info(0)?, info(1)?);
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let balance = from.lamports();
+    **from.try_borrow_mut_lamports()? = balance
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+impl SyscallStubs for RecordingStubs {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        INVOKED.with(|invoked| invoked.borrow_mut().push(instruction.clone()));
+        if instruction.program_id == system_program::id() {
+            system_transfer(instruction, account_infos)?;
+        }
+        Ok(())
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields = fields.iter().map(|field| field.to_vec()).collect();
+        LOGGED_DATA.with(|logged| logged.borrow_mut().push(fields));
+    }
+}
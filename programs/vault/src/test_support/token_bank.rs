@@ -0,0 +1,561 @@
+use std::collections::BTreeMap;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+use spl_token::{
+    error::TokenError,
+    state::{Account as TokenAccount, AccountState, Mint},
+};
+
+use super::TestAccount;
+use crate::{
+    processor::{process_instruction_with, TokenBackend},
+    utils::guards::{require, require_eq},
+};
+
+/// In-memory ledger of SPL mints and token accounts, plus any other account
+/// an instruction needs
+#[derive(Debug, Clone)]
+pub struct TokenBank {
+    pub accounts: BTreeMap<Pubkey, TestAccount>,
+}
+
+impl Default for TokenBank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenBank {
+    /// Bank holding the SPL token program account
+    pub fn new() -> Self {
+        let mut bank = Self {
+            accounts: BTreeMap::new(),
+        };
+        bank.add(TestAccount::new(spl_token::id(), Pubkey::default()).executable());
+        bank
+    }
+
+    pub fn add(&mut self, account: TestAccount) {
+        self.accounts.insert(account.key, account);
+    }
+
+    pub fn account(&self, key: &Pubkey) -> &TestAccount {
+        self.accounts
+            .get(key)
+            .unwrap_or_else(|| panic!("unknown account {}", key))
+    }
+
+    pub fn account_mut(&mut self, key: &Pubkey) -> &mut TestAccount {
+        self.accounts
+            .get_mut(key)
+            .unwrap_or_else(|| panic!("unknown account {}", key))
+    }
+
+    pub fn create_mint(&mut self, key: Pubkey, authority: Pubkey) {
+        let mint = Mint {
+            mint_authority: COption::Some(authority),
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        self.add(TestAccount::new(key, spl_token::id()).data(pack(&mint)));
+    }
+
+    pub fn create_token_account(&mut self, key: Pubkey, mint: Pubkey, owner: Pubkey) {
+        let account = TokenAccount {
+            mint,
+            owner,
+            state: AccountState::Initialized,
+            ..Default::default()
+        };
+        self.add(TestAccount::new(key, spl_token::id()).data(pack(&account)));
+    }
+
+    pub fn token_account(&self, key: &Pubkey) -> TokenAccount {
+        TokenAccount::unpack(&self.account(key).data).unwrap()
+    }
+
+    pub fn mint(&self, key: &Pubkey) -> Mint {
+        Mint::unpack(&self.account(key).data).unwrap()
+    }
+
+    pub fn balance(&self, key: &Pubkey) -> u64 {
+        self.token_account(key).amount
+    }
+
+    pub fn supply(&self, mint: &Pubkey) -> u64 {
+        self.mint(mint).supply
+    }
+
+    /// Mint tokens out of band, e.g. a reward paid into the vault
+    pub fn mint_to(&mut self, key: &Pubkey, amount: u64) {
+        let mut account = self.token_account(key);
+        let mut mint = self.mint(&account.mint);
+        account.amount += amount;
+        mint.supply += amount;
+        self.account_mut(key).data = pack(&account);
+        self.account_mut(&account.mint).data = pack(&mint);
+    }
+
+    /// `AccountInfo`s in the order of `metas`, flagged as in the metas
+    pub fn infos(&mut self, metas: &[AccountMeta]) -> Vec<AccountInfo<'_>> {
+        let mut infos: BTreeMap<Pubkey, AccountInfo> = self
+            .accounts
+            .iter_mut()
+            .filter(|(key, _)| metas.iter().any(|meta| meta.pubkey == **key))
+            .map(|(key, account)| (*key, account.info()))
+            .collect();
+        metas
+            .iter()
+            .map(|meta| {
+                let mut info = infos
+                    .get_mut(&meta.pubkey)
+                    .unwrap_or_else(|| panic!("unknown account {}", meta.pubkey))
+                    .clone();
+                info.is_signer = meta.is_signer;
+                info.is_writable = meta.is_writable;
+                info
+            })
+            .collect()
+    }
+
+    /// Run `ix` through the vault program with the bank as token backend
+    pub fn process(&mut self, ix: &Instruction) -> ProgramResult {
+        let infos = self.infos(&ix.accounts);
+        process_instruction_with::<TokenBank>(&ix.program_id, &infos, &ix.data)
+    }
+}
+
+fn pack<T: Pack>(state: &T) -> Vec<u8> {
+    let mut data = vec![0; T::LEN];
+    T::pack_into_slice(state, &mut data);
+    data
+}
+
+/// Token operations executed against the bank
+///
+/// Follows the SPL token program on the accounts the bank hands out. PDAs of
+/// the vault sign with their seeds: the vault assets account owns itself and
+/// the shares mint is its own mint authority.
+impl TokenBackend for TokenBank {
+    fn transfer_in<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        check_program(token_program)?;
+        transfer(amount, user_assets, vault_assets, mint, authority)
+    }
+
+    fn transfer_out<'info>(
+        amount: u64,
+        vault_assets: &AccountInfo<'info>,
+        user_assets: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        check_program(token_program)?;
+        let authority = signed_by_program(vault_assets, signer_seeds)?;
+        transfer(amount, vault_assets, user_assets, mint, &authority)
+    }
+
+    fn mint<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        check_program(token_program)?;
+        let authority = signed_by_program(mint, signer_seeds)?;
+        let mut shares_mint = load::<Mint>(mint)?;
+        let mut account = load::<TokenAccount>(user_shares)?;
+        require_eq!(
+            shares_mint.mint_authority,
+            COption::Some(*authority.key),
+            TokenError::OwnerMismatch.into()
+        );
+        require_eq!(account.mint, *mint.key, TokenError::MintMismatch.into());
+
+        shares_mint.supply = shares_mint
+            .supply
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        account.amount += amount;
+        store(mint, shares_mint)?;
+        store(user_shares, account)
+    }
+
+    fn burn<'info>(
+        amount: u64,
+        user_shares: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        check_program(token_program)?;
+        let mut shares_mint = load::<Mint>(mint)?;
+        let mut account = load::<TokenAccount>(user_shares)?;
+        check_authority(&account, authority)?;
+        require_eq!(account.mint, *mint.key, TokenError::MintMismatch.into());
+        require!(
+            account.amount >= amount,
+            TokenError::InsufficientFunds.into()
+        );
+
+        account.amount -= amount;
+        shares_mint.supply -= amount;
+        store(mint, shares_mint)?;
+        store(user_shares, account)
+    }
+
+    fn balance_of(info: &AccountInfo) -> Result<u64, ProgramError> {
+        Ok(load::<TokenAccount>(info)?.amount)
+    }
+}
+
+fn load<T: Pack + IsInitialized>(info: &AccountInfo) -> Result<T, ProgramError> {
+    T::unpack(&info.try_borrow_data()?)
+}
+
+fn store<T: Pack>(info: &AccountInfo, state: T) -> ProgramResult {
+    T::pack(state, &mut info.try_borrow_mut_data()?)
+}
+
+fn check_program(token_program: &AccountInfo) -> ProgramResult {
+    spl_token::check_program_account(token_program.key)
+}
+
+/// Check that `authority` signed for `account`
+fn check_authority(account: &TokenAccount, authority: &AccountInfo) -> ProgramResult {
+    require_eq!(
+        account.owner,
+        *authority.key,
+        TokenError::OwnerMismatch.into()
+    );
+    require!(authority.is_signer, ProgramError::MissingRequiredSignature);
+    Ok(())
+}
+
+fn transfer(
+    amount: u64,
+    from: &AccountInfo,
+    to: &AccountInfo,
+    mint: &AccountInfo,
+    authority: &AccountInfo,
+) -> ProgramResult {
+    let mut source = load::<TokenAccount>(from)?;
+    check_authority(&source, authority)?;
+    require_eq!(source.mint, *mint.key, TokenError::MintMismatch.into());
+    require!(
+        source.amount >= amount,
+        TokenError::InsufficientFunds.into()
+    );
+    if from.key == to.key {
+        return Ok(());
+    }
+
+    let mut destination = load::<TokenAccount>(to)?;
+    require_eq!(destination.mint, *mint.key, TokenError::MintMismatch.into());
+    source.amount -= amount;
+    destination.amount = destination
+        .amount
+        .checked_add(amount)
+        .ok_or(TokenError::Overflow)?;
+    store(from, source)?;
+    store(to, destination)
+}
+
+/// `info` signed by the vault program, as `invoke_signed` does for a PDA
+fn signed_by_program<'info>(
+    info: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+) -> Result<AccountInfo<'info>, ProgramError> {
+    let pda = Pubkey::create_program_address(signer_seeds, &crate::ID)?;
+    require_eq!(pda, *info.key, ProgramError::MissingRequiredSignature);
+    let mut info = info.clone();
+    info.is_signer = true;
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state::VaultBuilder, test_support::VaultFixture, vault_assets_account_seeds_with_bump,
+        vault_shares_mint_seeds_with_bump,
+    };
+
+    use super::*;
+
+    /// A user holding 100 assets and 10 shares of an empty vault
+    fn setup() -> VaultFixture {
+        let mut setup = VaultFixture::unique(VaultBuilder::new());
+        setup.bank.mint_to(&setup.user.assets_account, 100);
+        setup.bank.mint_to(&setup.user.shares_account, 10);
+        setup
+    }
+
+    /// `keys` as writable accounts, signed by `signer`
+    fn metas(keys: &[Pubkey], signer: &Pubkey) -> Vec<AccountMeta> {
+        keys.iter()
+            .map(|key| AccountMeta::new(*key, key == signer))
+            .collect()
+    }
+
+    fn transfer_in(
+        setup: &mut VaultFixture,
+        amount: u64,
+        mint: Pubkey,
+        signer: Pubkey,
+    ) -> ProgramResult {
+        let (vault, user) = (setup.vault, setup.user);
+        let keys = [
+            vault.vault_assets_account,
+            user.assets_account,
+            mint,
+            user.authority,
+            spl_token::id(),
+        ];
+        let infos = setup.bank.infos(&metas(&keys, &signer));
+        TokenBank::transfer_in(
+            amount, &infos[0], &infos[1], &infos[2], &infos[3], &infos[4],
+        )
+    }
+
+    fn transfer_out(
+        setup: &mut VaultFixture,
+        amount: u64,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let (vault, user) = (setup.vault, setup.user);
+        let keys = [
+            vault.vault_assets_account,
+            user.assets_account,
+            vault.assets_mint,
+            spl_token::id(),
+        ];
+        let infos = setup.bank.infos(&metas(&keys, &Pubkey::default()));
+        TokenBank::transfer_out(
+            amount,
+            &infos[0],
+            &infos[1],
+            &infos[2],
+            &infos[3],
+            signer_seeds,
+        )
+    }
+
+    fn mint(
+        setup: &mut VaultFixture,
+        amount: u64,
+        account: Pubkey,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let keys = [account, setup.vault.shares_mint, spl_token::id()];
+        let infos = setup.bank.infos(&metas(&keys, &Pubkey::default()));
+        <TokenBank as TokenBackend>::mint(amount, &infos[0], &infos[1], &infos[2], signer_seeds)
+    }
+
+    fn burn(setup: &mut VaultFixture, amount: u64, signer: Pubkey) -> ProgramResult {
+        let (vault, user) = (setup.vault, setup.user);
+        let keys = [
+            user.shares_account,
+            vault.shares_mint,
+            user.authority,
+            spl_token::id(),
+        ];
+        let infos = setup.bank.infos(&metas(&keys, &signer));
+        TokenBank::burn(amount, &infos[0], &infos[1], &infos[2], &infos[3])
+    }
+
+    #[test]
+    fn test_transfer_in() {
+        let mut setup = setup();
+        let (vault, user) = (setup.vault, setup.user);
+
+        transfer_in(&mut setup, 40, vault.assets_mint, user.authority).unwrap();
+        assert_eq!(setup.bank.balance(&user.assets_account), 60);
+        assert_eq!(setup.bank.balance(&vault.vault_assets_account), 40);
+    }
+
+    #[test]
+    fn test_transfer_in_errors() {
+        let mut setup = setup();
+        let (vault, user) = (setup.vault, setup.user);
+
+        assert_eq!(
+            transfer_in(&mut setup, 40, vault.assets_mint, Pubkey::default()),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(
+            transfer_in(&mut setup, 40, vault.shares_mint, user.authority),
+            Err(TokenError::MintMismatch.into())
+        );
+        assert_eq!(
+            transfer_in(&mut setup, 101, vault.assets_mint, user.authority),
+            Err(TokenError::InsufficientFunds.into())
+        );
+        assert_eq!(setup.bank.balance(&user.assets_account), 100);
+        assert_eq!(setup.bank.balance(&vault.vault_assets_account), 0);
+    }
+
+    #[test]
+    fn test_transfer_out() {
+        let mut setup = setup();
+        let (vault_pk, vault, user) = (setup.vault_pk, setup.vault, setup.user);
+        setup.bank.mint_to(&vault.vault_assets_account, 50);
+        let bump = vault.vault_assets_account_bump;
+
+        transfer_out(
+            &mut setup,
+            20,
+            vault_assets_account_seeds_with_bump!(vault_pk, bump),
+        )
+        .unwrap();
+        assert_eq!(setup.bank.balance(&vault.vault_assets_account), 30);
+        assert_eq!(setup.bank.balance(&user.assets_account), 120);
+    }
+
+    #[test]
+    fn test_transfer_out_errors() {
+        let mut setup = setup();
+        let (vault_pk, vault) = (setup.vault_pk, setup.vault);
+        setup.bank.mint_to(&vault.vault_assets_account, 50);
+        let (assets_bump, shares_bump) = (
+            vault.vault_assets_account_bump,
+            vault.vault_shares_mint_bump,
+        );
+
+        // -- seeds of another PDA of the vault
+        assert_eq!(
+            transfer_out(
+                &mut setup,
+                20,
+                vault_shares_mint_seeds_with_bump!(vault_pk, shares_bump)
+            ),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(
+            transfer_out(
+                &mut setup,
+                51,
+                vault_assets_account_seeds_with_bump!(vault_pk, assets_bump)
+            ),
+            Err(TokenError::InsufficientFunds.into())
+        );
+        assert_eq!(setup.bank.balance(&vault.vault_assets_account), 50);
+    }
+
+    #[test]
+    fn test_mint() {
+        let mut setup = setup();
+        let (vault_pk, vault, user) = (setup.vault_pk, setup.vault, setup.user);
+        let bump = vault.vault_shares_mint_bump;
+
+        mint(
+            &mut setup,
+            5,
+            user.shares_account,
+            vault_shares_mint_seeds_with_bump!(vault_pk, bump),
+        )
+        .unwrap();
+        assert_eq!(setup.bank.balance(&user.shares_account), 15);
+        assert_eq!(setup.bank.supply(&vault.shares_mint), 15);
+    }
+
+    #[test]
+    fn test_mint_errors() {
+        let mut setup = setup();
+        let (vault_pk, vault, user) = (setup.vault_pk, setup.vault, setup.user);
+        let (assets_bump, shares_bump) = (
+            vault.vault_assets_account_bump,
+            vault.vault_shares_mint_bump,
+        );
+
+        assert_eq!(
+            mint(
+                &mut setup,
+                5,
+                user.shares_account,
+                vault_assets_account_seeds_with_bump!(vault_pk, assets_bump),
+            ),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(
+            mint(
+                &mut setup,
+                5,
+                user.assets_account,
+                vault_shares_mint_seeds_with_bump!(vault_pk, shares_bump),
+            ),
+            Err(TokenError::MintMismatch.into())
+        );
+        assert_eq!(
+            mint(
+                &mut setup,
+                u64::MAX,
+                user.shares_account,
+                vault_shares_mint_seeds_with_bump!(vault_pk, shares_bump),
+            ),
+            Err(TokenError::Overflow.into())
+        );
+        assert_eq!(setup.bank.supply(&vault.shares_mint), 10);
+    }
+
+    #[test]
+    fn test_burn() {
+        let mut setup = setup();
+        let (vault, user) = (setup.vault, setup.user);
+
+        burn(&mut setup, 4, user.authority).unwrap();
+        assert_eq!(setup.bank.balance(&user.shares_account), 6);
+        assert_eq!(setup.bank.supply(&vault.shares_mint), 6);
+    }
+
+    #[test]
+    fn test_burn_errors() {
+        let mut setup = setup();
+        let (vault, user) = (setup.vault, setup.user);
+
+        assert_eq!(
+            burn(&mut setup, 4, Pubkey::default()),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(
+            burn(&mut setup, 11, user.authority),
+            Err(TokenError::InsufficientFunds.into())
+        );
+        assert_eq!(setup.bank.supply(&vault.shares_mint), 10);
+    }
+
+    #[test]
+    fn test_wrong_token_program() {
+        let mut setup = setup();
+        let (vault, user) = (setup.vault, setup.user);
+        let keys = [
+            vault.vault_assets_account,
+            user.assets_account,
+            vault.assets_mint,
+            user.authority,
+            setup.vault_pk,
+        ];
+        let infos = setup.bank.infos(&metas(&keys, &user.authority));
+
+        assert_eq!(
+            TokenBank::transfer_in(1, &infos[0], &infos[1], &infos[2], &infos[3], &infos[4]),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+}
@@ -1,3 +1,5 @@
+/// On failure, `require*` macros log the description, the operands and the
+/// error before returning the error
 #[cfg(not(feature = "certora"))]
 mod inner {
     #[rustfmt::skip]
@@ -6,7 +8,21 @@ mod inner {
             #[macro_export]
             macro_rules! $name {
                     ($lhs: expr, $rhs: expr, $err: expr $dollar(, $desc: literal)? ) => {{
-                        if $lhs $pred $rhs { } else { return Err($err); }
+                        match (&$lhs, &$rhs) {
+                            (lhs, rhs) => {
+                                if *lhs $pred *rhs { } else {
+                                    let err = $err;
+                                    ::solana_program::msg!(
+                                        concat!(
+                                            "guard failed: ", $dollar($desc, ": ",)?
+                                            "{:?} ", stringify!($pred), " {:?} ({:?})"
+                                        ),
+                                        lhs, rhs, err
+                                    );
+                                    return Err(err);
+                                }
+                            }
+                        }
                     }};
                 }
             pub use $name;
@@ -18,7 +34,17 @@ mod inner {
         ($cond: expr, $err: expr $(, $desc:literal)? ) => {{
             if $cond {
             } else {
-                return Err($err);
+                let err = $err;
+                ::solana_program::msg!(
+                    concat!(
+                        "guard failed: ",
+                        $($desc, ": ",)?
+                        stringify!($cond),
+                        " ({:?})"
+                    ),
+                    err
+                );
+                return Err(err);
             }
         }};
     }
@@ -26,6 +52,8 @@ mod inner {
     pub(crate) use require;
 }
 
+/// On failure, `require*` macros record the description and the error code
+/// in the counterexample before panicking
 #[cfg(feature = "certora")]
 mod inner {
     #[rustfmt::skip]
@@ -34,7 +62,17 @@ mod inner {
             #[macro_export]
             macro_rules! $name {
                     ($lhs: expr, $rhs: expr, $err: expr $dollar(, $desc: literal)? ) => {{
-                        if $lhs $pred $rhs { } else { panic!(); }
+                        if $lhs $pred $rhs { } else {
+                            let err = $err;
+                            // -- the return type of the caller infers the type of `err`
+                            if false { return Err(err); }
+                            let error_code = $crate::utils::guards::error_code(err);
+                            ::cvlr::clog!(error_code => concat!(
+                                "guard failed: ", $dollar($desc, ": ",)?
+                                stringify!($lhs), " ", stringify!($pred), " ", stringify!($rhs)
+                            ));
+                            panic!();
+                        }
                     }};
                 }
             pub use $name;
@@ -46,6 +84,17 @@ mod inner {
         ($cond: expr, $err: expr $(, $desc:literal)? ) => {{
             if $cond {
             } else {
+                let err = $err;
+                // -- the return type of the caller infers the type of `err`
+                if false {
+                    return Err(err);
+                }
+                let error_code = $crate::utils::guards::error_code(err);
+                ::cvlr::clog!(error_code => concat!(
+                    "guard failed: ",
+                    $($desc, ": ",)?
+                    stringify!($cond)
+                ));
                 panic!();
             }
         }};
@@ -54,6 +103,13 @@ mod inner {
     pub(crate) use require;
 }
 
+/// Code of the error of a failed guard, as returned to the runtime
+#[cfg(feature = "certora")]
+#[doc(hidden)]
+pub fn error_code<E: Into<solana_program::program_error::ProgramError>>(err: E) -> u64 {
+    u64::from(err.into())
+}
+
 pub(crate) use inner::*;
 impl_bin_require!(require_gt, >, $);
 impl_bin_require!(require_ge, >=, $);
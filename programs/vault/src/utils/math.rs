@@ -3,24 +3,31 @@ use crate::{
     utils::guards::require_le,
 };
 
+/// Rounding direction of an integer division
+///
+/// Which direction each vault operation uses is fixed by
+/// `operations::rounding_policy`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards zero
+    Down,
+    /// Away from zero
+    Up,
+}
+
 pub mod math_native {
     use super::*;
 
-    pub fn mul_div_floor(a: u64, b: u64, c: u64) -> VaultResult<u64> {
-        (a as u128)
+    /// `a * b / c` rounded in the direction of `rounding`
+    pub fn mul_div(a: u64, b: u64, c: u64, rounding: Rounding) -> VaultResult<u64> {
+        let prod = (a as u128)
             .checked_mul(b as u128)
-            .ok_or(VaultError::MathOverflow)?
-            .checked_div(c as u128)
-            .ok_or(VaultError::MathOverflow)?
-            .try_into()
-            .map_err(|_| VaultError::MathOverflow)
-    }
-
-    pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> VaultResult<u64> {
-        (a as u128)
-            .checked_mul(b as u128)
-            .ok_or(VaultError::MathOverflow)?
-            .div_ceil(c as u128)
+            .ok_or(VaultError::MathOverflow)?;
+        let res = match rounding {
+            Rounding::Down => prod.checked_div(c as u128),
+            Rounding::Up => (c != 0).then(|| prod.div_ceil(c as u128)),
+        };
+        res.ok_or(VaultError::MathOverflow)?
             .try_into()
             .map_err(|_| VaultError::MathOverflow)
     }
@@ -31,25 +38,16 @@ pub mod math_certora {
     use super::*;
     use cvlr::mathint::NativeInt;
 
-    pub fn mul_div_floor(a: u64, b: u64, c: u64) -> VaultResult<u64> {
+    /// `a * b / c` rounded in the direction of `rounding`
+    pub fn mul_div(a: u64, b: u64, c: u64, rounding: Rounding) -> VaultResult<u64> {
         let a = NativeInt::from(a);
         let b = NativeInt::from(b);
         let c = NativeInt::from(c);
 
-        let res = a.muldiv(b, c);
-
-        if res.is_u64() {
-            Ok(u64::from(res))
-        } else {
-            Err(VaultError::MathOverflow)
-        }
-    }
-
-    pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> VaultResult<u64> {
-        let a = NativeInt::from(a);
-        let b = NativeInt::from(b);
-        let c = NativeInt::from(c);
-        let res = a.muldiv_ceil(b, c);
+        let res = match rounding {
+            Rounding::Down => a.muldiv(b, c),
+            Rounding::Up => a.muldiv_ceil(b, c),
+        };
 
         if res.is_u64() {
             Ok(u64::from(res))
@@ -70,12 +68,14 @@ pub struct GrossAmount {
 }
 
 const ONE_IN_BPS: u64 = 10_000u64;
+/// Largest fee accepted by `FeeBps`, all of the amount
+pub const MAX_FEE_BPS: u64 = ONE_IN_BPS;
 pub struct FeeBps(u64);
 
 impl TryFrom<u64> for FeeBps {
     type Error = VaultError;
     fn try_from(value: u64) -> Result<Self, Self::Error> {
-        require_le!(value, ONE_IN_BPS, VaultError::GuardFail);
+        require_le!(value, ONE_IN_BPS, VaultError::FeeOutOfRange);
         Ok(FeeBps(value))
     }
 }
@@ -85,14 +85,9 @@ impl FeeBps {
         self.0 == 0
     }
 
-    pub fn apply(&self, amt: u64) -> VaultResult<GrossAmount> {
-        let fee = mul_div_floor(amt, self.0, ONE_IN_BPS)?;
-        let net_amount = amt.checked_sub(fee).ok_or(VaultError::MathOverflow)?;
-        Ok(GrossAmount { net_amount, fee })
-    }
-
-    pub fn apply_ceil(&self, amt: u64) -> VaultResult<GrossAmount> {
-        let fee = mul_div_ceil(amt, self.0, ONE_IN_BPS)?;
+    /// Split `amt` into fee and net amount, rounding the fee by `rounding`
+    pub fn apply(&self, amt: u64, rounding: Rounding) -> VaultResult<GrossAmount> {
+        let fee = mul_div(amt, self.0, ONE_IN_BPS, rounding)?;
         let net_amount = amt.checked_sub(fee).ok_or(VaultError::MathOverflow)?;
         Ok(GrossAmount { net_amount, fee })
     }